@@ -0,0 +1,198 @@
+//! ANSI 终端渲染后端：把元素树转成带转义序列的纯文本，而不是位图，让 DSL 也能在
+//! TUI 场景下直接渲染
+
+use crate::layout::*;
+
+/// ANSI 颜色分辨率：`Truecolor` 发 24 位真彩转义，`Palette256` 量化到最接近的
+/// xterm-256 调色板索引（不是所有终端都支持 truecolor）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnsiColorMode {
+    Truecolor,
+    Palette256,
+}
+
+/// 前景色还是背景色：决定 SGR 参数里用 `38`/`48` 系列前缀
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AnsiGround {
+    Foreground,
+    Background,
+}
+
+impl AnsiGround {
+    fn sgr_prefix(self) -> u8 {
+        match self {
+            AnsiGround::Foreground => 38,
+            AnsiGround::Background => 48,
+        }
+    }
+}
+
+/// 渲染完一段文字后恢复终端默认属性
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+impl Color {
+    /// 映射到最接近的 xterm-256 调色板索引：按 6x6x6 颜色立方体量化一个候选
+    /// （`16 + 36*round(r/51) + 6*round(g/51) + round(b/51)`），再按亮度算一个
+    /// 232-255 灰阶候选（`232 + round((luma-8)/10)`），两者还原回 RGB 后按欧氏距离
+    /// 取更接近原色的那个——纯色用立方体更准，近灰色用灰阶渐变更准
+    pub fn to_ansi256(&self) -> u8 {
+        let round_channel = |c: u8| (c as f32 / 51.0).round().clamp(0.0, 5.0) as i32;
+        let cube_index =
+            16 + 36 * round_channel(self.r) + 6 * round_channel(self.g) + round_channel(self.b);
+        let cube_index = cube_index.clamp(16, 231) as u8;
+
+        let luma = self.r as f32 * 0.299 + self.g as f32 * 0.587 + self.b as f32 * 0.114;
+        let gray_index = (232.0 + ((luma - 8.0) / 10.0).round()).clamp(232.0, 255.0) as u8;
+
+        let distance = |(r, g, b): (u8, u8, u8)| -> f32 {
+            let dr = self.r as f32 - r as f32;
+            let dg = self.g as f32 - g as f32;
+            let db = self.b as f32 - b as f32;
+            dr * dr + dg * dg + db * db
+        };
+
+        if distance(ansi256_cube_rgb(cube_index)) <= distance(ansi256_gray_rgb(gray_index)) {
+            cube_index
+        } else {
+            gray_index
+        }
+    }
+}
+
+/// 还原 6x6x6 颜色立方体某个索引对应的 RGB（xterm 的立方体台阶不是等距的 0/51/102/.../255，
+/// 而是 0/95/135/175/215/255），用于和灰阶候选比较谁离原色更近
+fn ansi256_cube_rgb(index: u8) -> (u8, u8, u8) {
+    const STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let i = index as i32 - 16;
+    let r = STEPS[(i / 36) as usize];
+    let g = STEPS[((i / 6) % 6) as usize];
+    let b = STEPS[(i % 6) as usize];
+    (r, g, b)
+}
+
+/// 还原 232-255 灰阶索引对应的 RGB
+fn ansi256_gray_rgb(index: u8) -> (u8, u8, u8) {
+    let level = (8 + (index as i32 - 232) * 10).clamp(0, 255) as u8;
+    (level, level, level)
+}
+
+/// 把 [`TextStyle`] 里已设置为 `true` 的修饰符开关换成对应的 SGR 参数码，未设置
+/// （`None`）或显式 `false` 的开关都不发
+fn style_sgr_codes(style: &TextStyle) -> Vec<u8> {
+    let mut codes = Vec::new();
+    if style.bold == Some(true) {
+        codes.push(1);
+    }
+    if style.dim == Some(true) {
+        codes.push(2);
+    }
+    if style.italic == Some(true) {
+        codes.push(3);
+    }
+    if style.underline == Some(true) {
+        codes.push(4);
+    }
+    if style.blink == Some(true) {
+        codes.push(5);
+    }
+    if style.reverse == Some(true) {
+        codes.push(7);
+    }
+    if style.strikethrough == Some(true) {
+        codes.push(9);
+    }
+    codes
+}
+
+/// 把一个颜色换成对应 ground（前景/背景）的 SGR 参数码；`a == 0`（默认的全透明色）
+/// 不发任何颜色指令，让终端保留当前前景/背景，而不是强行涂成黑色
+fn color_sgr_codes(color: &Color, ground: AnsiGround, mode: AnsiColorMode) -> Option<Vec<u8>> {
+    if color.a == 0 {
+        return None;
+    }
+    let prefix = ground.sgr_prefix();
+    Some(match mode {
+        AnsiColorMode::Truecolor => vec![prefix, 2, color.r, color.g, color.b],
+        AnsiColorMode::Palette256 => vec![prefix, 5, color.to_ansi256()],
+    })
+}
+
+/// 把修饰符开关和前景/背景色合并成一条 SGR 转义序列（`\x1b[...m`），而不是每个属性
+/// 各发一条——终端约定就是用分号把所有参数塞进同一个 `m` 结尾的序列里
+pub fn sgr_sequence(
+    style: &TextStyle,
+    foreground: Option<&Color>,
+    background: Option<&Color>,
+    mode: AnsiColorMode,
+) -> String {
+    let mut codes = style_sgr_codes(style);
+    if let Some(color) = foreground {
+        if let Some(mut c) = color_sgr_codes(color, AnsiGround::Foreground, mode) {
+            codes.append(&mut c);
+        }
+    }
+    if let Some(color) = background {
+        if let Some(mut c) = color_sgr_codes(color, AnsiGround::Background, mode) {
+            codes.append(&mut c);
+        }
+    }
+
+    if codes.is_empty() {
+        return String::new();
+    }
+
+    let params = codes
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(";");
+    format!("\x1b[{}m", params)
+}
+
+/// 把元素树按深度优先顺序渲染成一段带 ANSI 转义序列的文本：每个文本元素输出一行，
+/// 样式由它自己的 [`TextProperties::style`] 和 `color` 决定；容器/堆叠/网格只是
+/// 继续往下递归，不做几何排版——这是给 TUI 场景用的简化渲染路径，不追求和位图
+/// 渲染器一样的像素级布局
+pub struct AnsiRenderer {
+    pub mode: AnsiColorMode,
+}
+
+impl AnsiRenderer {
+    pub fn new(mode: AnsiColorMode) -> Self {
+        Self { mode }
+    }
+
+    pub fn render_layout(&self, layout: &Layout) -> String {
+        let mut out = String::new();
+        for element in &layout.elements {
+            self.render_element(element, &mut out);
+        }
+        out
+    }
+
+    fn render_element(&self, element: &Element, out: &mut String) {
+        match element {
+            Element::Text {
+                content, properties, ..
+            } => {
+                let sgr = sgr_sequence(&properties.style, Some(&properties.color), None, self.mode);
+                out.push_str(&sgr);
+                out.push_str(content);
+                if !sgr.is_empty() {
+                    out.push_str(ANSI_RESET);
+                }
+                out.push('\n');
+            }
+            Element::Container { children, .. }
+            | Element::VStack { children, .. }
+            | Element::HStack { children, .. }
+            | Element::ZStack { children, .. }
+            | Element::Grid { children, .. } => {
+                for child in children {
+                    self.render_element(child, out);
+                }
+            }
+            Element::Image { .. } | Element::Spacer { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! 渲染快照的落盘与重放
+//!
+//! `test_debug_mode` 这类例子只能靠肉眼比对输出的 PNG，没法做回归测试。这里给
+//! `AutoLayoutEngine` 加一套 capture/replay：`capture` 渲染一次布局，把解析后的
+//! `Layout`、引擎配置（目前只有 debug 开关）和渲染过程中实际加载的每一张图片
+//! （按内容落盘，不依赖原始路径）一起写进一个自包含目录；`replay` 从这个目录
+//! 重建引擎和图片缓存，在任意机器上确定性地重新渲染，方便测试对照一份参考 PNG
+//! 做字节级比较。
+
+use crate::layout::Layout;
+use crate::renderer::RenderError;
+use crate::{AutoLayoutEngine, AutoLayoutError};
+use image::{ImageOutputFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON serialize/deserialize error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// 快照里记录的引擎配置；目前只有 debug 开关，后续新增的引擎级配置都应该加在这里
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct CapturedEngineConfig {
+    pub debug: bool,
+}
+
+/// 快照清单：记录渲染时引擎配置，以及每个图片来源字符串到 capture 目录内
+/// 按内容哈希命名的副本的映射
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct CaptureManifest {
+    pub engine_config: CapturedEngineConfig,
+    pub images: HashMap<String, String>,
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+impl AutoLayoutEngine {
+    /// 渲染 `layout`，并把结果依赖的一切 —— 布局本身、引擎配置、渲染期间加载的
+    /// 每一张图片 —— 落盘到 `dir`，产出一个不依赖原始文件系统路径的自包含快照
+    pub fn capture<P: AsRef<Path>>(
+        &mut self,
+        layout: &Layout,
+        dir: P,
+    ) -> Result<RgbaImage, AutoLayoutError> {
+        let dir = dir.as_ref();
+        let images_dir = dir.join("images");
+        fs::create_dir_all(&images_dir).map_err(CaptureError::from)?;
+
+        let (rendered, _warnings) = self.render_layout(layout)?;
+
+        let mut manifest = CaptureManifest {
+            engine_config: CapturedEngineConfig { debug: self.debug },
+            images: HashMap::new(),
+        };
+
+        for (source, loaded) in self.renderer.loaded_images() {
+            let mut bytes: Vec<u8> = Vec::new();
+            loaded
+                .write_to(&mut std::io::Cursor::new(&mut bytes), ImageOutputFormat::Png)
+                .map_err(|e| AutoLayoutError::RenderError(RenderError::ImageError(e)))?;
+            let file_name = format!("{}.png", hash_bytes(&bytes));
+            fs::write(images_dir.join(&file_name), &bytes).map_err(CaptureError::from)?;
+            manifest
+                .images
+                .insert(source.clone(), format!("images/{}", file_name));
+        }
+
+        let layout_json = serde_json::to_string_pretty(layout).map_err(CaptureError::from)?;
+        fs::write(dir.join("layout.json"), layout_json).map_err(CaptureError::from)?;
+
+        let manifest_json = serde_json::to_string_pretty(&manifest).map_err(CaptureError::from)?;
+        fs::write(dir.join("manifest.json"), manifest_json).map_err(CaptureError::from)?;
+
+        Ok(rendered)
+    }
+
+    /// 从 [`AutoLayoutEngine::capture`] 产出的快照重建引擎（含 debug 开关）和图片缓存，
+    /// 用捕获的副本而不是原始路径重新渲染，结果应当与捕获时逐字节一致
+    pub fn replay<P: AsRef<Path>>(dir: P) -> Result<RgbaImage, AutoLayoutError> {
+        let dir = dir.as_ref();
+
+        let layout_json = fs::read_to_string(dir.join("layout.json")).map_err(CaptureError::from)?;
+        let layout: Layout = serde_json::from_str(&layout_json).map_err(CaptureError::from)?;
+
+        let manifest_json =
+            fs::read_to_string(dir.join("manifest.json")).map_err(CaptureError::from)?;
+        let manifest: CaptureManifest =
+            serde_json::from_str(&manifest_json).map_err(CaptureError::from)?;
+
+        let mut engine = AutoLayoutEngine::new();
+        engine.set_debug(manifest.engine_config.debug);
+
+        for (source, relative_path) in &manifest.images {
+            let bytes = fs::read(dir.join(relative_path)).map_err(CaptureError::from)?;
+            let image = image::load_from_memory(&bytes)
+                .map_err(|e| AutoLayoutError::RenderError(RenderError::ImageError(e)))?;
+            engine.renderer.preload_image(source, image);
+        }
+
+        let (rendered, _warnings) = engine.render_layout(&layout)?;
+        Ok(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DslParser;
+
+    fn sample_layout() -> Layout {
+        let json = r#"{
+            "canvas": { "width": 100, "height": 100, "background": "white" },
+            "elements": [
+                {
+                    "type": "text",
+                    "id": "title",
+                    "content": "hi",
+                    "properties": { "font_size": 16, "color": "black" },
+                    "constraints": [
+                        { "type": "centerX", "constant": 0 },
+                        { "type": "centerY", "constant": 0 }
+                    ]
+                }
+            ]
+        }"#;
+        DslParser::parse_json(json).unwrap()
+    }
+
+    #[test]
+    fn test_capture_replay_round_trip() {
+        let dir = std::env::temp_dir().join(format!("auto_layout_capture_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let layout = sample_layout();
+        let mut engine = AutoLayoutEngine::new();
+        engine.set_debug(true);
+        let captured = engine.capture(&layout, &dir).unwrap();
+
+        let replayed = AutoLayoutEngine::replay(&dir).unwrap();
+        assert_eq!(captured.dimensions(), replayed.dimensions());
+        assert_eq!(captured.into_raw(), replayed.into_raw());
+
+        let manifest_json = fs::read_to_string(dir.join("manifest.json")).unwrap();
+        let manifest: CaptureManifest = serde_json::from_str(&manifest_json).unwrap();
+        assert!(manifest.engine_config.debug);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
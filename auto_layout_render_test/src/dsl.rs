@@ -2,6 +2,7 @@
 
 use crate::layout::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use thiserror::Error;
@@ -14,6 +15,8 @@ pub enum DslError {
     JsonError(#[from] serde_json::Error),
     #[error("YAML parse error: {0}")]
     YamlError(#[from] serde_yaml::Error),
+    #[error("TOML parse error: {0}")]
+    TomlError(#[from] toml::de::Error),
     #[error("Validation error: {0}")]
     ValidationError(String),
 }
@@ -102,6 +105,13 @@ pub enum DslElement {
         constraints: Vec<DslConstraint>,
         children: Vec<DslElement>,
     },
+    #[serde(rename = "grid")]
+    Grid {
+        id: String,
+        properties: DslGridProperties,
+        constraints: Vec<DslConstraint>,
+        children: Vec<DslElement>,
+    },
     #[serde(rename = "spacer")]
     Spacer {
         id: String,
@@ -109,23 +119,85 @@ pub enum DslElement {
     },
 }
 
+/// DSL滤镜描述，对应内部的 [`crate::layout::Filter`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DslFilter {
+    #[serde(rename = "gaussianBlur")]
+    GaussianBlur { std_deviation: f32 },
+    #[serde(rename = "dropShadow")]
+    DropShadow {
+        dx: f32,
+        dy: f32,
+        std_deviation: f32,
+        color: DslColor,
+    },
+    #[serde(rename = "colorMatrix")]
+    ColorMatrix { values: [f32; 20] },
+}
+
 /// DSL文本属性
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DslTextProperties {
-    #[serde(default = "default_font_family")]
-    pub font_family: String,
-    #[serde(default = "default_font_size")]
-    pub font_size: f32,
+    /// 不写时留 `None`，交给 [`DslParser::inherit_defaults`] 沿元素树从祖先继承，
+    /// 树上从根到这个元素都没人设置过才会落到 [`default_font_family`]
+    #[serde(default)]
+    pub font_family: Option<String>,
+    #[serde(default)]
+    pub font_size: Option<f32>,
     #[serde(default)]
     pub font_weight: FontWeight,
     #[serde(default)]
+    pub font_style: FontStyle,
+    #[serde(default)]
     pub color: DslColor,
     #[serde(default)]
     pub alignment: TextAlignment,
-    #[serde(default = "default_line_height")]
-    pub line_height: f32,
-    #[serde(default = "default_letter_spacing")]
-    pub letter_spacing: f32,
+    #[serde(default)]
+    pub line_height: Option<f32>,
+    #[serde(default)]
+    pub letter_spacing: Option<f32>,
+    #[serde(default)]
+    pub filters: Vec<DslFilter>,
+    #[serde(default)]
+    pub style: DslStyle,
+    #[serde(default)]
+    pub margin: DslMargin,
+}
+
+/// DSL文本装饰样式：每个开关都用 `Option<bool>`，不写的字段保持 `None`（未设置），
+/// 交给 [`DslParser::convert_style`] 转成运行时 [`TextStyle`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DslStyle {
+    #[serde(default)]
+    pub bold: Option<bool>,
+    #[serde(default)]
+    pub italic: Option<bool>,
+    #[serde(default)]
+    pub underline: Option<bool>,
+    #[serde(default)]
+    pub strikethrough: Option<bool>,
+    #[serde(default)]
+    pub dim: Option<bool>,
+    #[serde(default)]
+    pub reverse: Option<bool>,
+    #[serde(default)]
+    pub blink: Option<bool>,
+}
+
+/// DSL投影样式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DslShadowStyle {
+    #[serde(default)]
+    pub color: DslColor,
+    #[serde(default = "default_shadow_blur_radius")]
+    pub blur_radius: f32,
+    #[serde(default)]
+    pub offset_x: f32,
+    #[serde(default = "default_shadow_offset_y")]
+    pub offset_y: f32,
+    #[serde(default = "default_shadow_opacity")]
+    pub opacity: f32,
 }
 
 /// DSL图片属性
@@ -133,11 +205,23 @@ pub struct DslTextProperties {
 pub struct DslImageProperties {
     #[serde(default)]
     pub scale_mode: ScaleMode,
-    #[serde(default = "default_opacity")]
-    pub opacity: f32,
+    /// 不写时留 `None`，交给 [`DslParser::inherit_defaults`] 沿元素树从祖先继承，
+    /// 树上从根到这个元素都没人设置过才会落到 [`default_opacity`]
+    #[serde(default)]
+    pub opacity: Option<f32>,
     pub tint_color: Option<DslColor>,
     #[serde(default)]
     pub corner_radius: f32,
+    #[serde(default)]
+    pub shadow: Option<DslShadowStyle>,
+    #[serde(default)]
+    pub filters: Vec<DslFilter>,
+    /// SVG `preserveAspectRatio` 原始字符串（如 `"xMidYMid meet"`），只对 `.svg`
+    /// 来源的图片有意义，解析成 [`PreserveAspectRatio`]
+    #[serde(default)]
+    pub preserve_aspect_ratio: Option<String>,
+    #[serde(default)]
+    pub margin: DslMargin,
 }
 
 /// DSL容器属性
@@ -146,13 +230,17 @@ pub struct DslContainerProperties {
     #[serde(default)]
     pub background: DslColor,
     #[serde(default)]
-    pub border_color: DslColor,
+    pub border: DslBorder,
     #[serde(default)]
-    pub border_width: f32,
-    #[serde(default)]
-    pub corner_radius: f32,
+    pub corners: DslCorners,
     #[serde(default)]
     pub padding: DslPadding,
+    #[serde(default)]
+    pub margin: DslMargin,
+    #[serde(default)]
+    pub shadow: Option<DslShadowStyle>,
+    #[serde(default)]
+    pub filters: Vec<DslFilter>,
 }
 
 /// DSL堆叠属性
@@ -166,6 +254,29 @@ pub struct DslStackProperties {
     pub spacing: f32,
     #[serde(default)]
     pub padding: DslPadding,
+    #[serde(default)]
+    pub margin: DslMargin,
+}
+
+/// DSL网格轨道描述："50%" 是百分比轨道，"2fr" 是按权重分配的弹性轨道，其余数值是固定尺寸
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DslGridTrack {
+    Fixed(f32),
+    Text(String),
+}
+
+/// DSL网格属性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DslGridProperties {
+    pub rows: Vec<DslGridTrack>,
+    pub cols: Vec<DslGridTrack>,
+    #[serde(default)]
+    pub row_spacing: f32,
+    #[serde(default)]
+    pub col_spacing: f32,
+    #[serde(default)]
+    pub margin: DslMargin,
 }
 
 /// DSL内边距
@@ -181,6 +292,61 @@ pub enum DslPadding {
     },
 }
 
+/// DSL外边距：形状和 [`DslPadding`] 完全一样（要么四边统一一个数，要么分别给
+/// 四个方向），只是转换到运行时类型时落在 [`Margin`] 而不是 [`Padding`] 上
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DslMargin {
+    Uniform(f32),
+    Detailed {
+        top: f32,
+        right: f32,
+        bottom: f32,
+        left: f32,
+    },
+}
+
+/// DSL圆角半径：要么四角统一一个数，要么分别指定四个角
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DslCorners {
+    Uniform(f32),
+    Detailed {
+        top_left: f32,
+        top_right: f32,
+        bottom_left: f32,
+        bottom_right: f32,
+    },
+}
+
+/// DSL单条边框描述
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DslBorderSide {
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub color: DslColor,
+    #[serde(default)]
+    pub style: BorderStyle,
+}
+
+/// DSL边框：要么四边共用同一条 [`DslBorderSide`]，要么分别指定四边
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DslBorder {
+    Uniform(DslBorderSide),
+    Sides {
+        #[serde(default)]
+        top: DslBorderSide,
+        #[serde(default)]
+        right: DslBorderSide,
+        #[serde(default)]
+        bottom: DslBorderSide,
+        #[serde(default)]
+        left: DslBorderSide,
+    },
+}
+
 /// DSL约束描述
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -248,6 +414,24 @@ pub enum DslConstraint {
         #[serde(default)]
         priority: Priority,
     },
+    #[serde(rename = "fill")]
+    Fill {
+        #[serde(default = "default_fill_weight")]
+        weight: f32,
+        #[serde(default)]
+        priority: Priority,
+    },
+    #[serde(rename = "gridPosition")]
+    GridPosition {
+        row: u32,
+        col: u32,
+        #[serde(default = "default_span")]
+        row_span: u32,
+        #[serde(default = "default_span")]
+        col_span: u32,
+        #[serde(default)]
+        priority: Priority,
+    },
 
     // 对齐约束
     #[serde(rename = "alignTop")]
@@ -300,26 +484,426 @@ pub struct DslParser;
 impl DslParser {
     /// 从JSON字符串解析布局
     pub fn parse_json(json: &str) -> Result<Layout, DslError> {
-        let dsl_layout: DslLayout = serde_json::from_str(json)?;
+        Self::parse_json_with_theme(json, None)
+    }
+
+    /// 从JSON字符串解析布局，并选用顶层 `themes` 字典中名为 `theme_name` 的具名主题
+    /// （模板只写了单个 `theme` 而非 `themes` 时，`theme_name` 被忽略）
+    pub fn parse_json_with_theme(json: &str, theme_name: Option<&str>) -> Result<Layout, DslError> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        Self::resolve_theme(&mut value, theme_name)?;
+        let dsl_layout: DslLayout = serde_json::from_value(value)?;
         Self::convert_to_layout(dsl_layout)
     }
 
     /// 从YAML字符串解析布局
     pub fn parse_yaml(yaml: &str) -> Result<Layout, DslError> {
-        let dsl_layout: DslLayout = serde_yaml::from_str(yaml)?;
+        Self::parse_yaml_with_theme(yaml, None)
+    }
+
+    /// 从YAML字符串解析布局，并选用具名主题，语义同 [`Self::parse_json_with_theme`]
+    pub fn parse_yaml_with_theme(yaml: &str, theme_name: Option<&str>) -> Result<Layout, DslError> {
+        // `serde_json::Value` 的 `Deserialize` 实现不关心来源格式，借这一点直接把 YAML
+        // 解析成同一棵 `Value` 树，这样主题/extends 的预处理逻辑对 JSON、YAML 都只写一份
+        let mut value: serde_json::Value = serde_yaml::from_str(yaml)?;
+        Self::resolve_theme(&mut value, theme_name)?;
+        let dsl_layout: DslLayout = serde_json::from_value(value)?;
         Self::convert_to_layout(dsl_layout)
     }
 
+    /// 从TOML字符串解析布局
+    pub fn parse_toml(toml_str: &str) -> Result<Layout, DslError> {
+        Self::parse_toml_with_theme(toml_str, None)
+    }
+
+    /// 从TOML字符串解析布局，并选用具名主题，语义同 [`Self::parse_json_with_theme`]
+    pub fn parse_toml_with_theme(toml_str: &str, theme_name: Option<&str>) -> Result<Layout, DslError> {
+        // 同样借道 `serde_json::Value`：`toml::Value` 实现了 `Serialize`，转换一次
+        // 就能复用 JSON/YAML 共用的主题/extends 预处理逻辑，不用再写一份 TOML 专属版本
+        let toml_value: toml::Value = toml::from_str(toml_str)?;
+        let mut value = serde_json::to_value(toml_value)?;
+        Self::resolve_theme(&mut value, theme_name)?;
+        let dsl_layout: DslLayout = serde_json::from_value(value)?;
+        Self::convert_to_layout(dsl_layout)
+    }
+
+    /// 从顶层取出本次要用的主题 token 字典：模板写了 `themes`（多个具名主题，供
+    /// light/dark/多品牌切换）时，必须通过 `theme_name` 指定用哪一个；只写了单个
+    /// `theme` 时忽略 `theme_name`，直接用它；两者都没写则没有任何 token
+    fn select_theme_tokens(
+        root: &mut serde_json::Value,
+        theme_name: Option<&str>,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, DslError> {
+        let obj = match root.as_object_mut() {
+            Some(obj) => obj,
+            None => return Ok(serde_json::Map::new()),
+        };
+
+        if let Some(serde_json::Value::Object(themes)) = obj.remove("themes") {
+            let name = theme_name.ok_or_else(|| {
+                DslError::ValidationError(format!(
+                    "模板定义了多个具名主题，必须指定使用哪一个（可用: {}）",
+                    themes.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))
+            })?;
+            let selected = themes.get(name).ok_or_else(|| {
+                DslError::ValidationError(format!(
+                    "未知的主题名称: {}（可用: {}）",
+                    name,
+                    themes.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))
+            })?;
+            return Ok(selected.as_object().cloned().unwrap_or_default());
+        }
+
+        match obj.remove("theme") {
+            Some(serde_json::Value::Object(map)) => Ok(map),
+            _ => Ok(serde_json::Map::new()),
+        }
+    }
+
+    /// 在反序列化成强类型 `DslLayout` 之前，原地展开顶层主题字典与元素级 `extends`：
+    /// 1. 主题里登记的具名 token（颜色/字号/字体/间距……）替换所有写成 `"$token"` 的位置，
+    ///    token 之间允许互相引用（`"$b": "$a"`），按需递归解析并缓存。顶层可以写单个
+    ///    `theme` 字典，也可以写多个具名主题的 `themes` 字典并通过 `theme_name` 二选一，
+    ///    这样同一份布局结构可以配出 light/dark 或多品牌的不同输出
+    /// 2. `theme.styles` 下的具名样式块，或 `extends` 指向的另一个元素，其 `properties` 会
+    ///    按 `extends` 列出的顺序合并，后者覆盖前者，元素自己的 `properties` 最后覆盖全部
+    /// 两步都做环检测，检测到循环引用时返回携带完整路径的 `ValidationError`
+    fn resolve_theme(root: &mut serde_json::Value, theme_name: Option<&str>) -> Result<(), DslError> {
+        let theme_raw = Self::select_theme_tokens(root, theme_name)?;
+
+        let mut tokens = serde_json::Map::new();
+        for key in theme_raw.keys().cloned().collect::<Vec<_>>() {
+            if tokens.contains_key(&key) {
+                continue;
+            }
+            let mut visiting = Vec::new();
+            let value = Self::resolve_token(&key, &theme_raw, &mut tokens, &mut visiting)?;
+            tokens.insert(key, value);
+        }
+
+        let styles = match tokens.get("styles") {
+            Some(serde_json::Value::Object(map)) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        let elements_by_id = Self::index_elements_by_id(root);
+
+        if let Some(elements) = root.get_mut("elements").and_then(|v| v.as_array_mut()) {
+            for element in elements.iter_mut() {
+                let mut visiting = Vec::new();
+                Self::resolve_extends(element, &styles, &elements_by_id, &mut visiting)?;
+            }
+            for element in elements.iter_mut() {
+                Self::substitute_tokens(element, &tokens);
+            }
+        }
+
+        Self::inherit_defaults(root);
+
+        Ok(())
+    }
+
+    /// 需要做"祖先→子孙"级联默认值的属性名：字段改成 `Option<T>` 之后，缺省值不再在
+    /// 反序列化时就抢占字段，而是沿元素树从上往下传，元素自己没写就继承最近的祖先，
+    /// 整条链上都没人设置过才会落到最后的硬编码默认值
+    const INHERITABLE_PROPERTY_KEYS: &[&str] =
+        &["font_family", "font_size", "line_height", "letter_spacing", "opacity"];
+
+    /// 从根开始，把 [`Self::INHERITABLE_PROPERTY_KEYS`] 里缺失的属性沿元素树逐级填上
+    fn inherit_defaults(root: &mut serde_json::Value) {
+        let mut inherited = serde_json::Map::new();
+        inherited.insert("font_family".to_string(), serde_json::json!(default_font_family()));
+        inherited.insert("font_size".to_string(), serde_json::json!(default_font_size()));
+        inherited.insert("line_height".to_string(), serde_json::json!(default_line_height()));
+        inherited.insert("letter_spacing".to_string(), serde_json::json!(default_letter_spacing()));
+        inherited.insert("opacity".to_string(), serde_json::json!(default_opacity()));
+
+        if let Some(elements) = root.get_mut("elements").and_then(|v| v.as_array_mut()) {
+            for element in elements.iter_mut() {
+                Self::inherit_defaults_element(element, &inherited);
+            }
+        }
+    }
+
+    fn inherit_defaults_element(
+        element: &mut serde_json::Value,
+        inherited: &serde_json::Map<String, serde_json::Value>,
+    ) {
+        let mut next_inherited = inherited.clone();
+
+        if let Some(props) = element.get_mut("properties").and_then(|v| v.as_object_mut()) {
+            for key in Self::INHERITABLE_PROPERTY_KEYS {
+                match props.get(*key) {
+                    Some(value) if !value.is_null() => {
+                        next_inherited.insert((*key).to_string(), value.clone());
+                    }
+                    _ => {
+                        if let Some(value) = inherited.get(*key) {
+                            props.insert((*key).to_string(), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(children) = element
+            .as_object_mut()
+            .and_then(|obj| obj.get_mut("children"))
+            .and_then(|v| v.as_array_mut())
+        {
+            for child in children.iter_mut() {
+                Self::inherit_defaults_element(child, &next_inherited);
+            }
+        }
+    }
+
+    /// 递归收集整棵元素树里 `id -> 元素原始 Value` 的映射，供 `extends` 按 id 查找兄弟/祖先元素
+    fn index_elements_by_id(root: &serde_json::Value) -> HashMap<String, serde_json::Value> {
+        let mut map = HashMap::new();
+        if let Some(elements) = root.get("elements").and_then(|v| v.as_array()) {
+            for element in elements {
+                Self::collect_elements_by_id(element, &mut map);
+            }
+        }
+        map
+    }
+
+    fn collect_elements_by_id(
+        element: &serde_json::Value,
+        map: &mut HashMap<String, serde_json::Value>,
+    ) {
+        if let Some(obj) = element.as_object() {
+            if let Some(id) = obj.get("id").and_then(|v| v.as_str()) {
+                map.insert(id.to_string(), element.clone());
+            }
+            if let Some(children) = obj.get("children").and_then(|v| v.as_array()) {
+                for child in children {
+                    Self::collect_elements_by_id(child, map);
+                }
+            }
+        }
+    }
+
+    /// 解析单个主题 token，支持 token 间互相引用；`resolved` 既是缓存也是防止重复解析的记忆表，
+    /// `visiting` 是当前递归链上的 token 名，用来探测循环引用
+    fn resolve_token(
+        name: &str,
+        theme_raw: &serde_json::Map<String, serde_json::Value>,
+        resolved: &mut serde_json::Map<String, serde_json::Value>,
+        visiting: &mut Vec<String>,
+    ) -> Result<serde_json::Value, DslError> {
+        if let Some(value) = resolved.get(name) {
+            return Ok(value.clone());
+        }
+        if visiting.contains(&name.to_string()) {
+            let mut path = visiting.clone();
+            path.push(name.to_string());
+            return Err(DslError::ValidationError(format!(
+                "Cycle detected in theme token references: {}",
+                path.join(" -> ")
+            )));
+        }
+        let raw = theme_raw.get(name).cloned().ok_or_else(|| {
+            DslError::ValidationError(format!("Unknown theme token: ${}", name))
+        })?;
+
+        visiting.push(name.to_string());
+        let value = match &raw {
+            serde_json::Value::String(s) => match s.strip_prefix('$') {
+                Some(referenced) => Self::resolve_token(referenced, theme_raw, resolved, visiting)?,
+                None => raw,
+            },
+            _ => raw,
+        };
+        visiting.pop();
+
+        resolved.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+
+    /// 合并 `extends` 指向的样式来源到元素的 `properties` 上；`visiting` 记录当前递归链上
+    /// 经过的元素 id，用来探测 `extends` 之间的循环引用
+    fn resolve_extends(
+        element: &mut serde_json::Value,
+        styles: &serde_json::Map<String, serde_json::Value>,
+        elements_by_id: &HashMap<String, serde_json::Value>,
+        visiting: &mut Vec<String>,
+    ) -> Result<(), DslError> {
+        let extends_value = element
+            .as_object_mut()
+            .and_then(|obj| obj.remove("extends"));
+
+        if let Some(extends_value) = extends_value {
+            let names = Self::extends_names(extends_value);
+            let id = element
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("<anonymous>")
+                .to_string();
+            if visiting.contains(&id) {
+                let mut path = visiting.clone();
+                path.push(id);
+                return Err(DslError::ValidationError(format!(
+                    "Cycle detected in `extends`: {}",
+                    path.join(" -> ")
+                )));
+            }
+            visiting.push(id);
+
+            let mut merged = serde_json::Map::new();
+            for name in &names {
+                let source_props = if let Some(style) = styles.get(name) {
+                    style.as_object().cloned().unwrap_or_default()
+                } else if let Some(source_element) = elements_by_id.get(name) {
+                    let mut resolved_source = source_element.clone();
+                    Self::resolve_extends(&mut resolved_source, styles, elements_by_id, visiting)?;
+                    resolved_source
+                        .get("properties")
+                        .and_then(|v| v.as_object())
+                        .cloned()
+                        .unwrap_or_default()
+                } else {
+                    visiting.pop();
+                    return Err(DslError::ValidationError(format!(
+                        "`extends` target not found: {}",
+                        name
+                    )));
+                };
+                for (k, v) in source_props {
+                    merged.insert(k, v);
+                }
+            }
+            visiting.pop();
+
+            if let Some(serde_json::Value::Object(local_props)) = element.get("properties").cloned() {
+                for (k, v) in local_props {
+                    merged.insert(k, v);
+                }
+            }
+            if let Some(obj) = element.as_object_mut() {
+                obj.insert("properties".to_string(), serde_json::Value::Object(merged));
+            }
+        }
+
+        if let Some(children) = element
+            .as_object_mut()
+            .and_then(|obj| obj.get_mut("children"))
+            .and_then(|v| v.as_array_mut())
+        {
+            for child in children.iter_mut() {
+                Self::resolve_extends(child, styles, elements_by_id, visiting)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `extends` 既可以写成单个字符串，也可以写成字符串数组；统一成数组，顺序就是合并顺序
+    fn extends_names(value: serde_json::Value) -> Vec<String> {
+        match value {
+            serde_json::Value::String(s) => vec![s],
+            serde_json::Value::Array(arr) => arr
+                .into_iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 递归地把值树里形如 `"$name"` 的字符串替换成对应主题 token 的值
+    fn substitute_tokens(
+        value: &mut serde_json::Value,
+        tokens: &serde_json::Map<String, serde_json::Value>,
+    ) {
+        match value {
+            serde_json::Value::String(s) => {
+                if let Some(name) = s.strip_prefix('$') {
+                    if let Some(resolved) = tokens.get(name) {
+                        *value = resolved.clone();
+                    }
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for v in map.values_mut() {
+                    Self::substitute_tokens(v, tokens);
+                }
+            }
+            serde_json::Value::Array(arr) => {
+                for v in arr.iter_mut() {
+                    Self::substitute_tokens(v, tokens);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// 从JSON文件加载布局
     pub fn load_json_file<P: AsRef<Path>>(path: P) -> Result<Layout, DslError> {
+        Self::load_json_file_with_theme(path, None)
+    }
+
+    /// 从JSON文件加载布局，并选用具名主题，语义同 [`Self::parse_json_with_theme`]
+    pub fn load_json_file_with_theme<P: AsRef<Path>>(
+        path: P,
+        theme_name: Option<&str>,
+    ) -> Result<Layout, DslError> {
         let content = fs::read_to_string(path)?;
-        Self::parse_json(&content)
+        Self::parse_json_with_theme(&content, theme_name)
     }
 
     /// 从YAML文件加载布局
     pub fn load_yaml_file<P: AsRef<Path>>(path: P) -> Result<Layout, DslError> {
+        Self::load_yaml_file_with_theme(path, None)
+    }
+
+    /// 从YAML文件加载布局，并选用具名主题，语义同 [`Self::parse_json_with_theme`]
+    pub fn load_yaml_file_with_theme<P: AsRef<Path>>(
+        path: P,
+        theme_name: Option<&str>,
+    ) -> Result<Layout, DslError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse_yaml_with_theme(&content, theme_name)
+    }
+
+    /// 从TOML文件加载布局
+    pub fn load_toml_file<P: AsRef<Path>>(path: P) -> Result<Layout, DslError> {
         let content = fs::read_to_string(path)?;
-        Self::parse_yaml(&content)
+        Self::parse_toml(&content)
+    }
+
+    /// 按名字在标准配置目录下查找一个打包好的布局模板：依次尝试
+    /// `<config_dir>/study-rust/<name>.toml`、`.yaml`、`.json`，用第一个存在的文件，
+    /// 按对应格式解析。`config_dir` 在 Linux 上遵循 XDG Base Directory（`$XDG_CONFIG_HOME`
+    /// 或 `~/.config`），其他平台走各自的标准配置目录。这样宿主应用可以内置一套默认模板，
+    /// 终端用户又能在这个众所周知的位置放文件覆盖它，不用自己再写一遍搜索逻辑
+    pub fn load_named(name: &str) -> Result<Layout, DslError> {
+        let base = dirs::config_dir()
+            .ok_or_else(|| DslError::ValidationError("无法定位标准配置目录".to_string()))?
+            .join("study-rust");
+
+        let toml_path = base.join(format!("{}.toml", name));
+        if toml_path.is_file() {
+            return Self::load_toml_file(&toml_path);
+        }
+        let yaml_path = base.join(format!("{}.yaml", name));
+        if yaml_path.is_file() {
+            return Self::load_yaml_file(&yaml_path);
+        }
+        let json_path = base.join(format!("{}.json", name));
+        if json_path.is_file() {
+            return Self::load_json_file(&json_path);
+        }
+
+        Err(DslError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "在 {} 下找不到名为 \"{}\" 的布局模板（尝试了 .toml/.yaml/.json）",
+                base.display(),
+                name
+            ),
+        )))
     }
 
     /// 将DSL布局转换为内部布局表示
@@ -415,6 +999,17 @@ impl DslParser {
                 constraints: Self::convert_constraints(constraints)?,
                 children: Self::convert_children(children)?,
             }),
+            DslElement::Grid {
+                id,
+                properties,
+                constraints,
+                children,
+            } => Ok(Element::Grid {
+                id,
+                properties: Self::convert_grid_properties(properties)?,
+                constraints: Self::convert_constraints(constraints)?,
+                children: Self::convert_children(children)?,
+            }),
             DslElement::Spacer { id, constraints } => Ok(Element::Spacer {
                 id,
                 min_length: 0.0,
@@ -432,43 +1027,162 @@ impl DslParser {
     /// 转换文本属性
     fn convert_text_properties(props: DslTextProperties) -> Result<TextProperties, DslError> {
         Ok(TextProperties {
-            font_family: props.font_family,
-            font_size: props.font_size,
+            font_family: props.font_family.unwrap_or_else(default_font_family),
+            font_size: props.font_size.unwrap_or_else(default_font_size),
             font_weight: props.font_weight,
+            font_style: props.font_style,
             color: Self::convert_color(&props.color)?,
             alignment: props.alignment,
-            line_height: props.line_height,
-            letter_spacing: props.letter_spacing,
+            line_height: props.line_height.unwrap_or_else(default_line_height),
+            letter_spacing: props.letter_spacing.unwrap_or_else(default_letter_spacing),
             max_lines: None,
             line_break_mode: LineBreakMode::WordWrap,
+            filters: Self::convert_filters(props.filters)?,
+            style: Self::convert_style(props.style),
+            margin: Self::convert_margin(props.margin),
         })
     }
 
+    /// 转换文本装饰样式
+    fn convert_style(style: DslStyle) -> TextStyle {
+        TextStyle {
+            bold: style.bold,
+            italic: style.italic,
+            underline: style.underline,
+            strikethrough: style.strikethrough,
+            dim: style.dim,
+            reverse: style.reverse,
+            blink: style.blink,
+        }
+    }
+
     /// 转换图片属性
     fn convert_image_properties(props: DslImageProperties) -> Result<ImageProperties, DslError> {
         Ok(ImageProperties {
             scale_mode: props.scale_mode,
             aspect_ratio: None,
-            opacity: props.opacity,
+            opacity: props.opacity.unwrap_or_else(default_opacity),
             tint_color: props
                 .tint_color
                 .map(|c| Self::convert_color(&c))
                 .transpose()?,
             corner_radius: props.corner_radius,
+            shadow: props.shadow.map(|s| Self::convert_shadow_style(s)).transpose()?,
+            filters: Self::convert_filters(props.filters)?,
+            preserve_aspect_ratio: match props.preserve_aspect_ratio {
+                Some(s) => Self::parse_preserve_aspect_ratio(&s)?,
+                None => PreserveAspectRatio::default(),
+            },
+            margin: Self::convert_margin(props.margin),
         })
     }
 
+    /// 解析 SVG `preserveAspectRatio` 字符串，语法为 `<align> [<meetOrSlice>]`：
+    /// `align` 是 `none` 或 `xMin/xMid/xMax` 与 `YMin/YMid/YMax` 的九种组合之一，
+    /// `meetOrSlice` 省略时按规范默认取 `meet`；`align` 为 `none` 时 `meetOrSlice`
+    /// 会被忽略（即便写了也不校验），任何无法识别的 token 都返回 `ValidationError`
+    fn parse_preserve_aspect_ratio(s: &str) -> Result<PreserveAspectRatio, DslError> {
+        let mut tokens = s.split_whitespace();
+        let align_token = tokens.next().ok_or_else(|| {
+            DslError::ValidationError("preserve_aspect_ratio 不能为空字符串".to_string())
+        })?;
+
+        if align_token.eq_ignore_ascii_case("none") {
+            return Ok(PreserveAspectRatio {
+                align: None,
+                mode: MeetOrSlice::Meet,
+            });
+        }
+
+        let align = match align_token {
+            "xMinYMin" => Align9::XMinYMin,
+            "xMidYMin" => Align9::XMidYMin,
+            "xMaxYMin" => Align9::XMaxYMin,
+            "xMinYMid" => Align9::XMinYMid,
+            "xMidYMid" => Align9::XMidYMid,
+            "xMaxYMid" => Align9::XMaxYMid,
+            "xMinYMax" => Align9::XMinYMax,
+            "xMidYMax" => Align9::XMidYMax,
+            "xMaxYMax" => Align9::XMaxYMax,
+            other => {
+                return Err(DslError::ValidationError(format!(
+                    "无法识别的 preserve_aspect_ratio 对齐方式: {}",
+                    other
+                )))
+            }
+        };
+
+        let mode = match tokens.next() {
+            None | Some("meet") => MeetOrSlice::Meet,
+            Some("slice") => MeetOrSlice::Slice,
+            Some(other) => {
+                return Err(DslError::ValidationError(format!(
+                    "无法识别的 preserve_aspect_ratio meetOrSlice: {}",
+                    other
+                )))
+            }
+        };
+
+        if tokens.next().is_some() {
+            return Err(DslError::ValidationError(format!(
+                "preserve_aspect_ratio 包含多余的内容: {}",
+                s
+            )));
+        }
+
+        Ok(PreserveAspectRatio { align: Some(align), mode })
+    }
+
     /// 转换容器属性
     fn convert_container_properties(
         props: DslContainerProperties,
     ) -> Result<ContainerProperties, DslError> {
         Ok(ContainerProperties {
             background: Self::convert_color(&props.background)?,
-            border_color: Self::convert_color(&props.border_color)?,
-            border_width: props.border_width,
-            corner_radius: props.corner_radius,
+            corners: Self::convert_corners(props.corners),
+            border: Self::convert_border(props.border)?,
             opacity: 1.0,
             padding: Self::convert_padding(props.padding),
+            margin: Self::convert_margin(props.margin),
+            shadow: props.shadow.map(|s| Self::convert_shadow_style(s)).transpose()?,
+            filters: Self::convert_filters(props.filters)?,
+        })
+    }
+
+    /// 转换滤镜列表，顺序保留，按顺序应用
+    fn convert_filters(filters: Vec<DslFilter>) -> Result<Vec<Filter>, DslError> {
+        filters.into_iter().map(Self::convert_filter).collect()
+    }
+
+    /// 转换单个滤镜
+    fn convert_filter(filter: DslFilter) -> Result<Filter, DslError> {
+        match filter {
+            DslFilter::GaussianBlur { std_deviation } => {
+                Ok(Filter::GaussianBlur { std_deviation })
+            }
+            DslFilter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => Ok(Filter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color: Self::convert_color(&color)?,
+            }),
+            DslFilter::ColorMatrix { values } => Ok(Filter::ColorMatrix { values }),
+        }
+    }
+
+    /// 转换投影样式
+    fn convert_shadow_style(shadow: DslShadowStyle) -> Result<ShadowStyle, DslError> {
+        Ok(ShadowStyle {
+            color: Self::convert_color(&shadow.color)?,
+            blur_radius: shadow.blur_radius,
+            offset_x: shadow.offset_x,
+            offset_y: shadow.offset_y,
+            opacity: shadow.opacity,
         })
     }
 
@@ -478,9 +1192,53 @@ impl DslParser {
             spacing: props.spacing,
             alignment: props.alignment,
             distribution: props.distribution,
+            padding: Self::convert_padding(props.padding),
+            margin: Self::convert_margin(props.margin),
+        })
+    }
+
+    /// 转换网格属性
+    fn convert_grid_properties(props: DslGridProperties) -> Result<GridProperties, DslError> {
+        Ok(GridProperties {
+            rows: props
+                .rows
+                .into_iter()
+                .map(Self::convert_grid_track)
+                .collect::<Result<Vec<_>, _>>()?,
+            cols: props
+                .cols
+                .into_iter()
+                .map(Self::convert_grid_track)
+                .collect::<Result<Vec<_>, _>>()?,
+            row_spacing: props.row_spacing,
+            col_spacing: props.col_spacing,
+            margin: Self::convert_margin(props.margin),
         })
     }
 
+    /// 转换网格轨道尺寸："50%" 解析为百分比轨道，"2fr" 解析为按权重分配的弹性轨道
+    fn convert_grid_track(track: DslGridTrack) -> Result<GridTrack, DslError> {
+        match track {
+            DslGridTrack::Fixed(value) => Ok(GridTrack::Fixed(value)),
+            DslGridTrack::Text(text) => {
+                if let Some(value) = text.strip_suffix('%') {
+                    return value.parse::<f32>().map(GridTrack::Percent).map_err(|_| {
+                        DslError::ValidationError(format!("Invalid grid track percentage: {}", text))
+                    });
+                }
+                if let Some(value) = text.strip_suffix("fr") {
+                    return value.parse::<f32>().map(GridTrack::Fraction).map_err(|_| {
+                        DslError::ValidationError(format!("Invalid grid track fraction: {}", text))
+                    });
+                }
+                Err(DslError::ValidationError(format!(
+                    "Invalid grid track: {}",
+                    text
+                )))
+            }
+        }
+    }
+
     /// 转换约束列表
     fn convert_constraints(constraints: Vec<DslConstraint>) -> Result<Vec<Constraint>, DslError> {
         constraints
@@ -621,6 +1379,19 @@ impl DslParser {
             DslConstraint::AspectRatio { ratio, priority } => {
                 (ConstraintType::AspectRatio { ratio }, priority)
             }
+            DslConstraint::Fill { weight, priority } => {
+                (ConstraintType::Fill { weight }, priority)
+            }
+            DslConstraint::GridPosition {
+                row,
+                col,
+                row_span,
+                col_span,
+                priority,
+            } => (
+                ConstraintType::GridPosition { row, col, row_span, col_span },
+                priority,
+            ),
             DslConstraint::AlignTop {
                 target,
                 constant: _,
@@ -705,147 +1476,20 @@ impl DslParser {
                 b: *b,
                 a: 255,
             }),
-            DslColor::Hex(hex_str) => Self::parse_hex_color(hex_str),
-            DslColor::Named(name) => Self::parse_named_color(name),
-        }
-    }
-
-    /// 解析十六进制颜色
-    fn parse_hex_color(hex: &str) -> Result<Color, DslError> {
-        let hex = hex.trim_start_matches('#');
-
-        match hex.len() {
-            3 => {
-                // #RGB -> #RRGGBB
-                let r = u8::from_str_radix(&hex[0..1].repeat(2), 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let g = u8::from_str_radix(&hex[1..2].repeat(2), 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let b = u8::from_str_radix(&hex[2..3].repeat(2), 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                Ok(Color { r, g, b, a: 255 })
-            }
-            6 => {
-                // #RRGGBB
-                let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                Ok(Color { r, g, b, a: 255 })
-            }
-            8 => {
-                // #RRGGBBAA
-                let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                let a = u8::from_str_radix(&hex[6..8], 16).map_err(|_| {
-                    DslError::ValidationError(format!("Invalid hex color: #{}", hex))
-                })?;
-                Ok(Color { r, g, b, a })
-            }
-            _ => Err(DslError::ValidationError(format!(
-                "Invalid hex color format: #{}",
-                hex
-            ))),
-        }
-    }
-
-    /// 解析命名颜色
-    fn parse_named_color(name: &str) -> Result<Color, DslError> {
-        match name.to_lowercase().as_str() {
-            "transparent" => Ok(Color {
-                r: 0,
-                g: 0,
-                b: 0,
-                a: 0,
-            }),
-            "black" => Ok(Color {
-                r: 0,
-                g: 0,
-                b: 0,
-                a: 255,
-            }),
-            "white" => Ok(Color {
-                r: 255,
-                g: 255,
-                b: 255,
-                a: 255,
-            }),
-            "red" => Ok(Color {
-                r: 255,
-                g: 0,
-                b: 0,
-                a: 255,
-            }),
-            "green" => Ok(Color {
-                r: 0,
-                g: 255,
-                b: 0,
-                a: 255,
-            }),
-            "blue" => Ok(Color {
-                r: 0,
-                g: 0,
-                b: 255,
-                a: 255,
-            }),
-            "yellow" => Ok(Color {
-                r: 255,
-                g: 255,
-                b: 0,
-                a: 255,
-            }),
-            "cyan" => Ok(Color {
-                r: 0,
-                g: 255,
-                b: 255,
-                a: 255,
-            }),
-            "magenta" => Ok(Color {
-                r: 255,
-                g: 0,
-                b: 255,
-                a: 255,
-            }),
-            "gray" | "grey" => Ok(Color {
-                r: 128,
-                g: 128,
-                b: 128,
-                a: 255,
-            }),
-            "lightgray" | "lightgrey" => Ok(Color {
-                r: 211,
-                g: 211,
-                b: 211,
-                a: 255,
-            }),
-            "darkgray" | "darkgrey" => Ok(Color {
-                r: 169,
-                g: 169,
-                b: 169,
-                a: 255,
-            }),
-            _ => Err(DslError::ValidationError(format!(
-                "Unknown color name: {}",
-                name
-            ))),
+            // `Hex`/`Named` 都只是套了层不同名字的 `String`：untagged 枚举按声明顺序匹配，
+            // 任何字符串形状都会先落进 `Hex`，`Named` 分支实际上不会被 serde 选中——
+            // 所以两者在这里统一交给同一个按内容分发的解析入口
+            DslColor::Hex(s) | DslColor::Named(s) => Self::parse_color_string(s),
         }
     }
 
+    /// 把字符串形式的颜色委托给 `Color::parse` 做真正的解析（十六进制、`rgb()`/
+    /// `rgba()`/`hsl()`/`hsla()`/`hwb()` 函数记法、CSS/SVG 命名颜色），只是把
+    /// `String` 错误包进 DSL 自己的 `ValidationError`
+    fn parse_color_string(s: &str) -> Result<Color, DslError> {
+        Color::parse(s).map_err(DslError::ValidationError)
+    }
+
     /// 转换内边距
     fn convert_padding(padding: DslPadding) -> Padding {
         match padding {
@@ -868,6 +1512,77 @@ impl DslParser {
             },
         }
     }
+
+    /// 转换外边距
+    fn convert_margin(margin: DslMargin) -> Margin {
+        match margin {
+            DslMargin::Uniform(value) => Margin::all(value),
+            DslMargin::Detailed {
+                top,
+                right,
+                bottom,
+                left,
+            } => Margin {
+                top,
+                right,
+                bottom,
+                left,
+            },
+        }
+    }
+
+    /// 转换圆角半径
+    fn convert_corners(corners: DslCorners) -> Corners {
+        match corners {
+            DslCorners::Uniform(value) => Corners::all(value),
+            DslCorners::Detailed {
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            } => Corners {
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            },
+        }
+    }
+
+    /// 转换边框：四边共用一条描述时展开成四个相同的 [`BorderSide`]
+    fn convert_border(border: DslBorder) -> Result<Border, DslError> {
+        Ok(match border {
+            DslBorder::Uniform(side) => {
+                let side = Self::convert_border_side(side)?;
+                Border {
+                    top: side.clone(),
+                    right: side.clone(),
+                    bottom: side.clone(),
+                    left: side,
+                }
+            }
+            DslBorder::Sides {
+                top,
+                right,
+                bottom,
+                left,
+            } => Border {
+                top: Self::convert_border_side(top)?,
+                right: Self::convert_border_side(right)?,
+                bottom: Self::convert_border_side(bottom)?,
+                left: Self::convert_border_side(left)?,
+            },
+        })
+    }
+
+    /// 转换单条边框描述
+    fn convert_border_side(side: DslBorderSide) -> Result<BorderSide, DslError> {
+        Ok(BorderSide {
+            width: side.width,
+            color: Self::convert_color(&side.color)?,
+            style: side.style,
+        })
+    }
 }
 
 // 默认值函数
@@ -891,6 +1606,26 @@ fn default_opacity() -> f32 {
     1.0
 }
 
+fn default_shadow_blur_radius() -> f32 {
+    8.0
+}
+
+fn default_shadow_offset_y() -> f32 {
+    4.0
+}
+
+fn default_shadow_opacity() -> f32 {
+    0.3
+}
+
+fn default_fill_weight() -> f32 {
+    1.0
+}
+
+fn default_span() -> u32 {
+    1
+}
+
 impl Default for DslColor {
     fn default() -> Self {
         DslColor::Named("transparent".to_string())
@@ -902,3 +1637,680 @@ impl Default for DslPadding {
         DslPadding::Uniform(0.0)
     }
 }
+
+impl Default for DslMargin {
+    fn default() -> Self {
+        DslMargin::Uniform(0.0)
+    }
+}
+
+impl Default for DslCorners {
+    fn default() -> Self {
+        DslCorners::Uniform(0.0)
+    }
+}
+
+impl Default for DslBorderSide {
+    fn default() -> Self {
+        Self {
+            width: 0.0,
+            color: DslColor::default(),
+            style: BorderStyle::default(),
+        }
+    }
+}
+
+impl Default for DslBorder {
+    fn default() -> Self {
+        DslBorder::Uniform(DslBorderSide::default())
+    }
+}
+
+impl Default for DslTextProperties {
+    fn default() -> Self {
+        Self {
+            font_family: Some(default_font_family()),
+            font_size: Some(default_font_size()),
+            font_weight: FontWeight::default(),
+            font_style: FontStyle::default(),
+            color: DslColor::default(),
+            alignment: TextAlignment::default(),
+            line_height: Some(default_line_height()),
+            letter_spacing: Some(default_letter_spacing()),
+            filters: Vec::new(),
+            style: DslStyle::default(),
+            margin: DslMargin::default(),
+        }
+    }
+}
+
+impl Default for DslShadowStyle {
+    fn default() -> Self {
+        Self {
+            color: DslColor::default(),
+            blur_radius: default_shadow_blur_radius(),
+            offset_x: 0.0,
+            offset_y: default_shadow_offset_y(),
+            opacity: default_shadow_opacity(),
+        }
+    }
+}
+
+impl Default for DslImageProperties {
+    fn default() -> Self {
+        Self {
+            scale_mode: ScaleMode::default(),
+            opacity: Some(default_opacity()),
+            tint_color: None,
+            corner_radius: 0.0,
+            shadow: None,
+            filters: Vec::new(),
+            preserve_aspect_ratio: None,
+            margin: DslMargin::default(),
+        }
+    }
+}
+
+impl Default for DslContainerProperties {
+    fn default() -> Self {
+        Self {
+            background: DslColor::default(),
+            border: DslBorder::default(),
+            corners: DslCorners::default(),
+            padding: DslPadding::default(),
+            margin: DslMargin::default(),
+            shadow: None,
+            filters: Vec::new(),
+        }
+    }
+}
+
+impl Default for DslStackProperties {
+    fn default() -> Self {
+        Self {
+            alignment: Alignment::default(),
+            distribution: Distribution::default(),
+            spacing: 0.0,
+            padding: DslPadding::default(),
+            margin: DslMargin::default(),
+        }
+    }
+}
+
+impl Default for DslGridProperties {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            cols: Vec::new(),
+            row_spacing: 0.0,
+            col_spacing: 0.0,
+            margin: DslMargin::default(),
+        }
+    }
+}
+
+/// 容错解析（[`DslParser::parse_json_lenient`]/[`DslParser::parse_yaml_lenient`]）过程中
+/// 记录的一条诊断：某个字段反序列化失败、被换成了对应类型的默认值，或者整个元素/约束
+/// 因为无法识别的 `type` 标签被跳过
+#[derive(Debug, Clone)]
+pub struct DslDiagnostic {
+    /// 出问题的字段在文档里的位置，例如 `elements[2].properties.font_size`
+    pub path: String,
+    pub message: String,
+    /// `true` 表示已经换上默认值继续渲染；`false` 表示这个节点被整个跳过（如未知的
+    /// 元素 `type`），不会出现在最终 `Layout` 里
+    pub recovered_default: bool,
+}
+
+impl DslParser {
+    /// 从JSON字符串按“尽量渲染”的策略解析布局：字段级的反序列化失败不会让整份
+    /// 布局解析失败，而是换上对应类型的默认值并记一条 [`DslDiagnostic`]；无法
+    /// 识别的元素 `type`/约束 `type` 直接跳过（同样记一条诊断）。只有 JSON 语法
+    /// 本身不合法时才报错；语法合法但顶层不是对象时，按缺少 `canvas`/`elements`
+    /// 处理（记诊断、元素列表为空），容错只发生在模式（schema）层面。
+    pub fn parse_json_lenient(json: &str) -> Result<(Layout, Vec<DslDiagnostic>), DslError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        Ok(Self::lenient_layout(value))
+    }
+
+    /// 从YAML字符串按“尽量渲染”的策略解析布局，语义同 [`DslParser::parse_json_lenient`]
+    pub fn parse_yaml_lenient(yaml: &str) -> Result<(Layout, Vec<DslDiagnostic>), DslError> {
+        let value: serde_json::Value = serde_yaml::from_str(yaml)?;
+        Ok(Self::lenient_layout(value))
+    }
+
+    /// 按 `path` 取出 `obj[key]` 并尝试反序列化成 `T`；字段缺失直接返回 `default`
+    /// （这本来就是 `#[serde(default)]` 的语义，不值得记一条诊断），字段存在但
+    /// 反序列化失败才记诊断并换上 `default`
+    fn lenient_field<T: serde::de::DeserializeOwned>(
+        obj: Option<&serde_json::Map<String, serde_json::Value>>,
+        key: &str,
+        path: &str,
+        default: T,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> T {
+        match obj.and_then(|o| o.get(key)) {
+            None => default,
+            Some(value) => match serde_json::from_value::<T>(value.clone()) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    diagnostics.push(DslDiagnostic {
+                        path: path.to_string(),
+                        message: e.to_string(),
+                        recovered_default: true,
+                    });
+                    default
+                }
+            },
+        }
+    }
+
+    /// 同 [`Self::lenient_field`]，但额外接受字面量字符串 `"none"`/`"null"`（大小写
+    /// 不敏感）作为“这个 `Option` 字段没有值”的另一种写法，方便手写模板的人不用
+    /// 记住 JSON/YAML 各自的空值语法
+    fn lenient_option_field<T: serde::de::DeserializeOwned>(
+        obj: Option<&serde_json::Map<String, serde_json::Value>>,
+        key: &str,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> Option<T> {
+        match obj.and_then(|o| o.get(key)) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(s))
+                if s.eq_ignore_ascii_case("none") || s.eq_ignore_ascii_case("null") =>
+            {
+                None
+            }
+            Some(value) => match serde_json::from_value::<T>(value.clone()) {
+                Ok(parsed) => Some(parsed),
+                Err(e) => {
+                    diagnostics.push(DslDiagnostic {
+                        path: path.to_string(),
+                        message: e.to_string(),
+                        recovered_default: true,
+                    });
+                    None
+                }
+            },
+        }
+    }
+
+    /// `filters` 字段的容错解析：解析失败（未知滤镜类型、字段缺失）就丢弃整个列表，
+    /// 记一条诊断，而不是让调用方连带着整个元素的解析都失败
+    fn lenient_filters(
+        obj: Option<&serde_json::Map<String, serde_json::Value>>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> Vec<Filter> {
+        let filter_path = format!("{}.filters", path);
+        let raw: Vec<DslFilter> =
+            Self::lenient_field(obj, "filters", &filter_path, Vec::new(), diagnostics);
+        match Self::convert_filters(raw) {
+            Ok(filters) => filters,
+            Err(e) => {
+                diagnostics.push(DslDiagnostic {
+                    path: filter_path,
+                    message: e.to_string(),
+                    recovered_default: true,
+                });
+                Vec::new()
+            }
+        }
+    }
+
+    /// 顶层布局的容错解析：`canvas`/`elements` 缺失或整体畸形都换上空布局继续，
+    /// 具体字段级的恢复发生在 [`Self::lenient_canvas`]/[`Self::lenient_element`] 里
+    fn lenient_layout(value: serde_json::Value) -> (Layout, Vec<DslDiagnostic>) {
+        let mut diagnostics = Vec::new();
+        let obj = value.as_object();
+
+        let canvas = match obj.and_then(|o| o.get("canvas")) {
+            Some(v) => Self::lenient_canvas(v, &mut diagnostics),
+            None => {
+                diagnostics.push(DslDiagnostic {
+                    path: "canvas".to_string(),
+                    message: "missing field `canvas`".to_string(),
+                    recovered_default: true,
+                });
+                Canvas {
+                    width: 0.0,
+                    height: 0.0,
+                    background: Color::TRANSPARENT,
+                    padding: Padding::all(0.0),
+                }
+            }
+        };
+
+        let elements = match obj.and_then(|o| o.get("elements")).and_then(|v| v.as_array()) {
+            Some(items) => items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    Self::lenient_element(item, format!("elements[{}]", i), &mut diagnostics)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        (
+            Layout {
+                version: "1.0".to_string(),
+                canvas,
+                elements,
+            },
+            diagnostics,
+        )
+    }
+
+    /// `canvas` 字段的容错解析：`width`/`height` 各自独立恢复，`background` 解析/转换
+    /// 失败就退回白色背景
+    fn lenient_canvas(value: &serde_json::Value, diagnostics: &mut Vec<DslDiagnostic>) -> Canvas {
+        let obj = value.as_object();
+        let width = Self::lenient_field(obj, "width", "canvas.width", 0.0, diagnostics);
+        let height = Self::lenient_field(obj, "height", "canvas.height", 0.0, diagnostics);
+        let background = match obj.and_then(|o| o.get("background")) {
+            Some(v) => Self::lenient_color(v, "canvas.background", diagnostics),
+            None => Color::WHITE,
+        };
+        Canvas {
+            width,
+            height,
+            background,
+            padding: Padding::all(0.0),
+        }
+    }
+
+    /// 解析一个颜色字段并立即转换成内部 [`Color`]；`DslColor` 本身反序列化失败，或者
+    /// 转换阶段报错（比如十六进制格式不对、颜色名不认识）都记诊断、退回白色
+    fn lenient_color(value: &serde_json::Value, path: &str, diagnostics: &mut Vec<DslDiagnostic>) -> Color {
+        let parse_result = serde_json::from_value::<DslColor>(value.clone())
+            .map_err(DslError::from)
+            .and_then(|c| Self::convert_color(&c));
+        match parse_result {
+            Ok(color) => color,
+            Err(e) => {
+                diagnostics.push(DslDiagnostic {
+                    path: path.to_string(),
+                    message: e.to_string(),
+                    recovered_default: true,
+                });
+                Color::WHITE
+            }
+        }
+    }
+
+    /// 单个元素的容错解析：未知的 `type` 标签记一条诊断并整个跳过（返回 `None`），
+    /// 已知类型内部的字段（`properties` 的各个字段、`constraints`）各自独立恢复
+    fn lenient_element(
+        value: &serde_json::Value,
+        path: String,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> Option<Element> {
+        let obj = match value.as_object() {
+            Some(obj) => obj,
+            None => {
+                diagnostics.push(DslDiagnostic {
+                    path,
+                    message: "element is not an object".to_string(),
+                    recovered_default: false,
+                });
+                return None;
+            }
+        };
+
+        let id = Self::lenient_field(Some(obj), "id", &format!("{}.id", path), String::new(), diagnostics);
+        let constraints = Self::lenient_constraints(obj.get("constraints"), &format!("{}.constraints", path), diagnostics);
+        let children = match obj.get("children").and_then(|v| v.as_array()) {
+            Some(items) => items
+                .iter()
+                .enumerate()
+                .filter_map(|(i, item)| {
+                    Self::lenient_element(item, format!("{}.children[{}]", path, i), diagnostics)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let type_tag = obj.get("type").and_then(|v| v.as_str());
+        match type_tag {
+            Some("text") => {
+                let content = Self::lenient_field(Some(obj), "content", &format!("{}.content", path), String::new(), diagnostics);
+                let properties = Self::lenient_text_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::Text { id, content, properties, constraints })
+            }
+            Some("image") => {
+                let source = Self::lenient_field(Some(obj), "source", &format!("{}.source", path), String::new(), diagnostics);
+                let properties = Self::lenient_image_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::Image { id, source, properties, constraints })
+            }
+            Some("container") => {
+                let properties = Self::lenient_container_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::Container { id, properties, constraints, children })
+            }
+            Some("vstack") => {
+                let properties = Self::lenient_stack_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::VStack { id, properties, constraints, children })
+            }
+            Some("hstack") => {
+                let properties = Self::lenient_stack_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::HStack { id, properties, constraints, children })
+            }
+            Some("zstack") => {
+                let properties = Self::lenient_stack_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::ZStack { id, properties, constraints, children })
+            }
+            Some("grid") => {
+                let properties = Self::lenient_grid_properties(obj.get("properties"), &format!("{}.properties", path), diagnostics);
+                Some(Element::Grid { id, properties, constraints, children })
+            }
+            Some("spacer") => Some(Element::Spacer {
+                id,
+                min_length: 0.0,
+                priority: Priority::Low,
+                constraints,
+            }),
+            other => {
+                diagnostics.push(DslDiagnostic {
+                    path: format!("{}.type", path),
+                    message: match other {
+                        Some(tag) => format!("unknown element type `{}`", tag),
+                        None => "missing field `type`".to_string(),
+                    },
+                    recovered_default: false,
+                });
+                None
+            }
+        }
+    }
+
+    /// 文本属性的容错解析：`font_size`、`color` 等字段各自独立恢复，满足本请求
+    /// 明确提到的 `font_size` 场景
+    fn lenient_text_properties(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> TextProperties {
+        let defaults = DslTextProperties::default();
+        let obj = value.and_then(|v| v.as_object());
+        let font_family = Self::lenient_field(obj, "font_family", &format!("{}.font_family", path), defaults.font_family.unwrap_or_else(default_font_family), diagnostics);
+        let font_size = Self::lenient_field(obj, "font_size", &format!("{}.font_size", path), defaults.font_size.unwrap_or_else(default_font_size), diagnostics);
+        let font_weight = Self::lenient_field(obj, "font_weight", &format!("{}.font_weight", path), defaults.font_weight, diagnostics);
+        let font_style = Self::lenient_field(obj, "font_style", &format!("{}.font_style", path), defaults.font_style, diagnostics);
+        let color = match obj.and_then(|o| o.get("color")) {
+            Some(v) => Self::lenient_color(v, &format!("{}.color", path), diagnostics),
+            None => Color::BLACK,
+        };
+        let alignment = Self::lenient_field(obj, "alignment", &format!("{}.alignment", path), defaults.alignment, diagnostics);
+        let line_height = Self::lenient_field(obj, "line_height", &format!("{}.line_height", path), defaults.line_height.unwrap_or_else(default_line_height), diagnostics);
+        let letter_spacing = Self::lenient_field(obj, "letter_spacing", &format!("{}.letter_spacing", path), defaults.letter_spacing.unwrap_or_else(default_letter_spacing), diagnostics);
+        let filters = Self::lenient_filters(obj, path, diagnostics);
+        let style = Self::convert_style(obj.and_then(|o| o.get("style")).and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default());
+        let margin = Self::lenient_field(obj, "margin", &format!("{}.margin", path), defaults.margin, diagnostics);
+
+        TextProperties {
+            font_family,
+            font_size,
+            font_weight,
+            font_style,
+            color,
+            alignment,
+            line_height,
+            letter_spacing,
+            max_lines: None,
+            line_break_mode: LineBreakMode::WordWrap,
+            filters,
+            style,
+            margin: Self::convert_margin(margin),
+        }
+    }
+
+    /// 图片属性的容错解析：`tint_color`/`shadow`/`preserve_aspect_ratio` 这类 `Option`
+    /// 字段额外接受字面量 `"none"`/`"null"`
+    fn lenient_image_properties(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> ImageProperties {
+        let defaults = DslImageProperties::default();
+        let obj = value.and_then(|v| v.as_object());
+        let scale_mode = Self::lenient_field(obj, "scale_mode", &format!("{}.scale_mode", path), defaults.scale_mode, diagnostics);
+        let opacity = Self::lenient_field(obj, "opacity", &format!("{}.opacity", path), defaults.opacity.unwrap_or_else(default_opacity), diagnostics);
+        let tint_color: Option<DslColor> = Self::lenient_option_field(obj, "tint_color", &format!("{}.tint_color", path), diagnostics);
+        let corner_radius = Self::lenient_field(obj, "corner_radius", &format!("{}.corner_radius", path), defaults.corner_radius, diagnostics);
+        let shadow: Option<DslShadowStyle> = Self::lenient_option_field(obj, "shadow", &format!("{}.shadow", path), diagnostics);
+
+        let tint_color = match tint_color {
+            Some(c) => match Self::convert_color(&c) {
+                Ok(color) => Some(color),
+                Err(e) => {
+                    diagnostics.push(DslDiagnostic {
+                        path: format!("{}.tint_color", path),
+                        message: e.to_string(),
+                        recovered_default: true,
+                    });
+                    None
+                }
+            },
+            None => None,
+        };
+        let shadow = match shadow {
+            Some(s) => match Self::convert_shadow_style(s) {
+                Ok(shadow) => Some(shadow),
+                Err(e) => {
+                    diagnostics.push(DslDiagnostic {
+                        path: format!("{}.shadow", path),
+                        message: e.to_string(),
+                        recovered_default: true,
+                    });
+                    None
+                }
+            },
+            None => None,
+        };
+        let filters = Self::lenient_filters(obj, path, diagnostics);
+        let preserve_aspect_ratio = Self::lenient_preserve_aspect_ratio(obj, path, diagnostics);
+        let margin = Self::lenient_field(obj, "margin", &format!("{}.margin", path), defaults.margin, diagnostics);
+
+        ImageProperties {
+            scale_mode,
+            aspect_ratio: None,
+            opacity,
+            tint_color,
+            corner_radius,
+            shadow,
+            filters,
+            preserve_aspect_ratio,
+            margin: Self::convert_margin(margin),
+        }
+    }
+
+    /// `preserve_aspect_ratio` 字段的容错解析：解析失败就记一条诊断，退回默认的
+    /// `xMidYMid meet`，不影响元素其余部分的解析
+    fn lenient_preserve_aspect_ratio(
+        obj: Option<&serde_json::Map<String, serde_json::Value>>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> PreserveAspectRatio {
+        let field_path = format!("{}.preserve_aspect_ratio", path);
+        let raw: Option<String> =
+            Self::lenient_option_field(obj, "preserve_aspect_ratio", &field_path, diagnostics);
+        match raw {
+            Some(s) => match Self::parse_preserve_aspect_ratio(&s) {
+                Ok(par) => par,
+                Err(e) => {
+                    diagnostics.push(DslDiagnostic {
+                        path: field_path,
+                        message: e.to_string(),
+                        recovered_default: true,
+                    });
+                    PreserveAspectRatio::default()
+                }
+            },
+            None => PreserveAspectRatio::default(),
+        }
+    }
+
+    /// 容器属性的容错解析
+    fn lenient_container_properties(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> ContainerProperties {
+        let defaults = DslContainerProperties::default();
+        let obj = value.and_then(|v| v.as_object());
+        let background = match obj.and_then(|o| o.get("background")) {
+            Some(v) => Self::lenient_color(v, &format!("{}.background", path), diagnostics),
+            None => Color::TRANSPARENT,
+        };
+        let border = Self::lenient_field(obj, "border", &format!("{}.border", path), defaults.border, diagnostics);
+        let corners = Self::lenient_field(obj, "corners", &format!("{}.corners", path), defaults.corners, diagnostics);
+        let padding = Self::lenient_field(obj, "padding", &format!("{}.padding", path), defaults.padding, diagnostics);
+        let margin = Self::lenient_field(obj, "margin", &format!("{}.margin", path), defaults.margin, diagnostics);
+        let shadow: Option<DslShadowStyle> = Self::lenient_option_field(obj, "shadow", &format!("{}.shadow", path), diagnostics);
+        let shadow = match shadow {
+            Some(s) => match Self::convert_shadow_style(s) {
+                Ok(shadow) => Some(shadow),
+                Err(e) => {
+                    diagnostics.push(DslDiagnostic {
+                        path: format!("{}.shadow", path),
+                        message: e.to_string(),
+                        recovered_default: true,
+                    });
+                    None
+                }
+            },
+            None => None,
+        };
+        let filters = Self::lenient_filters(obj, path, diagnostics);
+        let border = match Self::convert_border(border) {
+            Ok(border) => border,
+            Err(e) => {
+                diagnostics.push(DslDiagnostic {
+                    path: format!("{}.border", path),
+                    message: e.to_string(),
+                    recovered_default: true,
+                });
+                Border::default()
+            }
+        };
+
+        ContainerProperties {
+            background,
+            border,
+            corners: Self::convert_corners(corners),
+            opacity: 1.0,
+            padding: Self::convert_padding(padding),
+            margin: Self::convert_margin(margin),
+            shadow,
+            filters,
+        }
+    }
+
+    /// 堆叠属性的容错解析
+    fn lenient_stack_properties(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> StackProperties {
+        let defaults = DslStackProperties::default();
+        let obj = value.and_then(|v| v.as_object());
+        let alignment = Self::lenient_field(obj, "alignment", &format!("{}.alignment", path), defaults.alignment, diagnostics);
+        let distribution = Self::lenient_field(obj, "distribution", &format!("{}.distribution", path), defaults.distribution, diagnostics);
+        let spacing = Self::lenient_field(obj, "spacing", &format!("{}.spacing", path), defaults.spacing, diagnostics);
+        let padding = Self::lenient_field(obj, "padding", &format!("{}.padding", path), defaults.padding, diagnostics);
+        let margin = Self::lenient_field(obj, "margin", &format!("{}.margin", path), defaults.margin, diagnostics);
+
+        StackProperties {
+            spacing,
+            alignment,
+            distribution,
+            padding: Self::convert_padding(padding),
+            margin: Self::convert_margin(margin),
+        }
+    }
+
+    /// 网格属性的容错解析：单条轨道解析失败就整条跳过，而不是让整个 `rows`/`cols`
+    /// 都退回空列表
+    fn lenient_grid_properties(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> GridProperties {
+        let defaults = DslGridProperties::default();
+        let obj = value.and_then(|v| v.as_object());
+        let rows = Self::lenient_grid_tracks(obj.and_then(|o| o.get("rows")), &format!("{}.rows", path), diagnostics);
+        let cols = Self::lenient_grid_tracks(obj.and_then(|o| o.get("cols")), &format!("{}.cols", path), diagnostics);
+        let row_spacing = Self::lenient_field(obj, "row_spacing", &format!("{}.row_spacing", path), defaults.row_spacing, diagnostics);
+        let col_spacing = Self::lenient_field(obj, "col_spacing", &format!("{}.col_spacing", path), defaults.col_spacing, diagnostics);
+        let margin = Self::lenient_field(obj, "margin", &format!("{}.margin", path), defaults.margin, diagnostics);
+
+        GridProperties { rows, cols, row_spacing, col_spacing, margin: Self::convert_margin(margin) }
+    }
+
+    fn lenient_grid_tracks(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> Vec<GridTrack> {
+        let items = match value.and_then(|v| v.as_array()) {
+            Some(items) => items,
+            None => return Vec::new(),
+        };
+
+        items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let track_path = format!("{}[{}]", path, i);
+                match serde_json::from_value::<DslGridTrack>(item.clone())
+                    .map_err(DslError::from)
+                    .and_then(Self::convert_grid_track)
+                {
+                    Ok(track) => Some(track),
+                    Err(e) => {
+                        diagnostics.push(DslDiagnostic {
+                            path: track_path,
+                            message: e.to_string(),
+                            recovered_default: false,
+                        });
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// 约束列表的容错解析：单条约束解析/转换失败就整条跳过，不影响其余约束
+    fn lenient_constraints(
+        value: Option<&serde_json::Value>,
+        path: &str,
+        diagnostics: &mut Vec<DslDiagnostic>,
+    ) -> Vec<Constraint> {
+        let items = match value.and_then(|v| v.as_array()) {
+            Some(items) => items,
+            None => return Vec::new(),
+        };
+
+        items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let constraint_path = format!("{}[{}]", path, i);
+                match serde_json::from_value::<DslConstraint>(item.clone())
+                    .map_err(DslError::from)
+                    .and_then(Self::convert_constraint)
+                {
+                    Ok(constraint) => Some(constraint),
+                    Err(e) => {
+                        diagnostics.push(DslDiagnostic {
+                            path: constraint_path,
+                            message: e.to_string(),
+                            recovered_default: false,
+                        });
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
@@ -2,12 +2,24 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 /// 元素唯一标识符
 pub type ElementId = String;
 
+/// 按位哈希一个 f32：`f32` 本身没有实现 `Hash`（NaN/±0 的相等语义和按位比较不一致），
+/// 这里只是把它当作布局结果缓存的 key 来用，按位哈希足够稳定
+fn hash_f32<H: Hasher>(value: f32, state: &mut H) {
+    value.to_bits().hash(state);
+}
+
+/// 同 [`hash_f32`]，用于 `f64`
+fn hash_f64<H: Hasher>(value: f64, state: &mut H) {
+    value.to_bits().hash(state);
+}
+
 /// 颜色定义
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -25,7 +37,7 @@ impl Color {
         if hex.len() != 6 && hex.len() != 8 {
             return Err("Invalid hex color format".to_string());
         }
-        
+
         let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| "Invalid hex color")?;
         let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| "Invalid hex color")?;
         let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| "Invalid hex color")?;
@@ -34,9 +46,536 @@ impl Color {
         } else {
             255
         };
-        
+
         Ok(Color { r, g, b, a })
     }
+
+    /// 通用颜色字符串解析，覆盖 W3C CSS Color Module Level 4 规范里的主要记法：
+    /// 十六进制（`#rgb`/`#rgba`/`#rrggbb`/`#rrggbbaa`，`#` 可省略）、函数记法
+    /// `rgb()`/`rgba()`/`hsl()`/`hsla()`/`hwb()`（参数逗号或空格分隔，颜色通道接受
+    /// 整数或百分比，可选 `/ alpha` 后缀）、以及 CSS/SVG 命名颜色。按开头字符分发：
+    /// `#` 前缀、`xxx(` 前缀，其余的要么是裸十六进制数字串，要么查命名颜色表
+    pub fn parse(s: &str) -> Result<Color, String> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err("Color string cannot be empty".to_string());
+        }
+
+        if let Some(hex) = trimmed.strip_prefix('#') {
+            return parse_hex_color(hex);
+        }
+
+        let lower = trimmed.to_lowercase();
+        if let Some(inner) = lower.strip_prefix("rgba(").and_then(|s| s.strip_suffix(')')) {
+            return parse_rgb_like(inner, true);
+        }
+        if let Some(inner) = lower.strip_prefix("rgb(").and_then(|s| s.strip_suffix(')')) {
+            return parse_rgb_like(inner, false);
+        }
+        if let Some(inner) = lower.strip_prefix("hsla(").and_then(|s| s.strip_suffix(')')) {
+            return parse_hsl_like(inner, true);
+        }
+        if let Some(inner) = lower.strip_prefix("hsl(").and_then(|s| s.strip_suffix(')')) {
+            return parse_hsl_like(inner, false);
+        }
+        if let Some(inner) = lower.strip_prefix("hwb(").and_then(|s| s.strip_suffix(')')) {
+            return parse_hwb(inner);
+        }
+
+        if is_bare_hex_digits(trimmed) && matches!(trimmed.len(), 3 | 4 | 6 | 8) {
+            return parse_hex_color(trimmed);
+        }
+
+        parse_named_color(trimmed)
+    }
+
+    /// 按名字查找 CSS/SVG 命名颜色（大小写不敏感，如 `"RebeccaPurple"`），包含
+    /// `transparent`。在排序好的 [`NAMED_COLORS`] 静态表上二分查找，而不是一长串 `match`
+    pub fn from_name(name: &str) -> Option<Color> {
+        named_color_rgba(name).map(|[r, g, b, a]| Color { r, g, b, a })
+    }
+
+    /// RGB -> HSL：返回 `(h, s, l)`，`h` 是角度 `[0, 360)`，`s`/`l` 是 `[0, 1]` 的小数；
+    /// alpha 不参与换算
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+
+        if (max - min).abs() < f32::EPSILON {
+            return (0.0, 0.0, l);
+        }
+
+        let d = max - min;
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        };
+
+        (h, s, l)
+    }
+
+    /// HSL -> RGB，`alpha` 单独传入（HSL 本身不带 alpha 通道）
+    pub fn from_hsl(h: f32, s: f32, l: f32, alpha: u8) -> Color {
+        hsl_to_color(h.rem_euclid(360.0), s.clamp(0.0, 1.0), l.clamp(0.0, 1.0), alpha)
+    }
+
+    /// 转到 HSL，把亮度 `l` 往 1 方向推 `amount`（`[0, 1]`）再转回来，色相/饱和度不变
+    pub fn lighten(&self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h, s, (l + amount).clamp(0.0, 1.0), self.a)
+    }
+
+    /// 转到 HSL，把亮度 `l` 往 0 方向推 `amount`（`[0, 1]`）再转回来，色相/饱和度不变
+    pub fn darken(&self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h, s, (l - amount).clamp(0.0, 1.0), self.a)
+    }
+
+    /// 转到 HSL，把饱和度 `s` 往 1 方向推 `amount`（`[0, 1]`）再转回来
+    pub fn saturate(&self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h, (s + amount).clamp(0.0, 1.0), l, self.a)
+    }
+
+    /// 转到 HSL，把饱和度 `s` 往 0 方向推 `amount`（`[0, 1]`）再转回来
+    pub fn desaturate(&self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h, (s - amount).clamp(0.0, 1.0), l, self.a)
+    }
+
+    /// 和 `other` 按系数 `t`（`[0, 1]`，0 取 `self`，1 取 `other`）逐通道线性插值，
+    /// alpha 通道也一起插值
+    pub fn mix(&self, other: &Color, t: f32) -> Color {
+        let lerp = |a: u8, b: u8| -> u8 {
+            (a as f32 + (b as f32 - a as f32) * t).round().clamp(0.0, 255.0) as u8
+        };
+        Color {
+            r: lerp(self.r, other.r),
+            g: lerp(self.g, other.g),
+            b: lerp(self.b, other.b),
+            a: lerp(self.a, other.a),
+        }
+    }
+}
+
+/// 判断字符串是否只由十六进制数字组成（用于识别不带 `#` 前缀的裸十六进制颜色）
+fn is_bare_hex_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 把 `"r g b / a"`/`"r, g, b, a"` 之类的参数列表从可选的 `/ alpha` 后缀处拆开，
+/// 返回 `(通道部分, alpha 部分)`；没有 `/` 就整段都是通道部分
+fn split_alpha_slash(s: &str) -> (&str, Option<String>) {
+    match s.rsplit_once('/') {
+        Some((main, alpha)) => (main.trim(), Some(alpha.trim().to_string())),
+        None => (s.trim(), None),
+    }
+}
+
+/// 把函数记法的参数列表拆成若干个去掉首尾空白的片段：含逗号就按逗号分隔（CSS2 legacy
+/// 语法），否则按空白分隔（CSS4 语法）
+fn split_color_args(s: &str) -> Vec<String> {
+    if s.contains(',') {
+        s.split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect()
+    } else {
+        s.split_whitespace().map(|p| p.to_string()).collect()
+    }
+}
+
+/// 解析 `rgb()`/`rgba()` 里的单个颜色通道：百分比按 0%-100% 换算到 0-255，
+/// 否则当作已经是 0-255 的数字，四舍五入并裁剪到合法范围
+fn parse_rgb_channel(value: &str) -> Result<u8, String> {
+    if let Some(pct) = value.strip_suffix('%') {
+        pct.parse::<f32>()
+            .map(|v| (v.clamp(0.0, 100.0) / 100.0 * 255.0).round() as u8)
+            .map_err(|_| format!("Invalid color channel: {}", value))
+    } else {
+        value
+            .parse::<f32>()
+            .map(|v| v.round().clamp(0.0, 255.0) as u8)
+            .map_err(|_| format!("Invalid color channel: {}", value))
+    }
+}
+
+/// 解析 alpha 分量：百分比按 0%-100% 换算，否则当作 [0, 1] 的小数，都换算成 0-255 整数
+fn parse_alpha(value: &str) -> Result<u8, String> {
+    if let Some(pct) = value.strip_suffix('%') {
+        pct.parse::<f32>()
+            .map(|v| (v.clamp(0.0, 100.0) / 100.0 * 255.0).round() as u8)
+            .map_err(|_| format!("Invalid alpha value: {}", value))
+    } else {
+        value
+            .parse::<f32>()
+            .map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .map_err(|_| format!("Invalid alpha value: {}", value))
+    }
+}
+
+/// 解析色相（角度，对 360 取模，允许负数）
+fn parse_hue(value: &str) -> Result<f32, String> {
+    value
+        .parse::<f32>()
+        .map(|v| v.rem_euclid(360.0))
+        .map_err(|_| format!("Invalid hue value: {}", value))
+}
+
+/// 解析 `hsl()`/`hsla()`/`hwb()` 里的饱和度/亮度/白度/黑度：带 `%` 后缀就除以 100，
+/// 不带就当作已经是 [0, 1] 的小数，最后裁剪到 [0, 1]
+fn parse_percent_unit(value: &str) -> Result<f32, String> {
+    let (raw, is_percent) = match value.strip_suffix('%') {
+        Some(rest) => (rest, true),
+        None => (value, false),
+    };
+    raw.parse::<f32>()
+        .map(|v| (if is_percent { v / 100.0 } else { v }).clamp(0.0, 1.0))
+        .map_err(|_| format!("Invalid percentage value: {}", value))
+}
+
+/// 解析 `rgb()`/`rgba()`：`has_legacy_alpha` 为 `true` 时按 `rgba()` 处理——没有
+/// `/ alpha` 后缀时第 4 个逗号/空格分隔的参数就是 alpha
+fn parse_rgb_like(inner: &str, has_legacy_alpha: bool) -> Result<Color, String> {
+    let (main, slash_alpha) = split_alpha_slash(inner);
+    let tokens = split_color_args(main);
+
+    if slash_alpha.is_some() {
+        if tokens.len() != 3 {
+            return Err(format!(
+                "rgb() expects 3 color channels before `/ alpha`, got {}: {}",
+                tokens.len(),
+                inner
+            ));
+        }
+    } else {
+        let expected = if has_legacy_alpha { 4 } else { 3 };
+        if tokens.len() != expected {
+            return Err(format!(
+                "{} expects {} arguments, got {}: {}",
+                if has_legacy_alpha { "rgba()" } else { "rgb()" },
+                expected,
+                tokens.len(),
+                inner
+            ));
+        }
+    }
+
+    let r = parse_rgb_channel(&tokens[0])?;
+    let g = parse_rgb_channel(&tokens[1])?;
+    let b = parse_rgb_channel(&tokens[2])?;
+    let a = match slash_alpha {
+        Some(alpha) => parse_alpha(&alpha)?,
+        None if has_legacy_alpha => parse_alpha(&tokens[3])?,
+        None => 255,
+    };
+
+    Ok(Color { r, g, b, a })
+}
+
+/// 解析 `hsl()`/`hsla()`：语义同 [`parse_rgb_like`]，只是通道换成色相/饱和度/亮度
+fn parse_hsl_like(inner: &str, has_legacy_alpha: bool) -> Result<Color, String> {
+    let (main, slash_alpha) = split_alpha_slash(inner);
+    let tokens = split_color_args(main);
+
+    if slash_alpha.is_some() {
+        if tokens.len() != 3 {
+            return Err(format!(
+                "hsl() expects 3 arguments before `/ alpha`, got {}: {}",
+                tokens.len(),
+                inner
+            ));
+        }
+    } else {
+        let expected = if has_legacy_alpha { 4 } else { 3 };
+        if tokens.len() != expected {
+            return Err(format!(
+                "{} expects {} arguments, got {}: {}",
+                if has_legacy_alpha { "hsla()" } else { "hsl()" },
+                expected,
+                tokens.len(),
+                inner
+            ));
+        }
+    }
+
+    let h = parse_hue(&tokens[0])?;
+    let s = parse_percent_unit(&tokens[1])?;
+    let l = parse_percent_unit(&tokens[2])?;
+    let a = match slash_alpha {
+        Some(alpha) => parse_alpha(&alpha)?,
+        None if has_legacy_alpha => parse_alpha(&tokens[3])?,
+        None => 255,
+    };
+
+    Ok(hsl_to_color(h, s, l, a))
+}
+
+/// 解析 `hwb(h w% b%)`：先按 `s=1, l=0.5` 走一遍 HSL->RGB 得到这个色相最“纯”的颜色，
+/// 再按白度/黑度往白色/黑色混合：`channel = channel*(1-w-b) + w*255`
+fn parse_hwb(inner: &str) -> Result<Color, String> {
+    let tokens = split_color_args(inner);
+    if tokens.len() != 3 {
+        return Err(format!(
+            "hwb() expects 3 arguments, got {}: {}",
+            tokens.len(),
+            inner
+        ));
+    }
+
+    let h = parse_hue(&tokens[0])?;
+    let w = parse_percent_unit(&tokens[1])?;
+    let b = parse_percent_unit(&tokens[2])?;
+
+    let base = hsl_to_color(h, 1.0, 0.5, 255);
+    let blend = |channel: u8| -> u8 {
+        (channel as f32 * (1.0 - w - b) + w * 255.0)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    };
+
+    Ok(Color {
+        r: blend(base.r),
+        g: blend(base.g),
+        b: blend(base.b),
+        a: 255,
+    })
+}
+
+/// HSL -> RGB：`h` 是角度（已经 mod 360），`s`/`l` 是 [0, 1] 的小数。按标准六区间
+/// 公式算出 `(r', g', b')` 再加上 `m` 还原到 [0, 1]，最后换算成 0-255 的整数通道
+fn hsl_to_color(h: f32, s: f32, l: f32, a: u8) -> Color {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - (((h / 60.0) % 2.0) - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r1, g1, b1) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let to_channel = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Color {
+        r: to_channel(r1),
+        g: to_channel(g1),
+        b: to_channel(b1),
+        a,
+    }
+}
+
+/// 解析十六进制颜色（不含 `#`），支持 `rgb`/`rgba`/`rrggbb`/`rrggbbaa` 四种长度
+fn parse_hex_color(hex: &str) -> Result<Color, String> {
+    let hex = hex.trim_start_matches('#');
+    let digit =
+        |s: &str| u8::from_str_radix(s, 16).map_err(|_| format!("Invalid hex color: #{}", hex));
+
+    match hex.len() {
+        3 => {
+            let r = digit(&hex[0..1].repeat(2))?;
+            let g = digit(&hex[1..2].repeat(2))?;
+            let b = digit(&hex[2..3].repeat(2))?;
+            Ok(Color { r, g, b, a: 255 })
+        }
+        4 => {
+            let r = digit(&hex[0..1].repeat(2))?;
+            let g = digit(&hex[1..2].repeat(2))?;
+            let b = digit(&hex[2..3].repeat(2))?;
+            let a = digit(&hex[3..4].repeat(2))?;
+            Ok(Color { r, g, b, a })
+        }
+        6 => {
+            let r = digit(&hex[0..2])?;
+            let g = digit(&hex[2..4])?;
+            let b = digit(&hex[4..6])?;
+            Ok(Color { r, g, b, a: 255 })
+        }
+        8 => {
+            let r = digit(&hex[0..2])?;
+            let g = digit(&hex[2..4])?;
+            let b = digit(&hex[4..6])?;
+            let a = digit(&hex[6..8])?;
+            Ok(Color { r, g, b, a })
+        }
+        _ => Err(format!("Invalid hex color format: #{}", hex)),
+    }
+}
+
+/// 解析命名颜色（大小写不敏感），委托给 [`Color::from_name`]
+fn parse_named_color(name: &str) -> Result<Color, String> {
+    Color::from_name(name).ok_or_else(|| format!("Unknown color name: {}", name))
+}
+
+/// CSS Color Module / SVG 标准命名颜色表（含 `transparent`），按名字的字节序排好，
+/// 供 [`Color::from_name`] 二分查找；不再用一长串 `match`
+static NAMED_COLORS: &[(&str, [u8; 4])] = &[
+    ("aliceblue", [240, 248, 255, 255]),
+    ("antiquewhite", [250, 235, 215, 255]),
+    ("aqua", [0, 255, 255, 255]),
+    ("aquamarine", [127, 255, 212, 255]),
+    ("azure", [240, 255, 255, 255]),
+    ("beige", [245, 245, 220, 255]),
+    ("bisque", [255, 228, 196, 255]),
+    ("black", [0, 0, 0, 255]),
+    ("blanchedalmond", [255, 235, 205, 255]),
+    ("blue", [0, 0, 255, 255]),
+    ("blueviolet", [138, 43, 226, 255]),
+    ("brown", [165, 42, 42, 255]),
+    ("burlywood", [222, 184, 135, 255]),
+    ("cadetblue", [95, 158, 160, 255]),
+    ("chartreuse", [127, 255, 0, 255]),
+    ("chocolate", [210, 105, 30, 255]),
+    ("coral", [255, 127, 80, 255]),
+    ("cornflowerblue", [100, 149, 237, 255]),
+    ("cornsilk", [255, 248, 220, 255]),
+    ("crimson", [220, 20, 60, 255]),
+    ("cyan", [0, 255, 255, 255]),
+    ("darkblue", [0, 0, 139, 255]),
+    ("darkcyan", [0, 139, 139, 255]),
+    ("darkgoldenrod", [184, 134, 11, 255]),
+    ("darkgray", [169, 169, 169, 255]),
+    ("darkgreen", [0, 100, 0, 255]),
+    ("darkgrey", [169, 169, 169, 255]),
+    ("darkkhaki", [189, 183, 107, 255]),
+    ("darkmagenta", [139, 0, 139, 255]),
+    ("darkolivegreen", [85, 107, 47, 255]),
+    ("darkorange", [255, 140, 0, 255]),
+    ("darkorchid", [153, 50, 204, 255]),
+    ("darkred", [139, 0, 0, 255]),
+    ("darksalmon", [233, 150, 122, 255]),
+    ("darkseagreen", [143, 188, 143, 255]),
+    ("darkslateblue", [72, 61, 139, 255]),
+    ("darkslategray", [47, 79, 79, 255]),
+    ("darkslategrey", [47, 79, 79, 255]),
+    ("darkturquoise", [0, 206, 209, 255]),
+    ("darkviolet", [148, 0, 211, 255]),
+    ("deeppink", [255, 20, 147, 255]),
+    ("deepskyblue", [0, 191, 255, 255]),
+    ("dimgray", [105, 105, 105, 255]),
+    ("dimgrey", [105, 105, 105, 255]),
+    ("dodgerblue", [30, 144, 255, 255]),
+    ("firebrick", [178, 34, 34, 255]),
+    ("floralwhite", [255, 250, 240, 255]),
+    ("forestgreen", [34, 139, 34, 255]),
+    ("fuchsia", [255, 0, 255, 255]),
+    ("gainsboro", [220, 220, 220, 255]),
+    ("ghostwhite", [248, 248, 255, 255]),
+    ("gold", [255, 215, 0, 255]),
+    ("goldenrod", [218, 165, 32, 255]),
+    ("gray", [128, 128, 128, 255]),
+    ("green", [0, 128, 0, 255]),
+    ("greenyellow", [173, 255, 47, 255]),
+    ("grey", [128, 128, 128, 255]),
+    ("honeydew", [240, 255, 240, 255]),
+    ("hotpink", [255, 105, 180, 255]),
+    ("indianred", [205, 92, 92, 255]),
+    ("indigo", [75, 0, 130, 255]),
+    ("ivory", [255, 255, 240, 255]),
+    ("khaki", [240, 230, 140, 255]),
+    ("lavender", [230, 230, 250, 255]),
+    ("lavenderblush", [255, 240, 245, 255]),
+    ("lawngreen", [124, 252, 0, 255]),
+    ("lemonchiffon", [255, 250, 205, 255]),
+    ("lightblue", [173, 216, 230, 255]),
+    ("lightcoral", [240, 128, 128, 255]),
+    ("lightcyan", [224, 255, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210, 255]),
+    ("lightgray", [211, 211, 211, 255]),
+    ("lightgreen", [144, 238, 144, 255]),
+    ("lightgrey", [211, 211, 211, 255]),
+    ("lightpink", [255, 182, 193, 255]),
+    ("lightsalmon", [255, 160, 122, 255]),
+    ("lightseagreen", [32, 178, 170, 255]),
+    ("lightskyblue", [135, 206, 250, 255]),
+    ("lightslategray", [119, 136, 153, 255]),
+    ("lightslategrey", [119, 136, 153, 255]),
+    ("lightsteelblue", [176, 196, 222, 255]),
+    ("lightyellow", [255, 255, 224, 255]),
+    ("lime", [0, 255, 0, 255]),
+    ("limegreen", [50, 205, 50, 255]),
+    ("linen", [250, 240, 230, 255]),
+    ("magenta", [255, 0, 255, 255]),
+    ("maroon", [128, 0, 0, 255]),
+    ("mediumaquamarine", [102, 205, 170, 255]),
+    ("mediumblue", [0, 0, 205, 255]),
+    ("mediumorchid", [186, 85, 211, 255]),
+    ("mediumpurple", [147, 112, 219, 255]),
+    ("mediumseagreen", [60, 179, 113, 255]),
+    ("mediumslateblue", [123, 104, 238, 255]),
+    ("mediumspringgreen", [0, 250, 154, 255]),
+    ("mediumturquoise", [72, 209, 204, 255]),
+    ("mediumvioletred", [199, 21, 133, 255]),
+    ("midnightblue", [25, 25, 112, 255]),
+    ("mintcream", [245, 255, 250, 255]),
+    ("mistyrose", [255, 228, 225, 255]),
+    ("moccasin", [255, 228, 181, 255]),
+    ("navajowhite", [255, 222, 173, 255]),
+    ("navy", [0, 0, 128, 255]),
+    ("oldlace", [253, 245, 230, 255]),
+    ("olive", [128, 128, 0, 255]),
+    ("olivedrab", [107, 142, 35, 255]),
+    ("orange", [255, 165, 0, 255]),
+    ("orangered", [255, 69, 0, 255]),
+    ("orchid", [218, 112, 214, 255]),
+    ("palegoldenrod", [238, 232, 170, 255]),
+    ("palegreen", [152, 251, 152, 255]),
+    ("paleturquoise", [175, 238, 238, 255]),
+    ("palevioletred", [219, 112, 147, 255]),
+    ("papayawhip", [255, 239, 213, 255]),
+    ("peachpuff", [255, 218, 185, 255]),
+    ("peru", [205, 133, 63, 255]),
+    ("pink", [255, 192, 203, 255]),
+    ("plum", [221, 160, 221, 255]),
+    ("powderblue", [176, 224, 230, 255]),
+    ("purple", [128, 0, 128, 255]),
+    ("rebeccapurple", [102, 51, 153, 255]),
+    ("red", [255, 0, 0, 255]),
+    ("rosybrown", [188, 143, 143, 255]),
+    ("royalblue", [65, 105, 225, 255]),
+    ("saddlebrown", [139, 69, 19, 255]),
+    ("salmon", [250, 128, 114, 255]),
+    ("sandybrown", [244, 164, 96, 255]),
+    ("seagreen", [46, 139, 87, 255]),
+    ("seashell", [255, 245, 238, 255]),
+    ("sienna", [160, 82, 45, 255]),
+    ("silver", [192, 192, 192, 255]),
+    ("skyblue", [135, 206, 235, 255]),
+    ("slateblue", [106, 90, 205, 255]),
+    ("slategray", [112, 128, 144, 255]),
+    ("slategrey", [112, 128, 144, 255]),
+    ("snow", [255, 250, 250, 255]),
+    ("springgreen", [0, 255, 127, 255]),
+    ("steelblue", [70, 130, 180, 255]),
+    ("tan", [210, 180, 140, 255]),
+    ("teal", [0, 128, 128, 255]),
+    ("thistle", [216, 191, 216, 255]),
+    ("tomato", [255, 99, 71, 255]),
+    ("transparent", [0, 0, 0, 0]),
+    ("turquoise", [64, 224, 208, 255]),
+    ("violet", [238, 130, 238, 255]),
+    ("wheat", [245, 222, 179, 255]),
+    ("white", [255, 255, 255, 255]),
+    ("whitesmoke", [245, 245, 245, 255]),
+    ("yellow", [255, 255, 0, 255]),
+    ("yellowgreen", [154, 205, 50, 255]),
+];
+
+/// 在 [`NAMED_COLORS`] 里二分查找大小写不敏感的命名颜色
+fn named_color_rgba(name: &str) -> Option<[u8; 4]> {
+    let lower = name.to_lowercase();
+    NAMED_COLORS
+        .binary_search_by(|(n, _)| n.cmp(&lower.as_str()))
+        .ok()
+        .map(|idx| NAMED_COLORS[idx].1)
 }
 
 /// 尺寸定义
@@ -46,6 +585,56 @@ pub struct Size {
     pub height: f32,
 }
 
+impl Hash for Size {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.width, state);
+        hash_f32(self.height, state);
+    }
+}
+
+/// druid 风格的盒约束：父容器沿树自顶向下传递给子元素的可用尺寸范围，子元素上报的
+/// 尺寸应当落在 `[min, max]` 之内。用于在求解前为子元素（文本换行宽度、容器自身尺寸）
+/// 推导出比画布尺寸更紧的边界
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxConstraints {
+    pub min: Size,
+    pub max: Size,
+}
+
+impl BoxConstraints {
+    pub const UNBOUNDED: BoxConstraints = BoxConstraints {
+        min: Size { width: 0.0, height: 0.0 },
+        max: Size { width: f32::INFINITY, height: f32::INFINITY },
+    };
+
+    /// 最小尺寸等于最大尺寸的"紧"约束，常用作根（画布）约束
+    pub fn tight(size: Size) -> Self {
+        Self { min: size, max: size }
+    }
+
+    /// 把 `size` 夹到当前约束的 `[min, max]` 范围内
+    pub fn clamp(&self, size: Size) -> Size {
+        Size {
+            width: size.width.clamp(self.min.width, self.max.width),
+            height: size.height.clamp(self.min.height, self.max.height),
+        }
+    }
+
+    /// 与另一组约束取交集（收紧）：min 取更大者，max 取更小者
+    pub fn tighten_with(&self, other: &BoxConstraints) -> Self {
+        Self {
+            min: Size {
+                width: self.min.width.max(other.min.width),
+                height: self.min.height.max(other.min.height),
+            },
+            max: Size {
+                width: self.max.width.min(other.max.width),
+                height: self.max.height.min(other.max.height),
+            },
+        }
+    }
+}
+
 /// 位置定义
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Point {
@@ -53,8 +642,15 @@ pub struct Point {
     pub y: f32,
 }
 
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.x, state);
+        hash_f32(self.y, state);
+    }
+}
+
 /// 矩形定义
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
 pub struct Rect {
     pub origin: Point,
     pub size: Size,
@@ -69,18 +665,63 @@ pub struct Padding {
     pub right: f32,
 }
 
+impl Hash for Padding {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.top, state);
+        hash_f32(self.bottom, state);
+        hash_f32(self.left, state);
+        hash_f32(self.right, state);
+    }
+}
+
 impl Padding {
     pub fn all(value: f32) -> Self {
         Self { top: value, bottom: value, left: value, right: value }
     }
-    
+
     pub fn symmetric(vertical: f32, horizontal: f32) -> Self {
         Self { top: vertical, bottom: vertical, left: horizontal, right: horizontal }
     }
 }
 
-/// 文本对齐方式
+/// 外边距定义：和 [`Padding`] 形状完全相同，但作用方向相反——`padding` 向内收紧元素
+/// 自己内容的可用空间，`margin` 向外把元素从它在父级里分到的那块位置再收进去一圈，
+/// 腾出和兄弟节点/父容器边缘之间的间隙
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Margin {
+    pub top: f32,
+    pub bottom: f32,
+    pub left: f32,
+    pub right: f32,
+}
+
+impl Hash for Margin {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.top, state);
+        hash_f32(self.bottom, state);
+        hash_f32(self.left, state);
+        hash_f32(self.right, state);
+    }
+}
+
+impl Margin {
+    pub fn all(value: f32) -> Self {
+        Self { top: value, bottom: value, left: value, right: value }
+    }
+
+    pub fn symmetric(vertical: f32, horizontal: f32) -> Self {
+        Self { top: vertical, bottom: vertical, left: horizontal, right: horizontal }
+    }
+}
+
+impl Default for Margin {
+    fn default() -> Self {
+        Self::all(0.0)
+    }
+}
+
+/// 文本对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
 #[derive(Default)]
 pub enum TextAlignment {
     #[default]
@@ -92,7 +733,7 @@ pub enum TextAlignment {
 
 
 /// 字体粗细
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
 #[derive(Default)]
 pub enum FontWeight {
     Light,
@@ -102,8 +743,21 @@ pub enum FontWeight {
 }
 
 
+/// 字体样式：决定渲染时选用的字形变体（常规/斜体/粗体/粗斜体），驱动字体缓存键
+/// 和系统字体匹配，和控制整体字重的 [`FontWeight`] 是两个独立维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[derive(Default)]
+pub enum FontStyle {
+    #[default]
+    Regular,
+    Italic,
+    Bold,
+    BoldItalic,
+}
+
+
 /// 换行模式
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub enum LineBreakMode {
     #[default]
     WordWrap,
@@ -115,7 +769,7 @@ pub enum LineBreakMode {
 }
 
 /// 图片缩放模式
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
 #[derive(Default)]
 pub enum ScaleMode {
     #[default]
@@ -125,9 +779,48 @@ pub enum ScaleMode {
     Center,
 }
 
+/// SVG `preserveAspectRatio` 的九宫格对齐：第一个 `X` 分量决定水平方向贴哪条边
+/// （或居中），第二个 `Y` 分量决定垂直方向，对应 SVG 规范里的 `xMinYMin`..`xMaxYMax`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum Align9 {
+    XMinYMin,
+    XMidYMin,
+    XMaxYMin,
+    XMinYMid,
+    XMidYMid,
+    XMaxYMid,
+    XMinYMax,
+    XMidYMax,
+    XMaxYMax,
+}
+
+/// SVG `preserveAspectRatio` 的 `meetOrSlice`：`Meet` 等比缩放到完全落在目标框内
+/// （可能留白），`Slice` 等比缩放到完全盖满目标框（可能溢出裁切）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum MeetOrSlice {
+    Meet,
+    Slice,
+}
+
+/// 解析后的 SVG `preserveAspectRatio`：`align` 为 `None` 对应规范里的 `none`
+/// 关键字，表示不保持宽高比、非均匀拉伸填满目标框（此时 `mode` 被忽略）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct PreserveAspectRatio {
+    pub align: Option<Align9>,
+    pub mode: MeetOrSlice,
+}
+
+impl Default for PreserveAspectRatio {
+    fn default() -> Self {
+        Self {
+            align: Some(Align9::XMidYMid),
+            mode: MeetOrSlice::Meet,
+        }
+    }
+}
 
 /// 对齐方式
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
 #[derive(Default)]
 pub enum Alignment {
     Leading,
@@ -142,7 +835,7 @@ pub enum Alignment {
 
 
 /// 分布方式
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
 #[derive(Default)]
 pub enum Distribution {
     #[default]
@@ -154,15 +847,18 @@ pub enum Distribution {
 }
 
 
-/// 约束优先级
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
-#[repr(u32)]
+/// 约束优先级。四个固定档位覆盖大多数场景；当多个同档位的软约束互相竞争
+/// （例如首选尺寸 vs. 均分 vs. 对齐），用 `Custom` 携带一个原始的 Cassowary
+/// 强度，绕过档位直接参与求解
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
 pub enum Priority {
-    Required = 1000,
-    High = 750,
+    Required,
+    High,
     #[default]
-    Medium = 500,
-    Low = 250,
+    Medium,
+    Low,
+    /// 原始 Cassowary 强度，会被直接传给 `add_constraint`，不再做档位换算
+    Custom(f64),
 }
 
 impl Priority {
@@ -172,6 +868,16 @@ impl Priority {
             Priority::High => 750,
             Priority::Medium => 500,
             Priority::Low => 250,
+            Priority::Custom(strength) => *strength as u32,
+        }
+    }
+}
+
+impl Hash for Priority {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Priority::Custom(strength) = self {
+            hash_f64(*strength, state);
         }
     }
 }
@@ -185,6 +891,43 @@ pub enum SizeConstraint {
     Relative { target: ElementId, multiplier: f32 },
 }
 
+impl Hash for SizeConstraint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            SizeConstraint::Fixed(value) => hash_f32(*value, state),
+            SizeConstraint::Auto => {}
+            SizeConstraint::Percentage(value) => hash_f32(*value, state),
+            SizeConstraint::Relative { target, multiplier } => {
+                target.hash(state);
+                hash_f32(*multiplier, state);
+            }
+        }
+    }
+}
+
+/// 网格轨道（行/列）尺寸类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GridTrack {
+    /// 固定尺寸
+    Fixed(f32),
+    /// 相对于网格自身宽/高的百分比
+    Percent(f32),
+    /// 弹性轨道，按权重分配 Fixed/Percent 轨道占用后剩余的空间（类似 CSS `fr` 单位）
+    Fraction(f32),
+}
+
+impl Hash for GridTrack {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            GridTrack::Fixed(value) => hash_f32(*value, state),
+            GridTrack::Percent(value) => hash_f32(*value, state),
+            GridTrack::Fraction(value) => hash_f32(*value, state),
+        }
+    }
+}
+
 /// 约束类型
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ConstraintType {
@@ -204,7 +947,13 @@ pub enum ConstraintType {
     MaxWidth { value: f32 },
     MinHeight { value: f32 },
     MaxHeight { value: f32 },
-    
+
+    // 弹性约束：子元素在 VStack/HStack 内按权重分配剩余空间（类似 flex-grow）
+    Fill { weight: f32 },
+
+    // 网格约束：子元素在 Grid 内占据的行/列（从 0 开始），可选跨行/跨列
+    GridPosition { row: u32, col: u32, row_span: u32, col_span: u32 },
+
     // 对齐约束
     AlignTop { target: ElementId },
     AlignBottom { target: ElementId },
@@ -213,8 +962,51 @@ pub enum ConstraintType {
     AlignBaseline { target: ElementId },
 }
 
+impl Hash for ConstraintType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            ConstraintType::Top { target, value }
+            | ConstraintType::Bottom { target, value }
+            | ConstraintType::Leading { target, value }
+            | ConstraintType::Trailing { target, value } => {
+                target.hash(state);
+                hash_f32(*value, state);
+            }
+            ConstraintType::CenterX { target, offset } | ConstraintType::CenterY { target, offset } => {
+                target.hash(state);
+                hash_f32(*offset, state);
+            }
+            ConstraintType::Width { value, target, multiplier, percent }
+            | ConstraintType::Height { value, target, multiplier, percent } => {
+                value.map(f32::to_bits).hash(state);
+                target.hash(state);
+                hash_f32(*multiplier, state);
+                percent.map(f32::to_bits).hash(state);
+            }
+            ConstraintType::AspectRatio { ratio } => hash_f32(*ratio, state),
+            ConstraintType::MinWidth { value }
+            | ConstraintType::MaxWidth { value }
+            | ConstraintType::MinHeight { value }
+            | ConstraintType::MaxHeight { value } => hash_f32(*value, state),
+            ConstraintType::Fill { weight } => hash_f32(*weight, state),
+            ConstraintType::GridPosition { row, col, row_span, col_span } => {
+                row.hash(state);
+                col.hash(state);
+                row_span.hash(state);
+                col_span.hash(state);
+            }
+            ConstraintType::AlignTop { target }
+            | ConstraintType::AlignBottom { target }
+            | ConstraintType::AlignLeading { target }
+            | ConstraintType::AlignTrailing { target }
+            | ConstraintType::AlignBaseline { target } => target.hash(state),
+        }
+    }
+}
+
 /// 约束定义
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash)]
 pub struct Constraint {
     pub constraint_type: ConstraintType,
     pub priority: Priority,
@@ -234,11 +1026,87 @@ impl Constraint {
     }
 }
 
+/// 渲染完成之后施加在元素整个 `frame` 区域上的视觉滤镜，对标 librsvg 里的 SVG filter
+/// primitive：`feGaussianBlur`、`feDropShadow`、`feColorMatrix`。只挂在真正会画出像素的
+/// 元素属性（文本/图片/容器）上——堆叠、网格这类纯布局容器本身不渲染任何东西，挂了也没有
+/// 像素可以作用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Filter {
+    #[serde(rename = "gaussianBlur")]
+    GaussianBlur { std_deviation: f32 },
+    #[serde(rename = "dropShadow")]
+    DropShadow {
+        dx: f32,
+        dy: f32,
+        std_deviation: f32,
+        color: Color,
+    },
+    /// 4x5 矩阵，按 `out = M * [r, g, b, a, 1]` 逐像素应用，结果裁剪到 [0, 255]
+    #[serde(rename = "colorMatrix")]
+    ColorMatrix { values: [f32; 20] },
+}
+
+impl Hash for Filter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Filter::GaussianBlur { std_deviation } => hash_f32(*std_deviation, state),
+            Filter::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+            } => {
+                hash_f32(*dx, state);
+                hash_f32(*dy, state);
+                hash_f32(*std_deviation, state);
+                color.hash(state);
+            }
+            Filter::ColorMatrix { values } => {
+                for v in values {
+                    hash_f32(*v, state);
+                }
+            }
+        }
+    }
+}
+
+/// 文本装饰/修饰样式：记录粗体、斜体、下划线等开关位。每个字段用 `Option<bool>`
+/// 表达"未设置"而不是强制为 `false`，这样才能和基础样式做增量合并而不会把没提到
+/// 的修饰符意外关掉——和终端/TUI 样式结构体里的修饰符位集是同一个思路
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Hash)]
+pub struct TextStyle {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub dim: Option<bool>,
+    pub reverse: Option<bool>,
+    pub blink: Option<bool>,
+}
+
+impl TextStyle {
+    /// 用 `overrides` 里已设置（`Some`）的字段覆盖 `base`，未设置的字段保留 `base` 的值
+    pub fn merge(base: TextStyle, overrides: TextStyle) -> TextStyle {
+        TextStyle {
+            bold: overrides.bold.or(base.bold),
+            italic: overrides.italic.or(base.italic),
+            underline: overrides.underline.or(base.underline),
+            strikethrough: overrides.strikethrough.or(base.strikethrough),
+            dim: overrides.dim.or(base.dim),
+            reverse: overrides.reverse.or(base.reverse),
+            blink: overrides.blink.or(base.blink),
+        }
+    }
+}
+
 /// 文本属性
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextProperties {
     pub font_size: f32,
     pub font_weight: FontWeight,
+    pub font_style: FontStyle,
     pub font_family: String,
     pub color: Color,
     pub alignment: TextAlignment,
@@ -246,6 +1114,9 @@ pub struct TextProperties {
     pub letter_spacing: f32,
     pub max_lines: Option<u32>,
     pub line_break_mode: LineBreakMode,
+    pub filters: Vec<Filter>,
+    pub style: TextStyle,
+    pub margin: Margin,
 }
 
 impl Default for TextProperties {
@@ -253,6 +1124,7 @@ impl Default for TextProperties {
         Self {
             font_size: 16.0,
             font_weight: FontWeight::Normal,
+            font_style: FontStyle::Regular,
             font_family: "Arial".to_string(),
             color: Color::BLACK,
             alignment: TextAlignment::Leading,
@@ -260,6 +1132,62 @@ impl Default for TextProperties {
             letter_spacing: 0.0,
             max_lines: None,
             line_break_mode: LineBreakMode::WordWrap,
+            filters: Vec::new(),
+            style: TextStyle::default(),
+            margin: Margin::all(0.0),
+        }
+    }
+}
+
+impl Hash for TextProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.font_size, state);
+        self.font_weight.hash(state);
+        self.font_style.hash(state);
+        self.font_family.hash(state);
+        self.color.hash(state);
+        self.alignment.hash(state);
+        hash_f32(self.line_height, state);
+        hash_f32(self.letter_spacing, state);
+        self.max_lines.hash(state);
+        self.line_break_mode.hash(state);
+        self.filters.hash(state);
+        self.style.hash(state);
+        self.margin.hash(state);
+    }
+}
+
+/// 投影样式：`render_element` 在画元素本体之前先按这份样式画一层模糊、偏移过的
+/// 轮廓剪影，给容器/图片的背板做出悬浮的视觉深度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowStyle {
+    pub color: Color,
+    /// 高斯模糊的半径（像素），决定投影边缘的柔和程度
+    pub blur_radius: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// 投影整体不透明度，和 `color` 自身的 alpha 相乘
+    pub opacity: f32,
+}
+
+impl Hash for ShadowStyle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.color.hash(state);
+        hash_f32(self.blur_radius, state);
+        hash_f32(self.offset_x, state);
+        hash_f32(self.offset_y, state);
+        hash_f32(self.opacity, state);
+    }
+}
+
+impl Default for ShadowStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            blur_radius: 8.0,
+            offset_x: 0.0,
+            offset_y: 4.0,
+            opacity: 0.3,
         }
     }
 }
@@ -272,6 +1200,25 @@ pub struct ImageProperties {
     pub corner_radius: f32,
     pub opacity: f32,
     pub tint_color: Option<Color>,
+    pub shadow: Option<ShadowStyle>,
+    pub filters: Vec<Filter>,
+    /// 仅对 `.svg` 来源的图片生效；光栅图片继续沿用 `scale_mode`
+    pub preserve_aspect_ratio: PreserveAspectRatio,
+    pub margin: Margin,
+}
+
+impl Hash for ImageProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.scale_mode.hash(state);
+        self.aspect_ratio.map(f32::to_bits).hash(state);
+        hash_f32(self.corner_radius, state);
+        hash_f32(self.opacity, state);
+        self.tint_color.hash(state);
+        self.shadow.hash(state);
+        self.filters.hash(state);
+        self.preserve_aspect_ratio.hash(state);
+        self.margin.hash(state);
+    }
 }
 
 impl Default for ImageProperties {
@@ -282,6 +1229,112 @@ impl Default for ImageProperties {
             corner_radius: 0.0,
             opacity: 1.0,
             tint_color: None,
+            shadow: None,
+            filters: Vec::new(),
+            preserve_aspect_ratio: PreserveAspectRatio::default(),
+            margin: Margin::all(0.0),
+        }
+    }
+}
+
+/// 每个角各自的圆角半径，取代单一的 `corner_radius`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Corners {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_left: f32,
+    pub bottom_right: f32,
+}
+
+impl Corners {
+    pub fn all(value: f32) -> Self {
+        Self { top_left: value, top_right: value, bottom_left: value, bottom_right: value }
+    }
+
+    /// 给定一点相对矩形左上角的局部坐标，挑出它所在象限对应的圆角半径——渲染层的
+    /// 距离场函数按这个半径当作"如果四角都是这个半径"来算，这是 per-corner 圆角矩形
+    /// 距离场的标准近似：每个像素只关心自己所在那个角
+    pub fn radius_for(&self, local_x: f32, local_y: f32, width: f32, height: f32) -> f32 {
+        match (local_y < height / 2.0, local_x < width / 2.0) {
+            (true, true) => self.top_left,
+            (true, false) => self.top_right,
+            (false, true) => self.bottom_left,
+            (false, false) => self.bottom_right,
+        }
+    }
+}
+
+impl Hash for Corners {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.top_left, state);
+        hash_f32(self.top_right, state);
+        hash_f32(self.bottom_left, state);
+        hash_f32(self.bottom_right, state);
+    }
+}
+
+impl Default for Corners {
+    fn default() -> Self {
+        Self::all(0.0)
+    }
+}
+
+/// 边框线型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Hash)]
+#[derive(Default)]
+pub enum BorderStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+/// 单条边的边框描述：宽度为 0 等价于这条边没有边框
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BorderSide {
+    pub width: f32,
+    pub color: Color,
+    pub style: BorderStyle,
+}
+
+impl BorderSide {
+    pub fn new(width: f32, color: Color) -> Self {
+        Self { width, color, style: BorderStyle::Solid }
+    }
+}
+
+impl Hash for BorderSide {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.width, state);
+        self.color.hash(state);
+        self.style.hash(state);
+    }
+}
+
+impl Default for BorderSide {
+    fn default() -> Self {
+        Self::new(0.0, Color::BLACK)
+    }
+}
+
+/// 四条边各自独立的边框，取代单一的 `border_width`/`border_color`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Default)]
+pub struct Border {
+    pub top: BorderSide,
+    pub right: BorderSide,
+    pub bottom: BorderSide,
+    pub left: BorderSide,
+}
+
+impl Border {
+    /// 四条边共用同一种宽度/颜色/线型，对应旧版单一 `border_width`/`border_color` 的语义
+    pub fn uniform(width: f32, color: Color) -> Self {
+        let side = BorderSide::new(width, color);
+        Self {
+            top: side.clone(),
+            right: side.clone(),
+            bottom: side.clone(),
+            left: side,
         }
     }
 }
@@ -290,22 +1343,39 @@ impl Default for ImageProperties {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContainerProperties {
     pub background: Color,
-    pub corner_radius: f32,
-    pub border_width: f32,
-    pub border_color: Color,
+    pub corners: Corners,
+    pub border: Border,
     pub opacity: f32,
     pub padding: Padding,
+    pub margin: Margin,
+    pub shadow: Option<ShadowStyle>,
+    pub filters: Vec<Filter>,
+}
+
+impl Hash for ContainerProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.background.hash(state);
+        self.corners.hash(state);
+        self.border.hash(state);
+        hash_f32(self.opacity, state);
+        self.padding.hash(state);
+        self.margin.hash(state);
+        self.shadow.hash(state);
+        self.filters.hash(state);
+    }
 }
 
 impl Default for ContainerProperties {
     fn default() -> Self {
         Self {
             background: Color::TRANSPARENT,
-            corner_radius: 0.0,
-            border_width: 0.0,
-            border_color: Color::BLACK,
+            corners: Corners::all(0.0),
+            border: Border::default(),
             opacity: 1.0,
             padding: Padding::all(0.0),
+            margin: Margin::all(0.0),
+            shadow: None,
+            filters: Vec::new(),
         }
     }
 }
@@ -316,6 +1386,51 @@ pub struct StackProperties {
     pub spacing: f32,
     pub alignment: Alignment,
     pub distribution: Distribution,
+    /// 容器内边距，沿主轴收进第一个/最后一个子元素，沿交叉轴收进对齐边缘
+    pub padding: Padding,
+    pub margin: Margin,
+}
+
+impl Hash for StackProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.spacing, state);
+        self.alignment.hash(state);
+        self.distribution.hash(state);
+        self.padding.hash(state);
+        self.margin.hash(state);
+    }
+}
+
+/// 网格属性
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridProperties {
+    pub rows: Vec<GridTrack>,
+    pub cols: Vec<GridTrack>,
+    pub row_spacing: f32,
+    pub col_spacing: f32,
+    pub margin: Margin,
+}
+
+impl Default for GridProperties {
+    fn default() -> Self {
+        Self {
+            rows: vec![GridTrack::Fraction(1.0)],
+            cols: vec![GridTrack::Fraction(1.0)],
+            row_spacing: 0.0,
+            col_spacing: 0.0,
+            margin: Margin::all(0.0),
+        }
+    }
+}
+
+impl Hash for GridProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rows.hash(state);
+        self.cols.hash(state);
+        hash_f32(self.row_spacing, state);
+        hash_f32(self.col_spacing, state);
+        self.margin.hash(state);
+    }
 }
 
 impl Default for StackProperties {
@@ -324,6 +1439,8 @@ impl Default for StackProperties {
             spacing: 0.0,
             alignment: Alignment::Center,
             distribution: Distribution::Fill,
+            padding: Padding::all(0.0),
+            margin: Margin::all(0.0),
         }
     }
 }
@@ -367,6 +1484,12 @@ pub enum Element {
         constraints: Vec<Constraint>,
         children: Vec<Element>,
     },
+    Grid {
+        id: ElementId,
+        properties: GridProperties,
+        constraints: Vec<Constraint>,
+        children: Vec<Element>,
+    },
     Spacer {
         id: ElementId,
         min_length: f32,
@@ -375,6 +1498,52 @@ pub enum Element {
     },
 }
 
+impl Hash for Element {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Element::Text { id, content, properties, constraints } => {
+                id.hash(state);
+                content.hash(state);
+                properties.hash(state);
+                constraints.hash(state);
+            }
+            Element::Image { id, source, properties, constraints } => {
+                id.hash(state);
+                source.hash(state);
+                properties.hash(state);
+                constraints.hash(state);
+            }
+            Element::Container { id, properties, constraints, children } => {
+                id.hash(state);
+                properties.hash(state);
+                constraints.hash(state);
+                children.hash(state);
+            }
+            Element::VStack { id, properties, constraints, children }
+            | Element::HStack { id, properties, constraints, children }
+            | Element::ZStack { id, properties, constraints, children } => {
+                id.hash(state);
+                properties.hash(state);
+                constraints.hash(state);
+                children.hash(state);
+            }
+            Element::Grid { id, properties, constraints, children } => {
+                id.hash(state);
+                properties.hash(state);
+                constraints.hash(state);
+                children.hash(state);
+            }
+            Element::Spacer { id, min_length, priority, constraints } => {
+                id.hash(state);
+                hash_f32(*min_length, state);
+                priority.hash(state);
+                constraints.hash(state);
+            }
+        }
+    }
+}
+
 impl Element {
     pub fn id(&self) -> &ElementId {
         match self {
@@ -384,10 +1553,11 @@ impl Element {
             Element::VStack { id, .. } => id,
             Element::HStack { id, .. } => id,
             Element::ZStack { id, .. } => id,
+            Element::Grid { id, .. } => id,
             Element::Spacer { id, .. } => id,
         }
     }
-    
+
     pub fn constraints(&self) -> &Vec<Constraint> {
         match self {
             Element::Text { constraints, .. } => constraints,
@@ -396,19 +1566,202 @@ impl Element {
             Element::VStack { constraints, .. } => constraints,
             Element::HStack { constraints, .. } => constraints,
             Element::ZStack { constraints, .. } => constraints,
+            Element::Grid { constraints, .. } => constraints,
             Element::Spacer { constraints, .. } => constraints,
         }
     }
-    
+
     pub fn children(&self) -> Option<&Vec<Element>> {
         match self {
             Element::Container { children, .. } => Some(children),
             Element::VStack { children, .. } => Some(children),
             Element::HStack { children, .. } => Some(children),
             Element::ZStack { children, .. } => Some(children),
+            Element::Grid { children, .. } => Some(children),
             _ => None,
         }
     }
+
+    /// 元素自己的外边距：决定布局阶段把它从分到的那块位置再向内收进去多少。
+    /// `Spacer` 本身就是用来占位的间隔，没有外边距的概念，固定返回 0
+    pub fn margin(&self) -> Margin {
+        match self {
+            Element::Text { properties, .. } => properties.margin,
+            Element::Image { properties, .. } => properties.margin,
+            Element::Container { properties, .. } => properties.margin,
+            Element::VStack { properties, .. }
+            | Element::HStack { properties, .. }
+            | Element::ZStack { properties, .. } => properties.margin,
+            Element::Grid { properties, .. } => properties.margin,
+            Element::Spacer { .. } => Margin::all(0.0),
+        }
+    }
+
+    /// 两段式内在尺寸推导的第一段（自底向上）：在给定 `available`（通常是从
+    /// [`Canvas`] 或父容器逐层向下收紧下来的可用空间，只用来约束文本换行宽度/图片
+    /// 的长宽比换算，不代表元素必须占满它）下，算出这个元素自己需要的最小尺寸。
+    /// 第二段（自顶向下按约束把剩余可用空间分配下去）由
+    /// `LayoutSolver::solve_layout` 负责。
+    ///
+    /// `layout.rs` 是不依赖字体/图像解码库的纯数据模型，这里的文本宽度只是按平均
+    /// 字符宽度估算的近似值——像素精确的版本仍然由
+    /// `LayoutSolver::add_intrinsic_size_constraints` 基于真实字体重新测量并钉死。
+    pub fn intrinsic_size(&self, available: Size) -> Size {
+        match self {
+            Element::Text { content, properties, .. } => {
+                Self::text_intrinsic_size(content, properties, available)
+            }
+            Element::Image { properties, .. } => Self::image_intrinsic_size(properties, available),
+            Element::Container { properties, children, .. } => {
+                Self::overlay_intrinsic_size(children, &properties.padding, available)
+            }
+            Element::VStack { properties, children, .. } => {
+                Self::stack_intrinsic_size(children, properties, available, false)
+            }
+            Element::HStack { properties, children, .. } => {
+                Self::stack_intrinsic_size(children, properties, available, true)
+            }
+            Element::ZStack { properties, children, .. } => {
+                Self::overlay_intrinsic_size(children, &properties.padding, available)
+            }
+            // 网格轨道尺寸的解析依赖实际求解（`Fraction` 轨道要按剩余空间分配），
+            // 两段式的自底向上阶段没有足够信息算出精确的自然尺寸，退化为直接
+            // 沿用可用空间
+            Element::Grid { .. } => available,
+            Element::Spacer { min_length, .. } => Size {
+                width: *min_length,
+                height: *min_length,
+            },
+        }
+    }
+
+    /// 按平均字符宽度估算文本的自然尺寸：`LineBreakMode::WordWrap` 下用 `available.width`
+    /// 约束换行，其余模式视为单行
+    fn text_intrinsic_size(content: &str, properties: &TextProperties, available: Size) -> Size {
+        let avg_char_width = properties.font_size * 0.55;
+        let line_height = properties.font_size * properties.line_height;
+        let char_count = content.chars().count() as f32;
+
+        let (width, mut line_count) = if properties.line_break_mode == LineBreakMode::WordWrap
+            && available.width.is_finite()
+            && available.width > 0.0
+        {
+            let chars_per_line = (available.width / avg_char_width).floor().max(1.0) as usize;
+            let lines = Self::wrap_word_count(content, chars_per_line);
+            (
+                (char_count * avg_char_width).min(available.width),
+                lines.max(1),
+            )
+        } else {
+            (char_count * avg_char_width, 1)
+        };
+
+        if let Some(max_lines) = properties.max_lines {
+            line_count = line_count.min(max_lines as usize).max(1);
+        }
+
+        Size {
+            width,
+            height: line_count as f32 * line_height,
+        }
+    }
+
+    /// 按字符数贪心估算单词换行后的行数，算法结构与
+    /// `LayoutSolver::wrap_lines` 相同，只是用"字符数 × 平均宽度"代替真实字形宽度
+    fn wrap_word_count(content: &str, chars_per_line: usize) -> usize {
+        let mut lines = 1usize;
+        let mut current = 0usize;
+        for word in content.split_whitespace() {
+            let word_len = word.chars().count();
+            let needed = if current == 0 {
+                word_len
+            } else {
+                current + 1 + word_len
+            };
+            if needed > chars_per_line && current > 0 {
+                lines += 1;
+                current = word_len;
+            } else {
+                current = needed;
+            }
+        }
+        lines
+    }
+
+    /// 按 `aspect_ratio` 把一条已知边换算成另一条边；两边都未知（没有可用空间、也没有
+    /// 显式比例）时没有任何内在尺寸信息，返回零
+    fn image_intrinsic_size(properties: &ImageProperties, available: Size) -> Size {
+        match properties.aspect_ratio {
+            Some(ratio) if ratio > 0.0 && available.width.is_finite() => Size {
+                width: available.width,
+                height: available.width / ratio,
+            },
+            Some(ratio) if ratio > 0.0 && available.height.is_finite() => Size {
+                width: available.height * ratio,
+                height: available.height,
+            },
+            _ => Size { width: 0.0, height: 0.0 },
+        }
+    }
+
+    /// `Container`/`ZStack` 共用的重叠布局内在尺寸：子元素互相重叠，内容尺寸取各子
+    /// 元素尺寸的逐轴最大值，再按 `padding` 向外扩一圈
+    fn overlay_intrinsic_size(children: &[Element], padding: &Padding, available: Size) -> Size {
+        let inner = Size {
+            width: (available.width - padding.left - padding.right).max(0.0),
+            height: (available.height - padding.top - padding.bottom).max(0.0),
+        };
+        let content = children.iter().fold(
+            Size { width: 0.0, height: 0.0 },
+            |acc, child| {
+                let size = child.intrinsic_size(inner);
+                Size {
+                    width: acc.width.max(size.width),
+                    height: acc.height.max(size.height),
+                }
+            },
+        );
+        Size {
+            width: content.width + padding.left + padding.right,
+            height: content.height + padding.top + padding.bottom,
+        }
+    }
+
+    /// `VStack`/`HStack` 共用的堆叠布局内在尺寸：内容尺寸沿主轴是子元素尺寸之和加上
+    /// 子元素间的 `spacing`，沿交叉轴是子元素尺寸的最大值，再按 `padding` 向外扩一圈
+    fn stack_intrinsic_size(
+        children: &[Element],
+        properties: &StackProperties,
+        available: Size,
+        horizontal: bool,
+    ) -> Size {
+        let inner = Size {
+            width: (available.width - properties.padding.left - properties.padding.right).max(0.0),
+            height: (available.height - properties.padding.top - properties.padding.bottom).max(0.0),
+        };
+
+        let mut main = 0.0_f32;
+        let mut cross = 0.0_f32;
+        for child in children {
+            let size = child.intrinsic_size(inner);
+            if horizontal {
+                main += size.width;
+                cross = cross.max(size.height);
+            } else {
+                main += size.height;
+                cross = cross.max(size.width);
+            }
+        }
+        if children.len() > 1 {
+            main += properties.spacing * (children.len() - 1) as f32;
+        }
+
+        let (content_width, content_height) = if horizontal { (main, cross) } else { (cross, main) };
+        Size {
+            width: content_width + properties.padding.left + properties.padding.right,
+            height: content_height + properties.padding.top + properties.padding.bottom,
+        }
+    }
 }
 
 /// 画布配置
@@ -420,8 +1773,17 @@ pub struct Canvas {
     pub padding: Padding,
 }
 
+impl Hash for Canvas {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.width, state);
+        hash_f32(self.height, state);
+        self.background.hash(state);
+        self.padding.hash(state);
+    }
+}
+
 /// 布局定义
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash)]
 pub struct Layout {
     pub version: String,
     pub canvas: Canvas,
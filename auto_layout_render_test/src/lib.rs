@@ -7,15 +7,22 @@
 //! - JSON/YAML格式的DSL描述
 //! - 高质量的图像渲染输出
 
+pub mod ansi;
+pub mod capture;
 pub mod dsl;
 pub mod layout;
 pub mod renderer;
 pub mod solver;
+pub mod text_layout;
+pub mod watch;
 
-pub use dsl::{DslError, DslParser};
+pub use ansi::{AnsiColorMode, AnsiRenderer};
+pub use capture::{CaptureError, CaptureManifest, CapturedEngineConfig};
+pub use dsl::{DslDiagnostic, DslError, DslParser};
 pub use layout::*;
-pub use renderer::{RenderError, Renderer};
-pub use solver::{LayoutSolver, SolverError};
+pub use renderer::{RenderError, RenderWarning, Renderer};
+pub use solver::{LayoutSolver, SolverError, blended_strength};
+pub use watch::DslWatcher;
 
 use image::RgbaImage;
 use std::path::Path;
@@ -23,7 +30,8 @@ use std::path::Path;
 /// 自动布局引擎的主要接口
 pub struct AutoLayoutEngine {
     solver: LayoutSolver,
-    renderer: Renderer,
+    pub(crate) renderer: Renderer,
+    pub(crate) debug: bool,
 }
 
 impl AutoLayoutEngine {
@@ -32,11 +40,27 @@ impl AutoLayoutEngine {
         Self {
             solver: LayoutSolver::new(),
             renderer: Renderer::new(),
+            debug: false,
         }
     }
 
-    /// 从布局描述渲染图像
-    pub fn render_layout(&mut self, layout: &Layout) -> Result<RgbaImage, AutoLayoutError> {
+    /// 开启/关闭 debug 模式（目前作为引擎配置的一部分随 [`AutoLayoutEngine::capture`] 一起落盘，
+    /// 供后续渲染路径据此绘制调试信息使用）
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    /// 当前是否处于 debug 模式
+    pub fn is_debug(&self) -> bool {
+        self.debug
+    }
+
+    /// 从布局描述渲染图像，返回渲染结果以及渲染过程中因资源加载失败而被换成
+    /// 占位图块的记录（参见 [`RenderWarning`]）
+    pub fn render_layout(
+        &mut self,
+        layout: &Layout,
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
         // 1. 解析约束并计算布局
         let computed_layout = self
             .solver
@@ -44,22 +68,54 @@ impl AutoLayoutEngine {
             .map_err(AutoLayoutError::SolverError)?;
 
         // 2. 渲染布局到图像
-        let image = self
+        let result = self
             .renderer
             .render_layout(layout, &computed_layout)
             .map_err(AutoLayoutError::RenderError)?;
 
-        Ok(image)
+        Ok(result)
+    }
+
+    /// 在不重新构建约束系统的前提下调整画布尺寸并重新渲染：复用 `LayoutSolver::resize_canvas`
+    /// 的增量求解，适合需要反复重新布局的动画/交互式缩放场景，前提是此前已经对同一个
+    /// `layout` 调用过 `render_layout`
+    pub fn resize_and_render(
+        &mut self,
+        layout: &Layout,
+        width: f32,
+        height: f32,
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
+        let computed_layout = self
+            .solver
+            .resize_canvas(width, height)
+            .map_err(AutoLayoutError::SolverError)?;
+
+        let mut resized_layout = layout.clone();
+        resized_layout.canvas.width = width;
+        resized_layout.canvas.height = height;
+
+        let result = self
+            .renderer
+            .render_layout(&resized_layout, &computed_layout)
+            .map_err(AutoLayoutError::RenderError)?;
+
+        Ok(result)
     }
 
     /// 从JSON字符串渲染图像
-    pub fn render_from_json(&mut self, json: &str) -> Result<RgbaImage, AutoLayoutError> {
+    pub fn render_from_json(
+        &mut self,
+        json: &str,
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
         let layout = DslParser::parse_json(json).map_err(AutoLayoutError::DslError)?;
         self.render_layout(&layout)
     }
 
     /// 从YAML字符串渲染图像
-    pub fn render_from_yaml(&mut self, yaml: &str) -> Result<RgbaImage, AutoLayoutError> {
+    pub fn render_from_yaml(
+        &mut self,
+        yaml: &str,
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
         let layout = DslParser::parse_yaml(yaml).map_err(AutoLayoutError::DslError)?;
         self.render_layout(&layout)
     }
@@ -68,20 +124,43 @@ impl AutoLayoutEngine {
     pub fn render_from_json_file<P: AsRef<Path>>(
         &mut self,
         path: P,
-    ) -> Result<RgbaImage, AutoLayoutError> {
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
         let layout = DslParser::load_json_file(path).map_err(AutoLayoutError::DslError)?;
         self.render_layout(&layout)
     }
 
+    /// 从JSON文件渲染图像，并选用模板顶层 `themes` 字典中的具名主题，用于同一份模板
+    /// 产出 light/dark 或多品牌变体；模板只写了单个 `theme` 时 `theme_name` 被忽略
+    pub fn render_from_json_file_with_theme<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        theme_name: Option<&str>,
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
+        let layout =
+            DslParser::load_json_file_with_theme(path, theme_name).map_err(AutoLayoutError::DslError)?;
+        self.render_layout(&layout)
+    }
+
     /// 从YAML文件渲染图像
     pub fn render_from_yaml_file<P: AsRef<Path>>(
         &mut self,
         path: P,
-    ) -> Result<RgbaImage, AutoLayoutError> {
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
         let layout = DslParser::load_yaml_file(path).map_err(AutoLayoutError::DslError)?;
         self.render_layout(&layout)
     }
 
+    /// 从YAML文件渲染图像，并选用具名主题，语义同 [`Self::render_from_json_file_with_theme`]
+    pub fn render_from_yaml_file_with_theme<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        theme_name: Option<&str>,
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
+        let layout =
+            DslParser::load_yaml_file_with_theme(path, theme_name).map_err(AutoLayoutError::DslError)?;
+        self.render_layout(&layout)
+    }
+
     /// 保存渲染结果到文件
     pub fn save_image<P: AsRef<Path>>(image: &RgbaImage, path: P) -> Result<(), AutoLayoutError> {
         image
@@ -105,28 +184,34 @@ pub enum AutoLayoutError {
     RenderError(#[from] RenderError),
     #[error("DSL error: {0}")]
     DslError(#[from] DslError),
+    #[error("Capture error: {0}")]
+    CaptureError(#[from] CaptureError),
 }
 
 /// 便利函数：从JSON字符串快速渲染图像
-pub fn render_json(json: &str) -> Result<RgbaImage, AutoLayoutError> {
+pub fn render_json(json: &str) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
     let mut engine = AutoLayoutEngine::new();
     engine.render_from_json(json)
 }
 
 /// 便利函数：从YAML字符串快速渲染图像
-pub fn render_yaml(yaml: &str) -> Result<RgbaImage, AutoLayoutError> {
+pub fn render_yaml(yaml: &str) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
     let mut engine = AutoLayoutEngine::new();
     engine.render_from_yaml(yaml)
 }
 
 /// 便利函数：从JSON文件快速渲染图像
-pub fn render_json_file<P: AsRef<Path>>(path: P) -> Result<RgbaImage, AutoLayoutError> {
+pub fn render_json_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
     let mut engine = AutoLayoutEngine::new();
     engine.render_from_json_file(path)
 }
 
 /// 便利函数：从YAML文件快速渲染图像
-pub fn render_yaml_file<P: AsRef<Path>>(path: P) -> Result<RgbaImage, AutoLayoutError> {
+pub fn render_yaml_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<(RgbaImage, Vec<RenderWarning>), AutoLayoutError> {
     let mut engine = AutoLayoutEngine::new();
     engine.render_from_yaml_file(path)
 }
@@ -29,13 +29,16 @@ fn test_debug_mode() -> Result<(), Box<dyn std::error::Error>> {
     engine.set_debug(true);
 
     // 使用专门的debug演示文件
-    let image_debug = engine.render_from_json_file("examples/debug_demo.json")?;
+    let (image_debug, warnings_debug) = engine.render_from_json_file("examples/debug_demo.json")?;
     AutoLayoutEngine::save_image(&image_debug, "output/debug_demo.png")?;
+    print_warnings(&warnings_debug);
     println!("✅ Debug模式演示完成 -> output/debug_demo.png");
 
     // 也测试图片尺寸变体的debug效果
-    let image_variants_debug = engine.render_from_json_file("examples/image_size_variants.json")?;
+    let (image_variants_debug, warnings_variants_debug) =
+        engine.render_from_json_file("examples/image_size_variants.json")?;
     AutoLayoutEngine::save_image(&image_variants_debug, "output/debug_image_variants.png")?;
+    print_warnings(&warnings_variants_debug);
     println!("✅ Debug模式（图片变体）完成 -> output/debug_image_variants.png");
 
     println!("   🎨 Debug边框颜色说明：");
@@ -55,25 +58,36 @@ fn test_simple_layout() -> Result<(), Box<dyn std::error::Error>> {
     let mut engine = AutoLayoutEngine::new();
 
     println!("🧪 测试简单布局...");
-    let image = engine.render_from_json_file("examples/simple.json")?;
+    let (image, warnings) = engine.render_from_json_file("examples/simple.json")?;
     AutoLayoutEngine::save_image(&image, "output/simple.png")?;
+    print_warnings(&warnings);
     println!("✅ 简单布局渲染完成 -> output/simple.png\n");
 
     // 测试图片自动尺寸功能
     println!("🧪 测试图片自动尺寸...");
-    let image_auto = engine.render_from_json_file("examples/auto_image_size.json")?;
+    let (image_auto, warnings_auto) = engine.render_from_json_file("examples/auto_image_size.json")?;
     AutoLayoutEngine::save_image(&image_auto, "output/auto_image_size.png")?;
+    print_warnings(&warnings_auto);
     println!("✅ 图片自动尺寸测试完成 -> output/auto_image_size.png\n");
 
     // 测试图片尺寸变体
     println!("🧪 测试图片尺寸变体（完全自动、固定宽度、固定高度）...");
-    let image_variants = engine.render_from_json_file("examples/image_size_variants.json")?;
+    let (image_variants, warnings_variants) =
+        engine.render_from_json_file("examples/image_size_variants.json")?;
     AutoLayoutEngine::save_image(&image_variants, "output/image_size_variants.png")?;
+    print_warnings(&warnings_variants);
     println!("✅ 图片尺寸变体测试完成 -> output/image_size_variants.png\n");
 
     Ok(())
 }
 
+/// 把渲染过程中产生的资源加载警告打印出来（被替换成占位图块的元素）
+fn print_warnings(warnings: &[RenderWarning]) {
+    for warning in warnings {
+        println!("⚠️  元素 '{}' 使用了占位图块: {}", warning.element_id, warning.message);
+    }
+}
+
 /// 测试复杂布局
 fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
     println!("🧪 测试复杂布局（通过代码构建）...");
@@ -101,14 +115,16 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
                     b: 255,
                     a: 255,
                 },
-                border_color: Color {
-                    r: 200,
-                    g: 200,
-                    b: 200,
-                    a: 255,
-                },
-                border_width: 1.0,
-                corner_radius: 8.0,
+                border: Border::uniform(
+                    1.0,
+                    Color {
+                        r: 200,
+                        g: 200,
+                        b: 200,
+                        a: 255,
+                    },
+                ),
+                corners: Corners::all(8.0),
                 opacity: 1.0,
                 padding: Padding {
                     top: 20.0,
@@ -116,6 +132,9 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
                     bottom: 20.0,
                     left: 20.0,
                 },
+                margin: Margin::all(0.0),
+                shadow: Some(ShadowStyle::default()),
+                filters: Vec::new(),
             },
             constraints: vec![
                 Constraint::new(ConstraintType::Top {
@@ -143,6 +162,7 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
                         font_family: "Arial".to_string(),
                         font_size: 24.0,
                         font_weight: FontWeight::Bold,
+                        font_style: FontStyle::Bold,
                         color: Color {
                             r: 51,
                             g: 51,
@@ -154,6 +174,9 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
                         letter_spacing: 0.0,
                         max_lines: None,
                         line_break_mode: LineBreakMode::WordWrap,
+                        filters: Vec::new(),
+                        style: TextStyle::default(),
+                        margin: Margin::all(0.0),
                     },
                     constraints: vec![
                         Constraint::new(ConstraintType::Top {
@@ -173,6 +196,7 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
                         font_family: "Arial".to_string(),
                         font_size: 16.0,
                         font_weight: FontWeight::Normal,
+                        font_style: FontStyle::Regular,
                         color: Color {
                             r: 102,
                             g: 102,
@@ -184,6 +208,9 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
                         letter_spacing: 0.0,
                         max_lines: None,
                         line_break_mode: LineBreakMode::WordWrap,
+                        filters: Vec::new(),
+                        style: TextStyle::default(),
+                        margin: Margin::all(0.0),
                     },
                     constraints: vec![
                         Constraint::new(ConstraintType::Top {
@@ -201,9 +228,10 @@ fn test_complex_layout() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let mut engine = AutoLayoutEngine::new();
-    let image = engine.render_layout(&layout)?;
+    let (image, warnings) = engine.render_layout(&layout)?;
 
     AutoLayoutEngine::save_image(&image, "output/complex_layout.png")?;
+    print_warnings(&warnings);
     println!("✅ 复杂布局渲染完成 -> output/complex_layout.png\n");
 
     Ok(())
@@ -3,7 +3,11 @@
 use crate::layout::*;
 use image::{ImageBuffer, Rgba, RgbaImage, DynamicImage};
 use fontdue::{Font, FontSettings};
-use std::collections::HashMap;
+use font_kit::family_name::FamilyName;
+use font_kit::properties::{Properties, Style, Weight};
+use font_kit::source::SystemSource;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
 use std::path::Path;
 use thiserror::Error;
 
@@ -19,10 +23,423 @@ pub enum RenderError {
     ElementNotFound(String),
 }
 
+/// 渲染过程中某个元素的资源加载失败但没有中止整个渲染时留下的记录：`preload_resources`
+/// 遇到字体/图片加载失败时不再用 `?` 让整张图都渲染不出来，而是换上占位图块继续渲染，
+/// 把失败信息记在这里，随渲染结果一起返回给调用方
+#[derive(Debug, Clone)]
+pub struct RenderWarning {
+    pub element_id: String,
+    pub message: String,
+}
+
+/// 内嵌的兜底字体：系统里完全找不到匹配字体、或者匹配到的字体连自己都解析不出来时
+/// 的最后一道防线，保证任何请求都至少能画出点什么（哪怕是 tofu）
+const EMBEDDED_FALLBACK_FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+
+/// 一个 `(font_family, font_style)` 解析出的有序字体回退链：排在前面的优先尝试，某个
+/// 字符在当前字体里找不到字形（`fontdue` 把未映射码点解析成宽度为 0 的空字形）就换
+/// 下一个；链的最后一环固定是内嵌的 DejaVu Sans，保证整条链兜得住任何字符。当系统里
+/// 没有真正的粗体/斜体字形时，`synthesize_bold`/`synthesize_italic` 记录需要在栅格化
+/// 之后用像素级手段（膨胀 / 切变）模拟出对应效果
+struct FontCollection {
+    fonts: Vec<Font>,
+    synthesize_bold: bool,
+    synthesize_italic: bool,
+}
+
+impl FontCollection {
+    /// 按 `font_family` + `font_style` 向系统字体库（`font-kit` 的 `SystemSource`）解析
+    /// 最佳匹配字体，追加到内嵌 DejaVu Sans 前面；系统里找不到该家族、或者找到了但
+    /// 加载/解析失败时，静默跳过系统字体这一环，链里只剩下内嵌兜底字体。匹配到的字体
+    /// 粗细/样式达不到请求的粗体/斜体时，记下来交给调用方做像素级模拟
+    fn resolve(font_family: &str, font_style: FontStyle) -> Result<Self, RenderError> {
+        let mut fonts = Vec::new();
+        let mut synthesize_bold = matches!(font_style, FontStyle::Bold | FontStyle::BoldItalic);
+        let mut synthesize_italic = matches!(font_style, FontStyle::Italic | FontStyle::BoldItalic);
+
+        if let Some((system_font, matched_properties)) = load_system_font(font_family, font_style) {
+            if matched_properties.weight.0 >= Weight::BOLD.0 {
+                synthesize_bold = false;
+            }
+            if matched_properties.style != Style::Normal {
+                synthesize_italic = false;
+            }
+            fonts.push(system_font);
+        }
+
+        let fallback = Font::from_bytes(EMBEDDED_FALLBACK_FONT, FontSettings::default())
+            .map_err(|e| RenderError::FontError(format!("Failed to load DejaVu Sans font: {}", e)))?;
+        fonts.push(fallback);
+
+        Ok(Self { fonts, synthesize_bold, synthesize_italic })
+    }
+
+    /// 依次尝试链上的字体，返回第一个能画出字符 `ch` 的字体；整条链都没有覆盖时
+    /// 退回链上第一个字体（加载到系统字体时是它，否则是内嵌 DejaVu），交给调用方画出 tofu
+    fn font_for_char(&self, ch: char) -> &Font {
+        self.fonts
+            .iter()
+            .find(|font| font.lookup_glyph_index(ch) != 0)
+            .unwrap_or_else(|| self.fonts.first().expect("回退链末尾总有内嵌的 DejaVu Sans"))
+    }
+
+    /// 量一个字符的宽度等度量信息，和 [`FontCollection::rasterize`] 走同一条字体选择逻辑，
+    /// 保证量出来的宽度和实际绘制的字形一致
+    fn metrics(&self, ch: char, scale: f32) -> fontdue::Metrics {
+        self.font_for_char(ch).metrics(ch, scale)
+    }
+
+    /// 栅格化一个字符，没有系统字体的对应变体时，在位图上叠加粗体/斜体的像素级模拟
+    fn rasterize(&self, ch: char, scale: f32) -> (fontdue::Metrics, Vec<u8>) {
+        let (mut metrics, mut bitmap) = self.font_for_char(ch).rasterize(ch, scale);
+        if self.synthesize_bold {
+            dilate_alpha(&mut metrics, &mut bitmap);
+        }
+        if self.synthesize_italic {
+            shear_bitmap(&mut metrics, &mut bitmap);
+        }
+        (metrics, bitmap)
+    }
+
+    /// 整段文字排版用的上升高度（基线以上的高度），取链上主字体的字体级 metrics；
+    /// 取不到（字体没有提供 hhea 表之类的信息）时退回一个基于字号的经验值
+    fn ascent(&self, scale: f32) -> f32 {
+        self.fonts
+            .first()
+            .and_then(|font| font.horizontal_line_metrics(scale))
+            .map(|metrics| metrics.ascent)
+            .unwrap_or(scale * 0.8)
+    }
+}
+
+/// 把 `FontStyle` 翻译成 `font-kit` 用来筛选系统字体的粗细/样式属性
+fn style_to_properties(font_style: FontStyle) -> Properties {
+    let mut properties = Properties::new();
+    match font_style {
+        FontStyle::Regular => {}
+        FontStyle::Italic => {
+            properties.style(Style::Italic);
+        }
+        FontStyle::Bold => {
+            properties.weight(Weight::BOLD);
+        }
+        FontStyle::BoldItalic => {
+            properties.weight(Weight::BOLD);
+            properties.style(Style::Italic);
+        }
+    }
+    properties
+}
+
+/// 向系统字体库查询 `font_family` + `font_style` 的最佳匹配并转成 `fontdue::Font`，
+/// 连同匹配到的真实粗细/样式一起返回（调用方用它判断是否还需要做像素级模拟）；
+/// 任何一步失败（系统里没有这个家族、拿不到字体数据、`fontdue` 解析不了）都返回
+/// `None`，由调用方退回内嵌字体，不让个别系统字体的问题影响渲染
+fn load_system_font(font_family: &str, font_style: FontStyle) -> Option<(Font, Properties)> {
+    let handle = SystemSource::new()
+        .select_best_match(
+            &[FamilyName::Title(font_family.to_string()), FamilyName::SansSerif],
+            &style_to_properties(font_style),
+        )
+        .ok()?;
+    let loaded = handle.load().ok()?;
+    let matched_properties = loaded.properties();
+    let data = loaded.copy_font_data()?;
+    let font = Font::from_bytes(data.as_slice(), FontSettings::default()).ok()?;
+    Some((font, matched_properties))
+}
+
+/// 给栅格化结果叠加一个水平方向的像素膨胀：没有真正的粗体字形时用它近似模拟粗体，
+/// 每个像素和它右边一像素取最大 alpha，笔画整体变粗一圈
+fn dilate_alpha(metrics: &mut fontdue::Metrics, bitmap: &mut [u8]) {
+    let width = metrics.width;
+    let height = metrics.height;
+    if width == 0 || height == 0 {
+        return;
+    }
+    let original = bitmap.to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let mut max_alpha = original[idx];
+            if x + 1 < width {
+                max_alpha = max_alpha.max(original[idx + 1]);
+            }
+            bitmap[idx] = max_alpha;
+        }
+    }
+}
+
+/// 给栅格化结果叠加一个简单的垂直切变：没有真正的斜体字形时用它近似模拟斜体，
+/// 越靠字形顶部的行越往右移，形成向右倾斜的视觉效果；位图因此变宽一些，
+/// `metrics.width` 同步更新，保证后续按行展开位图时不会越界
+fn shear_bitmap(metrics: &mut fontdue::Metrics, bitmap: &mut Vec<u8>) {
+    let width = metrics.width;
+    let height = metrics.height;
+    if width == 0 || height == 0 {
+        return;
+    }
+    const SHEAR_FACTOR: f32 = 0.25;
+    let max_shift = ((height as f32) * SHEAR_FACTOR).ceil() as usize;
+    let new_width = width + max_shift;
+    let mut sheared = vec![0u8; new_width * height];
+    for y in 0..height {
+        let shift = ((height - 1 - y) as f32 * SHEAR_FACTOR).round() as usize;
+        for x in 0..width {
+            sheared[y * new_width + (x + shift)] = bitmap[y * width + x];
+        }
+    }
+    *bitmap = sheared;
+    metrics.width = new_width;
+}
+
+/// 栅格化结果缓存的上限：超过这个条目数就按最久未使用淘汰，避免重复排版/渲染同一段
+/// 文字（多次渲染 pass、重复出现的标签）反复占用内存
+const GLYPH_CACHE_CAPACITY: usize = 1000;
+
+/// 栅格化缓存的 key：同一个字体族 + 样式 + 字符 + 字号会产出完全相同的位图，
+/// 字号用 `to_bits()` 量化成整数，避免浮点数直接做 key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GlyphCacheKey {
+    font_family: String,
+    font_style: FontStyle,
+    ch: char,
+    size_bits: u32,
+}
+
+/// 字形栅格化缓存：`render_text` 里同一个字符（同一段文字里重复出现、或者同一段文字
+/// 被多次渲染）本来会重复调用 `FontCollection::rasterize`，这里按容量做 LRU 缓存，
+/// 命中时直接复用上次栅格化出来的位图
+struct GlyphCache {
+    entries: HashMap<GlyphCacheKey, (fontdue::Metrics, Vec<u8>)>,
+    order: VecDeque<GlyphCacheKey>,
+    capacity: usize,
+}
+
+impl GlyphCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// 命中直接返回缓存的位图并标记为最近使用；未命中才调用 `rasterize` 生成，
+    /// 容量已满时先淘汰最久未使用的条目再插入新的
+    fn get_or_insert_with(
+        &mut self,
+        key: GlyphCacheKey,
+        rasterize: impl FnOnce() -> (fontdue::Metrics, Vec<u8>),
+    ) -> &(fontdue::Metrics, Vec<u8>) {
+        if self.entries.contains_key(&key) {
+            self.touch(&key);
+        } else {
+            if self.entries.len() >= self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+            self.entries.insert(key.clone(), rasterize());
+            self.order.push_back(key.clone());
+        }
+        self.entries.get(&key).expect("刚插入或者已经存在")
+    }
+
+    fn touch(&mut self, key: &GlyphCacheKey) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            let recent = self.order.remove(pos).unwrap();
+            self.order.push_back(recent);
+        }
+    }
+}
+
+/// 判断点 `(x, y)`（相对矩形左上角的局部坐标）是否落在一个 `width` x `height`、
+/// 按 `corners` 分别指定四角半径的圆角矩形内：先排除外接矩形之外的点，再按这个点
+/// 所在的象限取对应角的半径，看它是否落在那个圆角扇形之外
+fn point_in_rounded_rect(x: f32, y: f32, width: f32, height: f32, corners: &Corners) -> bool {
+    if x < 0.0 || x >= width || y < 0.0 || y >= height {
+        return false;
+    }
+    let radius = corners
+        .radius_for(x, y, width, height)
+        .max(0.0)
+        .min(width.min(height) / 2.0);
+    if radius <= 0.0 {
+        return true;
+    }
+
+    let (corner_x, corner_y) = if x < radius && y < radius {
+        (radius, radius)
+    } else if x >= width - radius && y < radius {
+        (width - radius, radius)
+    } else if x < radius && y >= height - radius {
+        (radius, height - radius)
+    } else if x >= width - radius && y >= height - radius {
+        (width - radius, height - radius)
+    } else {
+        return true;
+    };
+
+    let dx = x - corner_x;
+    let dy = y - corner_y;
+    dx * dx + dy * dy <= radius * radius
+}
+
+/// 点 `(local_x, local_y)`（矩形左上角为原点的局部坐标）到圆角矩形边界的带符号距离：
+/// 负值在矩形内部，正值在外部，0 正好在边界上。直边区域退化成到矩形边的距离，
+/// 四个角的区域则是到对应圆心的距离减去半径——这就是图形学里常见的圆角矩形 SDF。
+/// 四角半径不同时，每个像素只按自己所在象限对应的 `corners` 分量计算，这是
+/// per-corner 圆角矩形 SDF 的标准近似
+fn sdf_rounded_rect(local_x: f32, local_y: f32, width: f32, height: f32, corners: &Corners) -> f32 {
+    let radius = corners
+        .radius_for(local_x, local_y, width, height)
+        .max(0.0)
+        .min(width.min(height) / 2.0);
+    let half_w = width / 2.0;
+    let half_h = height / 2.0;
+    let cx = (local_x - half_w).abs() - (half_w - radius);
+    let cy = (local_y - half_h).abs() - (half_h - radius);
+    cx.max(cy).min(0.0) + (cx.max(0.0).powi(2) + cy.max(0.0).powi(2)).sqrt() - radius
+}
+
+/// 把带符号距离转换成 [0, 1] 的抗锯齿覆盖率：`0.5 - distance` 在边界附近大约一个像素的
+/// 范围内从 1 过渡到 0，裁到 `[0, 1]` 之后就是这个像素该叠加多少前景色的系数
+fn rounded_rect_coverage(local_x: f32, local_y: f32, width: f32, height: f32, corners: &Corners) -> f32 {
+    (0.5 - sdf_rounded_rect(local_x, local_y, width, height, corners)).clamp(0.0, 1.0)
+}
+
+/// 圆角矩形上离给定局部坐标最近的那条边，用来在绘制每边独立宽度/颜色的边框时
+/// 决定这个像素该套用哪条边的样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RectSide {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+fn nearest_rect_side(local_x: f32, local_y: f32, width: f32, height: f32) -> RectSide {
+    let dist_top = local_y;
+    let dist_bottom = height - local_y;
+    let dist_left = local_x;
+    let dist_right = width - local_x;
+
+    let min_dist = dist_top.min(dist_bottom).min(dist_left).min(dist_right);
+    if min_dist == dist_top {
+        RectSide::Top
+    } else if min_dist == dist_bottom {
+        RectSide::Bottom
+    } else if min_dist == dist_left {
+        RectSide::Left
+    } else {
+        RectSide::Right
+    }
+}
+
+impl BorderStyle {
+    /// 某条边上的边框在"沿边方向的坐标" `position` 处是否应该画：实线永远画，
+    /// 虚线/点线按周期性的"画一段、留一段空"来取舍，`position` 就近取自矩形局部坐标
+    /// 里和这条边平行的那根轴，周期性足够让四条边首尾相接处看起来自然
+    fn is_visible_at(&self, position: f32) -> bool {
+        match self {
+            BorderStyle::Solid => true,
+            BorderStyle::Dashed => (position / 10.0).rem_euclid(2.0) < 1.0,
+            BorderStyle::Dotted => (position / 4.0).rem_euclid(2.0) < 1.0,
+        }
+    }
+}
+
+impl Border {
+    fn side(&self, side: RectSide) -> &BorderSide {
+        match side {
+            RectSide::Top => &self.top,
+            RectSide::Right => &self.right,
+            RectSide::Bottom => &self.bottom,
+            RectSide::Left => &self.left,
+        }
+    }
+}
+
+/// 按投影的模糊半径推导一维高斯核：`sigma` 取模糊半径的一半（经验值，半径越大
+/// 模糊越柔和），核的覆盖范围截到 3 倍 sigma，再归一化让权重之和为 1
+fn gaussian_kernel(blur_radius: f32) -> Vec<f32> {
+    let sigma = (blur_radius / 2.0).max(0.5);
+    let radius = (sigma * 3.0).ceil() as i32;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| {
+            let x = i as f32;
+            (-(x * x) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    for weight in kernel.iter_mut() {
+        *weight /= sum;
+    }
+    kernel
+}
+
+/// 和 [`gaussian_kernel`] 同样的可分离高斯核，区别是直接拿标准差而不是 `blur_radius`，
+/// 给按 SVG `feGaussianBlur`/`feDropShadow` 语义定义的 `Filter` 变体使用
+fn gaussian_kernel_from_std_deviation(std_deviation: f32) -> Vec<f32> {
+    let sigma = std_deviation.max(0.1);
+    let radius = (sigma * 3.0).ceil() as i32;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| {
+            let x = i as f32;
+            (-(x * x) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    for weight in kernel.iter_mut() {
+        *weight /= sum;
+    }
+    kernel
+}
+
+/// 一维高斯模糊的轴向：投影蒙版先沿水平方向卷积一次，再沿垂直方向卷积一次
+/// （可分离卷积），等价于一次二维高斯模糊，但开销只有 O(n) 而不是 O(n^2)
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// 沿 `axis` 方向对 alpha 蒙版做一维卷积，越界的采样点当作 0（蒙版外没有东西）
+fn blur_pass(mask: &[u8], width: usize, height: usize, kernel: &[f32], axis: Axis) -> Vec<u8> {
+    let radius = (kernel.len() / 2) as i32;
+    let mut out = vec![0u8; mask.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let mut acc = 0.0f32;
+            for (k, &weight) in kernel.iter().enumerate() {
+                let offset = k as i32 - radius;
+                let sample = match axis {
+                    Axis::Horizontal => {
+                        let sx = x as i32 + offset;
+                        (sx >= 0 && (sx as usize) < width).then(|| mask[y * width + sx as usize])
+                    }
+                    Axis::Vertical => {
+                        let sy = y as i32 + offset;
+                        (sy >= 0 && (sy as usize) < height).then(|| mask[sy as usize * width + x])
+                    }
+                };
+                if let Some(value) = sample {
+                    acc += value as f32 * weight;
+                }
+            }
+            out[y * width + x] = acc.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    out
+}
+
 /// 渲染上下文
 struct RenderContext {
-    fonts: HashMap<String, Font>,
+    fonts: HashMap<(String, FontStyle), FontCollection>,
     images: HashMap<String, DynamicImage>,
+    glyph_cache: GlyphCache,
+    /// 远程图片按 URL 缓存的原始字节，避免同一个 `http(s)` 来源被反复下载
+    url_cache: HashMap<String, Vec<u8>>,
 }
 
 impl RenderContext {
@@ -30,112 +447,245 @@ impl RenderContext {
         Self {
             fonts: HashMap::new(),
             images: HashMap::new(),
+            glyph_cache: GlyphCache::new(GLYPH_CACHE_CAPACITY),
+            url_cache: HashMap::new(),
         }
     }
-    
-    /// 加载字体
-    fn load_font(&mut self, font_family: &str) -> Result<(), RenderError> {
-        if self.fonts.contains_key(font_family) {
+
+    /// 加载字体：解析 `(font_family, font_style)` 对应的系统字体 + 内嵌兜底回退链，
+    /// 解析结果按家族名和样式一起缓存，粗体/斜体各自独立命中缓存
+    fn load_font(&mut self, font_family: &str, font_style: FontStyle) -> Result<(), RenderError> {
+        let key = (font_family.to_string(), font_style);
+        if self.fonts.contains_key(&key) {
             return Ok(());
         }
-        
-        // 使用默认的 DejaVu Sans 字体
-        let font_data = include_bytes!("../assets/fonts/DejaVuSans.ttf");
-        let font = Font::from_bytes(font_data as &[u8], FontSettings::default())
-            .map_err(|e| RenderError::FontError(format!("Failed to load DejaVu Sans font: {}", e)))?;
-        
-        self.fonts.insert(font_family.to_string(), font);
-        println!("✅ 成功加载字体: {} (使用 DejaVu Sans)", font_family);
+
+        let collection = FontCollection::resolve(font_family, font_style)?;
+        self.fonts.insert(key, collection);
+        println!("✅ 成功加载字体: {} {:?} (系统字体 + DejaVu Sans 回退)", font_family, font_style);
         Ok(())
     }
-    
-    /// 创建占位符字体数据
-    fn create_placeholder_font_data(&self) -> Vec<u8> {
-        // 返回空向量，跳过字体加载
-        vec![]
-    }
-    
-    /// 加载图片
+
+    /// 加载图片：本地路径直接走 `image::open`；`http`/`https` 来源需要启用
+    /// `http-images` feature 才能下载，字节按 URL 缓存在 [`Self::url_cache`] 里，
+    /// 同一个来源重复出现时不用再发一次请求
     fn load_image(&mut self, source: &str) -> Result<(), RenderError> {
         if self.images.contains_key(source) {
             return Ok(());
         }
-        
-        let img = if source.starts_with("http") {
-            // 网络图片加载（简化实现）
-            return Err(RenderError::ImageError(image::ImageError::Unsupported(
-                image::error::UnsupportedError::from_format_and_kind(
-                    image::error::ImageFormatHint::Unknown,
-                    image::error::UnsupportedErrorKind::GenericFeature("Network images not supported".to_string())
-                )
-            )));
+
+        let img = if source.starts_with("http://") || source.starts_with("https://") {
+            let bytes = self.fetch_url_bytes(source)?;
+            image::load_from_memory(&bytes)?
+        } else if source.to_ascii_lowercase().ends_with(".svg") {
+            Self::rasterize_svg(source)?
         } else {
-            // 本地图片加载
             image::open(source)?
         };
-        
+
         self.images.insert(source.to_string(), img);
         Ok(())
     }
+
+    /// 把本地 `.svg` 文件栅格化成和它自身 viewBox 同样像素尺寸的位图；`preserveAspectRatio`
+    /// 对目标容器框的对齐/缩放留给 `render_image` 按运行期的 `frame` 尺寸再计算，这里
+    /// 只负责产出一张尺寸正确的“原始图片”，后续就能跟光栅图片走同一套缩放/合成流程
+    #[cfg(feature = "svg-images")]
+    fn rasterize_svg(path: &str) -> Result<DynamicImage, RenderError> {
+        let data = fs::read(path)?;
+        let opt = usvg::Options::default();
+        let tree = usvg::Tree::from_data(&data, &opt).map_err(|e| {
+            RenderError::ImageError(image::ImageError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("解析 SVG 失败: {} - {}", path, e),
+            )))
+        })?;
+
+        let size = tree.size();
+        let width = size.width().ceil().max(1.0) as u32;
+        let height = size.height().ceil().max(1.0) as u32;
+
+        let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or_else(|| {
+            RenderError::ImageError(image::ImageError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("SVG 尺寸无效: {} ({}x{})", path, width, height),
+            )))
+        })?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+        let rgba = image::RgbaImage::from_raw(width, height, pixmap.take()).ok_or_else(|| {
+            RenderError::ImageError(image::ImageError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("SVG 像素缓冲区尺寸不匹配: {}", path),
+            )))
+        })?;
+        Ok(DynamicImage::ImageRgba8(rgba))
+    }
+
+    /// 未启用 `svg-images` feature 时的占位实现，给出明确的错误而不是悄悄拿占位图顶替
+    #[cfg(not(feature = "svg-images"))]
+    fn rasterize_svg(path: &str) -> Result<DynamicImage, RenderError> {
+        Err(RenderError::ImageError(image::ImageError::Unsupported(
+            image::error::UnsupportedError::from_format_and_kind(
+                image::error::ImageFormatHint::Unknown,
+                image::error::UnsupportedErrorKind::GenericFeature(format!(
+                    "加载 SVG 图片 {} 需要启用 `svg-images` feature",
+                    path
+                )),
+            ),
+        )))
+    }
+
+    /// 下载（或从 [`Self::url_cache`] 取出）`url` 对应的原始字节；未启用 `http-images`
+    /// feature 时直接返回明确的错误，而不是静默跳过
+    fn fetch_url_bytes(&mut self, url: &str) -> Result<Vec<u8>, RenderError> {
+        if let Some(bytes) = self.url_cache.get(url) {
+            return Ok(bytes.clone());
+        }
+
+        #[cfg(feature = "http-images")]
+        {
+            use std::io::Read;
+
+            let response = ureq::get(url).call().map_err(|e| {
+                RenderError::ImageError(image::ImageError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("请求远程图片失败: {} - {}", url, e),
+                )))
+            })?;
+
+            let mut bytes = Vec::new();
+            response
+                .into_reader()
+                .read_to_end(&mut bytes)
+                .map_err(RenderError::IoError)?;
+
+            self.url_cache.insert(url.to_string(), bytes.clone());
+            Ok(bytes)
+        }
+
+        #[cfg(not(feature = "http-images"))]
+        {
+            Err(RenderError::ImageError(image::ImageError::Unsupported(
+                image::error::UnsupportedError::from_format_and_kind(
+                    image::error::ImageFormatHint::Unknown,
+                    image::error::UnsupportedErrorKind::GenericFeature(format!(
+                        "加载远程图片 {} 需要启用 `http-images` feature",
+                        url
+                    )),
+                ),
+            )))
+        }
+    }
+
+    /// 取 `(font_family, font_style)` 回退链里字符 `ch` 在 `scale` 像素字号下的栅格化结果，
+    /// 优先查栅格化缓存，未命中才真正调用 `FontCollection::rasterize` 并存入缓存
+    fn rasterize_glyph(
+        &mut self,
+        font_family: &str,
+        font_style: FontStyle,
+        ch: char,
+        scale: f32,
+    ) -> Result<&(fontdue::Metrics, Vec<u8>), RenderError> {
+        let collection = self.fonts.get(&(font_family.to_string(), font_style))
+            .ok_or_else(|| RenderError::FontError(format!("Font not loaded: {} {:?}", font_family, font_style)))?;
+
+        let key = GlyphCacheKey {
+            font_family: font_family.to_string(),
+            font_style,
+            ch,
+            size_bits: scale.to_bits(),
+        };
+        Ok(self.glyph_cache.get_or_insert_with(key, || collection.rasterize(ch, scale)))
+    }
 }
 
 /// 渲染引擎
 pub struct Renderer {
     context: RenderContext,
+    /// 当前这次 `render_layout` 调用里，因资源加载失败而被换成占位图块的记录；
+    /// 每次 `render_layout` 开始时清空
+    warnings: Vec<RenderWarning>,
 }
 
 impl Renderer {
     pub fn new() -> Self {
         Self {
             context: RenderContext::new(),
+            warnings: Vec::new(),
         }
     }
-    
-    /// 渲染布局到图像
+
+    /// 渲染期间实际加载进缓存的图片，按来源字符串索引；`capture` 落盘快照时用它
+    /// 找出需要一起打包的图片
+    pub(crate) fn loaded_images(&self) -> impl Iterator<Item = (&String, &DynamicImage)> {
+        self.context.images.iter()
+    }
+
+    /// 直接把一张已经解码好的图片注入缓存，绕过 `RenderContext::load_image` 的磁盘 I/O；
+    /// `replay` 用捕获的副本重建图片缓存时使用
+    pub(crate) fn preload_image(&mut self, source: &str, image: DynamicImage) {
+        self.context.images.insert(source.to_string(), image);
+    }
+
+    /// 渲染布局到图像，返回渲染结果以及渲染过程中被换成占位图块的资源失败记录
+    /// （字体/图片加载失败不再中止整个渲染，见 [`Self::preload_resources`]）
     pub fn render_layout(
         &mut self,
         layout: &Layout,
         computed_layout: &ComputedLayout,
-    ) -> Result<RgbaImage, RenderError> {
+    ) -> Result<(RgbaImage, Vec<RenderWarning>), RenderError> {
+        self.warnings.clear();
+
         // 创建画布
         let canvas_width = computed_layout.canvas_size.width as u32;
         let canvas_height = computed_layout.canvas_size.height as u32;
         let mut image = ImageBuffer::new(canvas_width, canvas_height);
-        
+
         // 填充背景色
         let bg_color = color_to_rgba(&layout.canvas.background);
         for pixel in image.pixels_mut() {
             *pixel = bg_color;
         }
-        
-        // 预加载资源
-        self.preload_resources(&layout.elements)?;
-        
+
+        // 预加载资源（失败的资源只记一条警告，不中止渲染）
+        self.preload_resources(&layout.elements);
+
         // 渲染所有元素
         self.render_elements(&layout.elements, computed_layout, &mut image)?;
-        
-        Ok(image)
+
+        Ok((image, std::mem::take(&mut self.warnings)))
     }
-    
-    /// 预加载所有需要的资源
-    fn preload_resources(&mut self, elements: &[Element]) -> Result<(), RenderError> {
+
+    /// 预加载所有需要的资源：某个元素的字体/图片加载失败不会中止整棵树的预加载，
+    /// 只记一条 [`RenderWarning`]，对应元素在渲染阶段会画出占位图块
+    fn preload_resources(&mut self, elements: &[Element]) {
         for element in elements {
             match element {
                 Element::Text { properties, .. } => {
-                    self.context.load_font(&properties.font_family)?;
+                    if let Err(e) = self.context.load_font(&properties.font_family, properties.font_style) {
+                        self.warnings.push(RenderWarning {
+                            element_id: element.id().clone(),
+                            message: e.to_string(),
+                        });
+                    }
                 }
                 Element::Image { source, .. } => {
-                    self.context.load_image(source)?;
+                    if let Err(e) = self.context.load_image(source) {
+                        self.warnings.push(RenderWarning {
+                            element_id: element.id().clone(),
+                            message: e.to_string(),
+                        });
+                    }
                 }
                 _ => {}
             }
-            
+
             // 递归处理子元素
             if let Some(children) = element.children() {
-                self.preload_resources(children)?;
+                self.preload_resources(children);
             }
         }
-        Ok(())
     }
     
     /// 渲染元素列表
@@ -169,25 +719,202 @@ impl Renderer {
         match element {
             Element::Text { content, properties, .. } => {
                 self.render_text(content, properties, frame, image)?;
+                self.apply_filters(image, frame, &properties.filters);
             }
             Element::Image { source, properties, .. } => {
                 self.render_image(source, properties, frame, image)?;
+                self.apply_filters(image, frame, &properties.filters);
             }
             Element::Container { properties, .. } => {
                 self.render_container(properties, frame, image)?;
+                self.apply_filters(image, frame, &properties.filters);
             }
-            Element::VStack { .. } | Element::HStack { .. } | Element::ZStack { .. } => {
-                // 堆叠容器本身不需要渲染，只渲染子元素
+            Element::VStack { .. } | Element::HStack { .. } | Element::ZStack { .. } | Element::Grid { .. } => {
+                // 堆叠/网格容器本身不需要渲染，只渲染子元素
             }
             Element::Spacer { .. } => {
                 // Spacer不需要渲染
             }
         }
-        
+
         Ok(())
     }
-    
-    /// 渲染文本（简化版本，绘制文本框占位符）
+
+    /// 按声明顺序把 `filters` 依次施加在元素已经画好的 `frame` 区域上，对标 SVG filter
+    /// primitive 链的效果：前一个滤镜的输出是后一个滤镜的输入
+    fn apply_filters(&self, image: &mut RgbaImage, frame: &Rect, filters: &[Filter]) {
+        for filter in filters {
+            match filter {
+                Filter::GaussianBlur { std_deviation } => {
+                    self.apply_gaussian_blur(image, frame, *std_deviation);
+                }
+                Filter::DropShadow { dx, dy, std_deviation, color } => {
+                    self.apply_drop_shadow(image, frame, *dx, *dy, *std_deviation, color);
+                }
+                Filter::ColorMatrix { values } => {
+                    self.apply_color_matrix(image, frame, values);
+                }
+            }
+        }
+    }
+
+    /// `feGaussianBlur`：对 `frame`（留出模糊核半径的余量）覆盖的区域逐通道做一次
+    /// 可分离高斯模糊，RGB 和 alpha 都参与模糊，而不只是模糊轮廓蒙版
+    fn apply_gaussian_blur(&self, image: &mut RgbaImage, frame: &Rect, std_deviation: f32) {
+        if std_deviation <= 0.0 {
+            return;
+        }
+        let kernel = gaussian_kernel_from_std_deviation(std_deviation);
+        let margin = (kernel.len() / 2) as i32;
+
+        let (x0, y0, x1, y1) = match Self::expanded_bounds(image, frame, margin, margin) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let region_w = (x1 - x0) as usize;
+        let region_h = (y1 - y0) as usize;
+
+        let mut channels: Vec<Vec<u8>> = (0..4).map(|_| vec![0u8; region_w * region_h]).collect();
+        for y in 0..region_h {
+            for x in 0..region_w {
+                let pixel = image.get_pixel((x0 + x as i32) as u32, (y0 + y as i32) as u32);
+                for (c, plane) in channels.iter_mut().enumerate() {
+                    plane[y * region_w + x] = pixel[c];
+                }
+            }
+        }
+        for plane in channels.iter_mut() {
+            let blurred = blur_pass(plane, region_w, region_h, &kernel, Axis::Horizontal);
+            *plane = blur_pass(&blurred, region_w, region_h, &kernel, Axis::Vertical);
+        }
+
+        for y in 0..region_h {
+            for x in 0..region_w {
+                let idx = y * region_w + x;
+                let pixel = Rgba([channels[0][idx], channels[1][idx], channels[2][idx], channels[3][idx]]);
+                image.put_pixel((x0 + x as i32) as u32, (y0 + y as i32) as u32, pixel);
+            }
+        }
+    }
+
+    /// `feDropShadow`：把已经画好的内容的 alpha 轮廓模糊、偏移、按 `color` 上色，
+    /// 再用 "本体盖在投影上面"（src-over-shadow）的公式合成，即使投影是在本体画完
+    /// 之后才补画的，视觉效果也和投影真的画在本体下面一样
+    fn apply_drop_shadow(&self, image: &mut RgbaImage, frame: &Rect, dx: f32, dy: f32, std_deviation: f32, color: &Color) {
+        let kernel = gaussian_kernel_from_std_deviation(std_deviation.max(0.0));
+        let margin = (kernel.len() / 2) as i32 + dx.abs().ceil() as i32 + dy.abs().ceil() as i32;
+
+        let (x0, y0, x1, y1) = match Self::expanded_bounds(image, frame, margin, margin) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let region_w = (x1 - x0) as usize;
+        let region_h = (y1 - y0) as usize;
+
+        let mut alpha = vec![0u8; region_w * region_h];
+        for y in 0..region_h {
+            for x in 0..region_w {
+                alpha[y * region_w + x] = image.get_pixel((x0 + x as i32) as u32, (y0 + y as i32) as u32)[3];
+            }
+        }
+        let alpha = blur_pass(&alpha, region_w, region_h, &kernel, Axis::Horizontal);
+        let alpha = blur_pass(&alpha, region_w, region_h, &kernel, Axis::Vertical);
+
+        let shadow_rgb = [color.r as f32, color.g as f32, color.b as f32];
+        let shadow_alpha_scale = color.a as f32 / 255.0;
+        let dx = dx.round() as i32;
+        let dy = dy.round() as i32;
+
+        for y in 0..region_h {
+            for x in 0..region_w {
+                let src_x = x as i32 - dx;
+                let src_y = y as i32 - dy;
+                if src_x < 0 || src_y < 0 || src_x as usize >= region_w || src_y as usize >= region_h {
+                    continue;
+                }
+                let shadow_a = (alpha[src_y as usize * region_w + src_x as usize] as f32 / 255.0) * shadow_alpha_scale;
+                if shadow_a <= 0.0 {
+                    continue;
+                }
+
+                let px = (x0 + x as i32) as u32;
+                let py = (y0 + y as i32) as u32;
+                let existing = image.get_pixel(px, py);
+                let src_a = existing[3] as f32 / 255.0;
+                let out_a = src_a + shadow_a * (1.0 - src_a);
+                if out_a <= 0.0 {
+                    continue;
+                }
+                let blend_channel = |src_c: u8, shadow_c: f32| -> u8 {
+                    ((src_c as f32 * src_a + shadow_c * shadow_a * (1.0 - src_a)) / out_a)
+                        .round()
+                        .clamp(0.0, 255.0) as u8
+                };
+                let out = Rgba([
+                    blend_channel(existing[0], shadow_rgb[0]),
+                    blend_channel(existing[1], shadow_rgb[1]),
+                    blend_channel(existing[2], shadow_rgb[2]),
+                    (out_a * 255.0).round().clamp(0.0, 255.0) as u8,
+                ]);
+                image.put_pixel(px, py, out);
+            }
+        }
+    }
+
+    /// `feColorMatrix`：`out = M * [r, g, b, a, 1]`，`r/g/b/a` 取 0-255 的原始通道值，
+    /// 矩阵最后一列是已经在 0-255 量纲下的加性常数（不是 SVG 规范里 0-1 量纲的偏移量），
+    /// 这样矩阵系数和结果都能直接对着 0-255 的 `Color` 调，不需要额外换算
+    fn apply_color_matrix(&self, image: &mut RgbaImage, frame: &Rect, values: &[f32; 20]) {
+        let x0 = frame.origin.x.floor().max(0.0) as u32;
+        let y0 = frame.origin.y.floor().max(0.0) as u32;
+        let x1 = ((frame.origin.x + frame.size.width).ceil().max(0.0) as u32).min(image.width());
+        let y1 = ((frame.origin.y + frame.size.height).ceil().max(0.0) as u32).min(image.height());
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let pixel = image.get_pixel(x, y);
+                let input = [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32, pixel[3] as f32, 1.0];
+                let mut out = [0.0f32; 4];
+                for (row, channel) in out.iter_mut().enumerate() {
+                    let base = row * 5;
+                    *channel = (0..5).map(|col| values[base + col] * input[col]).sum();
+                }
+                image.put_pixel(
+                    x,
+                    y,
+                    Rgba([
+                        out[0].round().clamp(0.0, 255.0) as u8,
+                        out[1].round().clamp(0.0, 255.0) as u8,
+                        out[2].round().clamp(0.0, 255.0) as u8,
+                        out[3].round().clamp(0.0, 255.0) as u8,
+                    ]),
+                );
+            }
+        }
+    }
+
+    /// 把 `frame` 向外扩张 `margin_x`/`margin_y` 像素并裁剪到图像边界内，返回
+    /// `(x0, y0, x1, y1)`；扩张后区域退化成空时返回 `None`
+    fn expanded_bounds(
+        image: &RgbaImage,
+        frame: &Rect,
+        margin_x: i32,
+        margin_y: i32,
+    ) -> Option<(i32, i32, i32, i32)> {
+        let x0 = (frame.origin.x.floor() as i32 - margin_x).max(0);
+        let y0 = (frame.origin.y.floor() as i32 - margin_y).max(0);
+        let x1 = ((frame.origin.x + frame.size.width).ceil() as i32 + margin_x).min(image.width() as i32);
+        let y1 = ((frame.origin.y + frame.size.height).ceil() as i32 + margin_y).min(image.height() as i32);
+        if x1 <= x0 || y1 <= y0 {
+            None
+        } else {
+            Some((x0, y0, x1, y1))
+        }
+    }
+
+    /// 渲染文本：先用 [`text_layout::layout_paragraph`] 把整段文字排版成按视觉顺序
+    /// 摆好坐标的字符列表（grapheme cluster 切分、双向重排、按词贪心换行、
+    /// `Justified` 词间距展开都在排版阶段完成），渲染阶段只需要逐个栅格化、blit
     fn render_text(
         &mut self,
         content: &str,
@@ -195,66 +922,71 @@ impl Renderer {
         frame: &Rect,
         image: &mut RgbaImage,
     ) -> Result<(), RenderError> {
-        let font = self.context.fonts.get(&properties.font_family)
-            .ok_or_else(|| RenderError::FontError(format!("Font not loaded: {}", properties.font_family)))?;
-        
+        let font = match self.context.fonts.get(&(properties.font_family.clone(), properties.font_style)) {
+            Some(font) => font,
+            None => {
+                self.draw_placeholder_tile(image, frame);
+                return Ok(());
+            }
+        };
+
         let scale = properties.font_size;
         let color = color_to_rgba(&properties.color);
-        
-        // 计算文本位置
-        let mut x = frame.origin.x;
-        // 简化基线计算：将文本基线设置在frame底部向上偏移一定距离
-        // 这样可以确保文本在frame内正确显示
-        let baseline_offset = properties.font_size * 0.2; // 字体大小的20%作为底部边距
-        let y = frame.origin.y + frame.size.height - baseline_offset;
-        
-        // 根据对齐方式调整x位置
-        match properties.alignment {
-            TextAlignment::Leading => {
-                // x已经是正确的
-            }
-            TextAlignment::Center => {
-                let text_width = self.measure_text_width(content, font, scale);
-                x = frame.origin.x + (frame.size.width - text_width) / 2.0;
-            }
-            TextAlignment::Trailing => {
-                let text_width = self.measure_text_width(content, font, scale);
-                x = frame.origin.x + frame.size.width - text_width;
-            }
-            TextAlignment::Justified => {
-                // 简化实现，当作左对齐处理
-            }
-        }
-        
-        // 渲染每个字符
-        for ch in content.chars() {
-            let (metrics, bitmap) = font.rasterize(ch, scale);
-            
+        let line_height_pixels = properties.font_size * properties.line_height;
+        let ascent = font.ascent(scale);
+
+        let wrap_mode = match properties.line_break_mode {
+            LineBreakMode::WordWrap => crate::text_layout::WrapMode::Word,
+            LineBreakMode::CharWrap => crate::text_layout::WrapMode::Char,
+            LineBreakMode::Clip
+            | LineBreakMode::TruncateHead
+            | LineBreakMode::TruncateTail
+            | LineBreakMode::TruncateMiddle => crate::text_layout::WrapMode::NoWrap,
+        };
+
+        let glyphs = crate::text_layout::layout_paragraph(
+            content,
+            frame.size.width,
+            line_height_pixels,
+            properties.alignment,
+            wrap_mode,
+            properties.max_lines,
+            |cluster| cluster.chars().map(|ch| font.metrics(ch, scale).advance_width).sum(),
+        );
+
+        // 渲染每个字符：主字体没有这个字形（CJK、emoji 或请求字体本身没覆盖的字符）
+        // 就沿回退链找第一个能画出它的字体，链末尾的内嵌 DejaVu Sans 兜底
+        for glyph in &glyphs {
+            let (metrics, bitmap) = self.context.rasterize_glyph(
+                &properties.font_family,
+                properties.font_style,
+                glyph.ch,
+                scale,
+            )?;
+
             // 绘制字符位图
             if metrics.width > 0 {
                 for (bitmap_y, row) in bitmap.chunks(metrics.width).enumerate() {
-                for (bitmap_x, &alpha) in row.iter().enumerate() {
-                    if alpha > 0 {
-                        let pixel_x = (x + bitmap_x as f32 + metrics.xmin as f32) as u32;
-                        // 修正基线对齐：y是基线位置，bitmap从上到下，需要正确处理垂直偏移
-                        let pixel_y = (y + bitmap_y as f32 + metrics.ymin as f32) as u32;
-                        
-                        if pixel_x < image.width() && pixel_y < image.height() {
-                            let existing_pixel = image.get_pixel(pixel_x, pixel_y);
-                            let blended = blend_colors(*existing_pixel, color, alpha);
-                            image.put_pixel(pixel_x, pixel_y, blended);
+                    for (bitmap_x, &alpha) in row.iter().enumerate() {
+                        if alpha > 0 {
+                            let pixel_x = (frame.origin.x + glyph.x + bitmap_x as f32 + metrics.xmin as f32) as u32;
+                            // 修正基线对齐：glyph.y 是这一行的顶部偏移，加上 ascent 才是基线
+                            let pixel_y = (frame.origin.y + glyph.y + ascent + bitmap_y as f32 + metrics.ymin as f32) as u32;
+
+                            if pixel_x < image.width() && pixel_y < image.height() {
+                                let existing_pixel = image.get_pixel(pixel_x, pixel_y);
+                                let blended = blend_colors(*existing_pixel, color, alpha);
+                                image.put_pixel(pixel_x, pixel_y, blended);
+                            }
                         }
                     }
-                 }
-             }
-             }
-             
-             x += metrics.advance_width;
-         }
-        
-        println!("📝 渲染文本: '{}' 在位置 ({}, {}) 尺寸 {}x{}", 
+                }
+            }
+        }
+
+        println!("📝 渲染文本: '{}' 在位置 ({}, {}) 尺寸 {}x{}",
                 content, frame.origin.x, frame.origin.y, frame.size.width, frame.size.height);
-        
+
         Ok(())
     }
     
@@ -266,26 +998,55 @@ impl Renderer {
         frame: &Rect,
         image: &mut RgbaImage,
     ) -> Result<(), RenderError> {
-        let src_image = self.context.images.get(source)
-            .ok_or_else(|| RenderError::ImageError(image::ImageError::Unsupported(
-                image::error::UnsupportedError::from_format_and_kind(
-                    image::error::ImageFormatHint::Unknown,
-                    image::error::UnsupportedErrorKind::GenericFeature(format!("Image not loaded: {}", source))
-                )
-            )))?;
-        
+        let src_image = match self.context.images.get(source) {
+            Some(img) => img,
+            None => {
+                self.draw_placeholder_tile(image, frame);
+                return Ok(());
+            }
+        };
+
+        // 投影要在图片本体之前画，这样本体才会叠在投影上面
+        if let Some(shadow) = &properties.shadow {
+            self.draw_shadow(image, frame, &Corners::all(properties.corner_radius), shadow);
+        }
+
         // 转换为RGBA格式
         let src_rgba = src_image.to_rgba8();
-        
-        // 计算缩放后的尺寸
-        let (scaled_width, scaled_height) = self.calculate_scaled_size(
-            src_rgba.width(),
-            src_rgba.height(),
-            frame.size.width as u32,
-            frame.size.height as u32,
-            properties.scale_mode,
-        );
-        
+
+        // `.svg` 来源按 `preserveAspectRatio` 的 align/meetOrSlice 模型计算缩放和对齐，
+        // 光栅图片继续走原来的 `scale_mode`（始终居中）
+        let (scaled_width, scaled_height, draw_x, draw_y) =
+            if source.to_ascii_lowercase().ends_with(".svg") {
+                let (w, h, offset_x, offset_y) = compute_preserve_aspect_ratio_rect(
+                    src_rgba.width() as f32,
+                    src_rgba.height() as f32,
+                    frame.size.width,
+                    frame.size.height,
+                    &properties.preserve_aspect_ratio,
+                );
+                (
+                    w.round().max(1.0) as u32,
+                    h.round().max(1.0) as u32,
+                    frame.origin.x + offset_x,
+                    frame.origin.y + offset_y,
+                )
+            } else {
+                let (w, h) = self.calculate_scaled_size(
+                    src_rgba.width(),
+                    src_rgba.height(),
+                    frame.size.width as u32,
+                    frame.size.height as u32,
+                    properties.scale_mode,
+                );
+                (
+                    w,
+                    h,
+                    frame.origin.x + (frame.size.width - w as f32) / 2.0,
+                    frame.origin.y + (frame.size.height - h as f32) / 2.0,
+                )
+            };
+
         // 缩放图片
         let scaled_image = image::imageops::resize(
             &src_rgba,
@@ -294,10 +1055,6 @@ impl Renderer {
             image::imageops::FilterType::Lanczos3,
         );
         
-        // 计算绘制位置（居中）
-        let draw_x = frame.origin.x + (frame.size.width - scaled_width as f32) / 2.0;
-        let draw_y = frame.origin.y + (frame.size.height - scaled_height as f32) / 2.0;
-        
         // 绘制图片
         for (src_x, src_y, src_pixel) in scaled_image.enumerate_pixels() {
             let dst_x = (draw_x + src_x as f32) as u32;
@@ -305,12 +1062,28 @@ impl Renderer {
             
             if dst_x < image.width() && dst_y < image.height() {
                 let mut pixel = *src_pixel;
-                
+
+                // 裁剪到和容器同款的圆角蒙版：超出 `frame` 圆角范围的像素直接跳过，
+                // 保证圆角容器里的图片不会在角上露出方形的边角
+                let coverage = rounded_rect_coverage(
+                    dst_x as f32 + 0.5 - frame.origin.x,
+                    dst_y as f32 + 0.5 - frame.origin.y,
+                    frame.size.width,
+                    frame.size.height,
+                    &Corners::all(properties.corner_radius),
+                );
+                if coverage <= 0.0 {
+                    continue;
+                }
+                if coverage < 1.0 {
+                    pixel[3] = (pixel[3] as f32 * coverage).round().clamp(0.0, 255.0) as u8;
+                }
+
                 // 应用透明度
                 if properties.opacity < 1.0 {
                     pixel[3] = (pixel[3] as f32 * properties.opacity) as u8;
                 }
-                
+
                 // 应用着色
                 if let Some(tint) = &properties.tint_color {
                     pixel[0] = ((pixel[0] as f32 * tint.r as f32) / 255.0) as u8;
@@ -335,82 +1108,188 @@ impl Renderer {
         frame: &Rect,
         image: &mut RgbaImage,
     ) -> Result<(), RenderError> {
+        // 投影要在容器本体之前画，这样本体（背景、边框）才会叠在投影上面
+        if let Some(shadow) = &properties.shadow {
+            self.draw_shadow(image, frame, &properties.corners, shadow);
+        }
+
         // 绘制背景
         if properties.background.a > 0 {
             let bg_color = color_to_rgba(&properties.background);
-            self.fill_rect(image, frame, bg_color);
-        }
-        
-        // 绘制边框
-        if properties.border_width > 0.0 {
-            let border_color = color_to_rgba(&properties.border_color);
-            self.draw_border(image, frame, properties.border_width, border_color);
+            self.fill_rect(image, frame, bg_color, &properties.corners);
         }
-        
+
+        // 绘制边框：每条边各自的宽度/颜色/线型独立处理，不再要求四边一致
+        self.draw_border(image, frame, &properties.border, &properties.corners);
+
         Ok(())
     }
-    
-    /// 填充矩形
-    fn fill_rect(&self, image: &mut RgbaImage, rect: &Rect, color: Rgba<u8>) {
-        let x1 = rect.origin.x as u32;
-        let y1 = rect.origin.y as u32;
-        let x2 = (rect.origin.x + rect.size.width) as u32;
-        let y2 = (rect.origin.y + rect.size.height) as u32;
-        
-        for y in y1..y2.min(image.height()) {
-            for x in x1..x2.min(image.width()) {
-                image.put_pixel(x, y, color);
+
+    /// 投影：在元素本体画出来之前，按 `frame`（外扩模糊半径）分配一张 alpha 蒙版，
+    /// 先按 `corners` 填实轮廓剪影，再做一次水平、一次垂直的一维高斯模糊
+    /// （可分离卷积，比直接做二维卷积开销小得多），最后用 `shadow.color` 染色、
+    /// 按 `shadow.opacity` 叠加混合到 `frame.origin + offset` 对应的位置
+    fn draw_shadow(&self, image: &mut RgbaImage, frame: &Rect, corners: &Corners, shadow: &ShadowStyle) {
+        let kernel = gaussian_kernel(shadow.blur_radius);
+        let margin = (kernel.len() / 2) as i32;
+
+        let mask_width = (frame.size.width.ceil() as i32 + margin * 2).max(0) as usize;
+        let mask_height = (frame.size.height.ceil() as i32 + margin * 2).max(0) as usize;
+        if mask_width == 0 || mask_height == 0 {
+            return;
+        }
+
+        let mut mask = vec![0u8; mask_width * mask_height];
+        for y in 0..mask_height {
+            for x in 0..mask_width {
+                let local_x = x as f32 - margin as f32;
+                let local_y = y as f32 - margin as f32;
+                if point_in_rounded_rect(local_x, local_y, frame.size.width, frame.size.height, corners) {
+                    mask[y * mask_width + x] = 255;
+                }
             }
         }
-    }
-    
-    /// 绘制边框
-    fn draw_border(&self, image: &mut RgbaImage, rect: &Rect, width: f32, color: Rgba<u8>) {
-        let border_width = width as u32;
-        let x1 = rect.origin.x as u32;
-        let y1 = rect.origin.y as u32;
-        let x2 = (rect.origin.x + rect.size.width) as u32;
-        let y2 = (rect.origin.y + rect.size.height) as u32;
-        
-        // 上边框
-        for y in y1..y1.saturating_add(border_width).min(image.height()) {
-            for x in x1..x2.min(image.width()) {
-                image.put_pixel(x, y, color);
+
+        let mask = blur_pass(&mask, mask_width, mask_height, &kernel, Axis::Horizontal);
+        let mask = blur_pass(&mask, mask_width, mask_height, &kernel, Axis::Vertical);
+
+        let shadow_alpha_scale = shadow.opacity.clamp(0.0, 1.0) * (shadow.color.a as f32 / 255.0);
+        let shadow_rgb = Rgba([shadow.color.r, shadow.color.g, shadow.color.b, 255]);
+
+        let origin_x = frame.origin.x + shadow.offset_x - margin as f32;
+        let origin_y = frame.origin.y + shadow.offset_y - margin as f32;
+
+        for y in 0..mask_height {
+            for x in 0..mask_width {
+                let alpha = (mask[y * mask_width + x] as f32 * shadow_alpha_scale).round().clamp(0.0, 255.0) as u8;
+                if alpha == 0 {
+                    continue;
+                }
+                let px = origin_x + x as f32;
+                let py = origin_y + y as f32;
+                if px < 0.0 || py < 0.0 {
+                    continue;
+                }
+                let (px, py) = (px as u32, py as u32);
+                if px < image.width() && py < image.height() {
+                    let existing = image.get_pixel(px, py);
+                    let blended = blend_colors(*existing, shadow_rgb, alpha);
+                    image.put_pixel(px, py, blended);
+                }
             }
         }
-        
-        // 下边框
-        for y in y2.saturating_sub(border_width)..y2.min(image.height()) {
+    }
+
+    /// 填充矩形：`corners` 四角都是 0 时就是普通直角矩形，非 0 时按带符号距离场算每个
+    /// 像素的覆盖率并调制 alpha，圆角边缘因此是抗锯齿的，而不是阶梯状的硬边
+    fn fill_rect(&self, image: &mut RgbaImage, rect: &Rect, color: Rgba<u8>, corners: &Corners) {
+        let x1 = rect.origin.x.floor().max(0.0) as u32;
+        let y1 = rect.origin.y.floor().max(0.0) as u32;
+        let x2 = (rect.origin.x + rect.size.width).ceil().max(0.0) as u32;
+        let y2 = (rect.origin.y + rect.size.height).ceil().max(0.0) as u32;
+
+        for y in y1..y2.min(image.height()) {
             for x in x1..x2.min(image.width()) {
-                image.put_pixel(x, y, color);
+                let local_x = x as f32 + 0.5 - rect.origin.x;
+                let local_y = y as f32 + 0.5 - rect.origin.y;
+                let coverage = rounded_rect_coverage(local_x, local_y, rect.size.width, rect.size.height, corners);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                let alpha = (color.0[3] as f32 * coverage).round().clamp(0.0, 255.0) as u8;
+                if alpha == 0 {
+                    continue;
+                }
+                let existing = *image.get_pixel(x, y);
+                let blended = alpha_blend(existing, Rgba([color.0[0], color.0[1], color.0[2], alpha]));
+                image.put_pixel(x, y, blended);
             }
         }
-        
-        // 左边框
-        for x in x1..x1.saturating_add(border_width).min(image.width()) {
-            for y in y1..y2.min(image.height()) {
-                image.put_pixel(x, y, color);
-            }
+    }
+
+    /// 绘制边框：每个像素先按离矩形边界最近的那条边决定套用哪条边的宽度/颜色/线型
+    /// （`nearest_rect_side`），再和单一边框一样按到圆角矩形边界的带符号距离落在
+    /// `[-width/2, width/2]` 内做覆盖率抗锯齿；`style` 额外按沿边方向的坐标决定
+    /// 虚线/点线在这个位置是否该画
+    fn draw_border(&self, image: &mut RgbaImage, rect: &Rect, border: &Border, corners: &Corners) {
+        if border.top.width <= 0.0
+            && border.right.width <= 0.0
+            && border.bottom.width <= 0.0
+            && border.left.width <= 0.0
+        {
+            return;
         }
-        
-        // 右边框
-        for x in x2.saturating_sub(border_width)..x2.min(image.width()) {
-            for y in y1..y2.min(image.height()) {
-                image.put_pixel(x, y, color);
+
+        let x1 = rect.origin.x.floor().max(0.0) as u32;
+        let y1 = rect.origin.y.floor().max(0.0) as u32;
+        let x2 = (rect.origin.x + rect.size.width).ceil().max(0.0) as u32;
+        let y2 = (rect.origin.y + rect.size.height).ceil().max(0.0) as u32;
+
+        for y in y1..y2.min(image.height()) {
+            for x in x1..x2.min(image.width()) {
+                let local_x = x as f32 + 0.5 - rect.origin.x;
+                let local_y = y as f32 + 0.5 - rect.origin.y;
+
+                let side = nearest_rect_side(local_x, local_y, rect.size.width, rect.size.height);
+                let border_side = border.side(side);
+                if border_side.width <= 0.0 {
+                    continue;
+                }
+
+                let position_along_edge = match side {
+                    RectSide::Top | RectSide::Bottom => local_x,
+                    RectSide::Left | RectSide::Right => local_y,
+                };
+                if !border_side.style.is_visible_at(position_along_edge) {
+                    continue;
+                }
+
+                let distance = sdf_rounded_rect(local_x, local_y, rect.size.width, rect.size.height, corners);
+                let coverage = (0.5 - (distance.abs() - border_side.width / 2.0)).clamp(0.0, 1.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                let color = color_to_rgba(&border_side.color);
+                let alpha = (color.0[3] as f32 * coverage).round().clamp(0.0, 255.0) as u8;
+                if alpha == 0 {
+                    continue;
+                }
+                let existing = *image.get_pixel(x, y);
+                let blended = alpha_blend(existing, Rgba([color.0[0], color.0[1], color.0[2], alpha]));
+                image.put_pixel(x, y, blended);
             }
         }
     }
     
-    /// 测量文本宽度
-    fn measure_text_width(&self, text: &str, font: &Font, scale: f32) -> f32 {
-        let mut width = 0.0;
-        for ch in text.chars() {
-            let metrics = font.metrics(ch, scale);
-            width += metrics.advance_width;
+    /// 资源加载失败（字体/图片）时代替原本内容画出的“缺失资源”占位图块：填满
+    /// `frame` 的浅红色底，加一条对角十字，一眼就能和正常渲染结果区分开，
+    /// 不会像空白或中止渲染那样让人以为整块区域本该就是空的
+    fn draw_placeholder_tile(&self, image: &mut RgbaImage, frame: &Rect) {
+        const FILL: Rgba<u8> = Rgba([255, 200, 200, 255]);
+        const CROSS: Rgba<u8> = Rgba([200, 60, 60, 255]);
+        const CROSS_THICKNESS: f32 = 2.0;
+
+        let x1 = frame.origin.x.floor().max(0.0) as u32;
+        let y1 = frame.origin.y.floor().max(0.0) as u32;
+        let x2 = (frame.origin.x + frame.size.width).ceil().max(0.0) as u32;
+        let y2 = (frame.origin.y + frame.size.height).ceil().max(0.0) as u32;
+
+        for y in y1..y2.min(image.height()) {
+            for x in x1..x2.min(image.width()) {
+                let local_x = x as f32 + 0.5 - frame.origin.x;
+                let local_y = y as f32 + 0.5 - frame.origin.y;
+
+                // 到两条对角线的距离，任一条落在十字线宽之内就画十字颜色；两条对角线
+                // 按宽高比归一化到同一个尺度，矩形不是正方形时也能画成真正贴着四角的对角线
+                let aspect = frame.size.width / frame.size.height.max(0.001);
+                let on_diagonal = (local_x - local_y * aspect).abs() <= CROSS_THICKNESS
+                    || (local_x + local_y * aspect - frame.size.width).abs() <= CROSS_THICKNESS;
+
+                image.put_pixel(x, y, if on_diagonal { CROSS } else { FILL });
+            }
         }
-        width
     }
-    
+
     /// 计算缩放后的尺寸
     fn calculate_scaled_size(
         &self,
@@ -445,6 +1324,47 @@ impl Renderer {
     }
 }
 
+/// 按 SVG `preserveAspectRatio` 的 align/meetOrSlice 模型，把 `src_w x src_h` 的内容
+/// 映射进 `dst_w x dst_h` 的目标框：返回 `(缩放后宽, 缩放后高, 相对目标框左上角的偏移 x, y)`。
+/// `align` 为 `None`（对应 `none` 关键字）时不保持宽高比，非均匀拉伸铺满整个目标框；
+/// 否则 `Meet` 取较小缩放比完全落在框内（可能留白），`Slice` 取较大缩放比盖满框
+/// （可能溢出，溢出部分由调用方按 `frame` 边界/圆角蒙版裁切）
+fn compute_preserve_aspect_ratio_rect(
+    src_w: f32,
+    src_h: f32,
+    dst_w: f32,
+    dst_h: f32,
+    par: &PreserveAspectRatio,
+) -> (f32, f32, f32, f32) {
+    let align = match par.align {
+        Some(align) => align,
+        None => return (dst_w, dst_h, 0.0, 0.0),
+    };
+
+    let scale_x = dst_w / src_w;
+    let scale_y = dst_h / src_h;
+    let scale = match par.mode {
+        MeetOrSlice::Meet => scale_x.min(scale_y),
+        MeetOrSlice::Slice => scale_x.max(scale_y),
+    };
+
+    let scaled_w = src_w * scale;
+    let scaled_h = src_h * scale;
+
+    let offset_x = match align {
+        Align9::XMinYMin | Align9::XMinYMid | Align9::XMinYMax => 0.0,
+        Align9::XMidYMin | Align9::XMidYMid | Align9::XMidYMax => (dst_w - scaled_w) / 2.0,
+        Align9::XMaxYMin | Align9::XMaxYMid | Align9::XMaxYMax => dst_w - scaled_w,
+    };
+    let offset_y = match align {
+        Align9::XMinYMin | Align9::XMidYMin | Align9::XMaxYMin => 0.0,
+        Align9::XMinYMid | Align9::XMidYMid | Align9::XMaxYMid => (dst_h - scaled_h) / 2.0,
+        Align9::XMinYMax | Align9::XMidYMax | Align9::XMaxYMax => dst_h - scaled_h,
+    };
+
+    (scaled_w, scaled_h, offset_x, offset_y)
+}
+
 /// 将Color转换为Rgba<u8>
 fn color_to_rgba(color: &Color) -> Rgba<u8> {
     Rgba([color.r, color.g, color.b, color.a])
@@ -2,13 +2,25 @@
 
 use crate::layout::*;
 use cassowary::{
-    AddConstraintError, Solver, SuggestValueError, Variable, WeightedRelation::*, strength::*,
+    AddConstraintError, AddEditVariableError, Solver, SuggestValueError, Variable,
+    WeightedRelation::*, strength::*,
 };
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
-use rusttype::{Font, Scale, point};
+use rusttype::{Font, Scale};
 use image::DynamicImage;
 
+/// 布局求解结果缓存的最大条目数，超出后淘汰最久未被访问的条目
+const LAYOUT_CACHE_CAPACITY: usize = 32;
+
+fn hash_layout(layout: &Layout) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    layout.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[derive(Debug)]
 pub enum SolverError {
     ConstraintError(String),
@@ -42,6 +54,12 @@ impl From<SuggestValueError> for SolverError {
     }
 }
 
+impl From<AddEditVariableError> for SolverError {
+    fn from(err: AddEditVariableError) -> Self {
+        SolverError::ConstraintError(format!("{:?}", err))
+    }
+}
+
 /// 元素变量集合
 #[derive(Debug)]
 struct ElementVariables {
@@ -70,6 +88,146 @@ impl ElementVariables {
     }
 }
 
+/// VStack/HStack 的排布方向：决定 `add_stack_constraints` 把哪一对变量当作主轴（排列方向）
+/// 和交叉轴（对齐方向），让同一份堆叠逻辑同时服务于垂直和水平两种容器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StackDirection {
+    Vertical,
+    Horizontal,
+}
+
+/// 交叉轴上子元素相对容器的对齐方式，与 `Alignment` 的含义一致，只是与方向无关
+enum CrossAlignment {
+    Leading,
+    Center,
+    Trailing,
+}
+
+impl StackDirection {
+    /// 把 `StackProperties::alignment` 翻译成方向无关的交叉轴对齐：垂直容器的交叉轴是水平
+    /// 方向（Leading/Center/Trailing），水平容器的交叉轴是垂直方向（Top/Center/Bottom）
+    fn cross_alignment(self, alignment: Alignment) -> Option<CrossAlignment> {
+        match (self, alignment) {
+            (StackDirection::Vertical, Alignment::Leading) => Some(CrossAlignment::Leading),
+            (StackDirection::Vertical, Alignment::Center) => Some(CrossAlignment::Center),
+            (StackDirection::Vertical, Alignment::Trailing) => Some(CrossAlignment::Trailing),
+            (StackDirection::Horizontal, Alignment::Top) => Some(CrossAlignment::Leading),
+            (StackDirection::Horizontal, Alignment::Center) => Some(CrossAlignment::Center),
+            (StackDirection::Horizontal, Alignment::Bottom) => Some(CrossAlignment::Trailing),
+            _ => None,
+        }
+    }
+
+    /// 取盒约束在主轴上的 max：垂直方向看 max.height，水平方向看 max.width
+    fn main_max(self, bounds: &BoxConstraints) -> f32 {
+        match self {
+            StackDirection::Vertical => bounds.max.height,
+            StackDirection::Horizontal => bounds.max.width,
+        }
+    }
+
+    /// 主轴方向上起始边（第一个子元素之前）的内边距：垂直方向是 top，水平方向是 left
+    fn main_padding_start(self, padding: &Padding) -> f32 {
+        match self {
+            StackDirection::Vertical => padding.top,
+            StackDirection::Horizontal => padding.left,
+        }
+    }
+
+    /// 主轴方向上终止边（最后一个子元素之后）的内边距：垂直方向是 bottom，水平方向是 right
+    fn main_padding_end(self, padding: &Padding) -> f32 {
+        match self {
+            StackDirection::Vertical => padding.bottom,
+            StackDirection::Horizontal => padding.right,
+        }
+    }
+
+    /// 交叉轴起始边的内边距：垂直方向的交叉轴是水平方向（left），水平方向的交叉轴是
+    /// 垂直方向（top）
+    fn cross_padding_start(self, padding: &Padding) -> f32 {
+        match self {
+            StackDirection::Vertical => padding.left,
+            StackDirection::Horizontal => padding.top,
+        }
+    }
+
+    /// 交叉轴终止边的内边距，与 `cross_padding_start` 相对
+    fn cross_padding_end(self, padding: &Padding) -> f32 {
+        match self {
+            StackDirection::Vertical => padding.right,
+            StackDirection::Horizontal => padding.bottom,
+        }
+    }
+
+    /// 子元素自己的外边距在主轴起始方向上的分量，含义与 `main_padding_start` 相同，
+    /// 只是作用对象从容器的 padding 换成子元素自己的 margin
+    fn main_margin_start(self, margin: &Margin) -> f32 {
+        match self {
+            StackDirection::Vertical => margin.top,
+            StackDirection::Horizontal => margin.left,
+        }
+    }
+
+    /// 子元素自己的外边距在主轴终止方向上的分量，与 `main_margin_start` 相对
+    fn main_margin_end(self, margin: &Margin) -> f32 {
+        match self {
+            StackDirection::Vertical => margin.bottom,
+            StackDirection::Horizontal => margin.right,
+        }
+    }
+
+    /// 子元素自己的外边距在交叉轴起始方向上的分量
+    fn cross_margin_start(self, margin: &Margin) -> f32 {
+        match self {
+            StackDirection::Vertical => margin.left,
+            StackDirection::Horizontal => margin.top,
+        }
+    }
+
+    /// 子元素自己的外边距在交叉轴终止方向上的分量
+    fn cross_margin_end(self, margin: &Margin) -> f32 {
+        match self {
+            StackDirection::Vertical => margin.right,
+            StackDirection::Horizontal => margin.bottom,
+        }
+    }
+}
+
+/// 从一个元素的 `ElementVariables` 中按方向取出主轴（start/end/size）和交叉轴
+/// （cross_start/cross_center/cross_end）变量；垂直方向主轴是 y/bottom/height，
+/// 水平方向主轴是 x/right/width，交叉轴互换
+struct AxisVars {
+    start: Variable,
+    end: Variable,
+    size: Variable,
+    cross_start: Variable,
+    cross_center: Variable,
+    cross_end: Variable,
+}
+
+impl AxisVars {
+    fn from_direction(direction: StackDirection, vars: &ElementVariables) -> Self {
+        match direction {
+            StackDirection::Vertical => Self {
+                start: vars.y,
+                end: vars.bottom,
+                size: vars.height,
+                cross_start: vars.x,
+                cross_center: vars.center_x,
+                cross_end: vars.right,
+            },
+            StackDirection::Horizontal => Self {
+                start: vars.x,
+                end: vars.right,
+                size: vars.width,
+                cross_start: vars.y,
+                cross_center: vars.center_y,
+                cross_end: vars.bottom,
+            },
+        }
+    }
+}
+
 /// 约束求解器
 pub struct LayoutSolver {
     solver: Solver,
@@ -77,6 +235,17 @@ pub struct LayoutSolver {
     canvas_vars: ElementVariables,
     fonts: HashMap<String, Font<'static>>,
     images: HashMap<String, DynamicImage>,
+    /// 上一次 `solve_layout` 求解的元素树，供 `resize_canvas` 复用以重新提取结果，
+    /// 不需要调用方再传一遍
+    last_elements: Vec<Element>,
+    /// `solve_layout` 结果缓存，键为 (布局哈希, 画布宽, 画布高)。重复以相同尺寸求解
+    /// 同一棵布局树（常见于反复渲染同一帧或预览）时可以跳过整棵约束树的构建与求解
+    layout_cache: HashMap<(u64, u32, u32), ComputedLayout>,
+    /// 记录缓存键的访问顺序（命中时挪到队尾），用于容量超限时淘汰最久未被访问的条目
+    layout_cache_order: std::collections::VecDeque<(u64, u32, u32)>,
+    /// 自顶向下为每个元素推导出的盒约束（可用尺寸范围），求解前计算一次，
+    /// 供文本换行宽度推断和容器尺寸收敛（clamp）使用
+    box_constraints: HashMap<ElementId, BoxConstraints>,
 }
 
 impl Default for LayoutSolver {
@@ -93,9 +262,19 @@ impl LayoutSolver {
             canvas_vars: ElementVariables::new(),
             fonts: HashMap::new(),
             images: HashMap::new(),
+            last_elements: Vec::new(),
+            layout_cache: HashMap::new(),
+            layout_cache_order: std::collections::VecDeque::new(),
+            box_constraints: HashMap::new(),
         }
     }
 
+    /// 清空布局求解结果缓存
+    pub fn clear_cache(&mut self) {
+        self.layout_cache.clear();
+        self.layout_cache_order.clear();
+    }
+
     /// 加载字体
     pub fn load_font(&mut self, font_family: &str) -> Result<(), SolverError> {
         if self.fonts.contains_key(font_family) {
@@ -152,6 +331,20 @@ impl LayoutSolver {
                 // 加载字体
                 self.load_font(&properties.font_family)?;
 
+                // 只有显式的固定宽度值在约束求解之前就是已知的，传播/百分比宽度要等求解完成
+                // 才能拿到；但自顶向下推导出的盒约束 max.width 在求解前也是已知的，所以
+                // 没有显式宽度时退而用它作为换行宽度，而不是退化成不换行的单行
+                let explicit_width = constraints.iter().find_map(|c| match &c.constraint_type {
+                    ConstraintType::Width { value: Some(width), .. } => Some(*width),
+                    _ => None,
+                });
+                let bounded_width = self
+                    .box_constraints
+                    .get(element.id())
+                    .map(|b| b.max.width)
+                    .filter(|w| w.is_finite());
+                let wrap_width = explicit_width.or(bounded_width);
+
                 if let Some(font) = self.fonts.get(&properties.font_family) {
                     let vars = self
                         .variables
@@ -161,13 +354,28 @@ impl LayoutSolver {
                     if !has_width_constraint {
                         let text_width =
                             self.measure_text_width(content, font, properties.font_size);
+                        // 没有显式宽度时，内在宽度不应超出自顶向下推导出的可用空间
+                        let width = match bounded_width {
+                            Some(max_width) => text_width.min(max_width),
+                            None => text_width,
+                        };
                         self.solver
-                            .add_constraint(vars.width | EQ(MEDIUM) | (text_width as f64))?;
+                            .add_constraint(vars.width | EQ(MEDIUM) | (width as f64))?;
                     }
 
                     if !has_height_constraint {
-                        // 使用字体大小作为文本高度的近似值
-                        let text_height = properties.font_size * 1.2; // 添加一些行间距
+                        let line_count = match wrap_width {
+                            Some(width) => self
+                                .wrap_lines(content, font, properties.font_size, width)
+                                .len(),
+                            None => 1,
+                        };
+
+                        let scale = Scale::uniform(properties.font_size);
+                        let v_metrics = font.v_metrics(scale);
+                        let line_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+                        let text_height = line_count as f32 * line_height;
+
                         self.solver
                             .add_constraint(vars.height | EQ(MEDIUM) | (text_height as f64))?;
                     }
@@ -215,29 +423,221 @@ impl LayoutSolver {
                     }
                 }
             }
+        } else if matches!(
+            element,
+            Element::Container { .. }
+                | Element::VStack { .. }
+                | Element::HStack { .. }
+                | Element::ZStack { .. }
+                | Element::Grid { .. }
+        ) {
+            // 容器/栈没有显式宽高时，用 `Element::intrinsic_size` 两段式推导的第一段
+            // （自底向上）算出的自然尺寸作为软约束喂给求解器，语义和上面 Text/Image
+            // 分支一致：只是下限提示，真正的摆放仍然交给约束网络本身
+            let has_width_constraint = element.constraints().iter().any(|c| {
+                matches!(
+                    c.constraint_type,
+                    ConstraintType::Width { value: Some(_), .. }
+                )
+            });
+            let has_height_constraint = element.constraints().iter().any(|c| {
+                matches!(
+                    c.constraint_type,
+                    ConstraintType::Height { value: Some(_), .. }
+                )
+            });
+
+            if !has_width_constraint || !has_height_constraint {
+                let available = self
+                    .box_constraints
+                    .get(element.id())
+                    .map(|b| b.max)
+                    .unwrap_or(Size {
+                        width: f32::INFINITY,
+                        height: f32::INFINITY,
+                    });
+                let natural = element.intrinsic_size(available);
+
+                let vars = self
+                    .variables
+                    .get(element.id())
+                    .ok_or_else(|| SolverError::ElementNotFound(element.id().clone()))?;
+
+                if !has_width_constraint {
+                    self.solver
+                        .add_constraint(vars.width | EQ(MEDIUM) | (natural.width as f64))?;
+                }
+
+                if !has_height_constraint {
+                    self.solver
+                        .add_constraint(vars.height | EQ(MEDIUM) | (natural.height as f64))?;
+                }
+            }
         }
         Ok(())
     }
 
-    /// 测量文本宽度
+    /// 自顶向下为元素列表及其子树推导盒约束：每个元素的 `max` 继承自父级可用空间
+    /// （容器会先按 padding 收紧），`min` 固定为 0——子元素没有必须填满父级的义务，
+    /// 真正的下限交给 `MinWidth`/`MinHeight` 这类显式用户约束处理
+    fn compute_box_constraints(
+        elements: &[Element],
+        parent: BoxConstraints,
+        out: &mut HashMap<ElementId, BoxConstraints>,
+    ) {
+        for element in elements {
+            let slot = BoxConstraints {
+                min: Size { width: 0.0, height: 0.0 },
+                max: parent.max,
+            };
+            // 元素自己的外边距先从分到的那块位置里收掉，子树拿到的可用空间和最终求出的
+            // 尺寸都已经不包含 margin：先按 margin 收紧算出"元素能用多大”，下面再按
+            // 容器自己的 padding 决定"给子元素留多大”
+            let bounds = Self::shrink_by_margin(slot, &element.margin());
+            out.insert(element.id().clone(), bounds);
+
+            match element {
+                Element::Container { properties, children, .. } => {
+                    let inner = Self::shrink_by_padding(bounds, &properties.padding);
+                    Self::compute_box_constraints(children, inner, out);
+                }
+                Element::VStack { children, .. }
+                | Element::HStack { children, .. }
+                | Element::ZStack { children, .. }
+                | Element::Grid { children, .. } => {
+                    Self::compute_box_constraints(children, bounds, out);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 把盒约束按容器的 padding 向内收紧，得到子元素的可用空间
+    fn shrink_by_padding(bounds: BoxConstraints, padding: &Padding) -> BoxConstraints {
+        let horizontal = padding.left + padding.right;
+        let vertical = padding.top + padding.bottom;
+        BoxConstraints {
+            min: Size {
+                width: (bounds.min.width - horizontal).max(0.0),
+                height: (bounds.min.height - vertical).max(0.0),
+            },
+            max: Size {
+                width: (bounds.max.width - horizontal).max(0.0),
+                height: (bounds.max.height - vertical).max(0.0),
+            },
+        }
+    }
+
+    /// 把盒约束按元素自己的 margin 向内收紧，语义和 `shrink_by_padding` 完全一致，
+    /// 只是作用方向相反（margin 收紧的是元素自己能占的空间，不是它给子元素留的空间）
+    fn shrink_by_margin(bounds: BoxConstraints, margin: &Margin) -> BoxConstraints {
+        let horizontal = margin.left + margin.right;
+        let vertical = margin.top + margin.bottom;
+        BoxConstraints {
+            min: Size {
+                width: (bounds.min.width - horizontal).max(0.0),
+                height: (bounds.min.height - vertical).max(0.0),
+            },
+            max: Size {
+                width: (bounds.max.width - horizontal).max(0.0),
+                height: (bounds.max.height - vertical).max(0.0),
+            },
+        }
+    }
+
+    /// 测量文本宽度：逐字形累加 advance width，而不是只看首尾字形的包围盒（那样对带字距
+    /// 调整或宽度不均的文字不准确）
     fn measure_text_width(&self, text: &str, font: &Font<'static>, scale: f32) -> f32 {
         let scale = Scale::uniform(scale);
-        let v_metrics = font.v_metrics(scale);
-        let glyphs: Vec<_> = font
-            .layout(text, scale, point(0.0, v_metrics.ascent))
-            .collect();
+        text.chars()
+            .map(|c| font.glyph(c).scaled(scale).h_metrics().advance_width)
+            .sum()
+    }
+
+    /// 按单词边界贪心换行：逐词尝试加入当前行，一旦累计前进宽度超过可用宽度就另起一行；
+    /// 单个词本身就超宽时退化为按字符强制断词
+    fn wrap_lines(
+        &self,
+        text: &str,
+        font: &Font<'static>,
+        scale: f32,
+        max_width: f32,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+
+        for word in text.split_whitespace() {
+            if self.measure_text_width(word, font, scale) > max_width {
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+                lines.extend(self.break_overlong_word(word, font, scale, max_width));
+                continue;
+            }
+
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
 
-        if let (Some(first), Some(last)) = (glyphs.first(), glyphs.last()) {
-            let min_x = first.pixel_bounding_box().map(|bb| bb.min.x).unwrap_or(0) as f32;
-            let max_x = last.pixel_bounding_box().map(|bb| bb.max.x).unwrap_or(0) as f32;
-            max_x - min_x
-        } else {
-            0.0
+            if self.measure_text_width(&candidate, font, scale) > max_width {
+                lines.push(std::mem::take(&mut current));
+                current = word.to_string();
+            } else {
+                current = candidate;
+            }
+        }
+
+        if !current.is_empty() {
+            lines.push(current);
+        }
+
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        lines
+    }
+
+    /// 把一个本身就超出可用宽度的词按字符切成若干行
+    fn break_overlong_word(
+        &self,
+        word: &str,
+        font: &Font<'static>,
+        scale: f32,
+        max_width: f32,
+    ) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+
+        for ch in word.chars() {
+            let candidate = format!("{}{}", current, ch);
+            if !current.is_empty() && self.measure_text_width(&candidate, font, scale) > max_width {
+                chunks.push(std::mem::take(&mut current));
+                current.push(ch);
+            } else {
+                current = candidate;
+            }
         }
+
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+
+        chunks
     }
 
     /// 求解布局约束
     pub fn solve_layout(&mut self, layout: &Layout) -> Result<ComputedLayout, SolverError> {
+        let cache_key = (hash_layout(layout), layout.canvas.width as u32, layout.canvas.height as u32);
+        if let Some(cached) = self.layout_cache.get(&cache_key) {
+            let cached = cached.clone();
+            self.touch_cache_entry(cache_key);
+            self.last_elements = layout.elements.clone();
+            return Ok(cached);
+        }
+
         // 清空之前的状态
         self.solver = Solver::new();
         self.variables.clear();
@@ -249,16 +649,22 @@ impl LayoutSolver {
         // 为所有元素创建变量
         self.create_variables_for_elements(&layout.elements)?;
 
+        // 自顶向下推导每个元素的盒约束（可用尺寸范围），供后续文本换行和容器尺寸收敛使用
+        self.box_constraints.clear();
+        let root_bounds = BoxConstraints::tight(Size {
+            width: layout.canvas.width,
+            height: layout.canvas.height,
+        });
+        Self::compute_box_constraints(&layout.elements, root_bounds, &mut self.box_constraints);
+
         // 添加基础约束（位置关系约束）
         self.add_basic_constraints()?;
 
         // 添加用户定义的约束
         self.add_user_constraints(&layout.elements)?;
 
-        // 求解
-        // 注意：由于画布尺寸已经通过约束固定，不需要suggest_value
-        // self.solver.suggest_value(self.canvas_vars.width, layout.canvas.width as f64)?;
-        // self.solver.suggest_value(self.canvas_vars.height, layout.canvas.height as f64)?;
+        // 记住这次求解的元素树，resize_canvas 重新提取结果时要用到
+        self.last_elements = layout.elements.clone();
 
         // 提取结果
         let mut computed_layout = ComputedLayout::new(Size {
@@ -268,6 +674,38 @@ impl LayoutSolver {
 
         self.extract_results(&layout.elements, &mut computed_layout)?;
 
+        if self.layout_cache.len() >= LAYOUT_CACHE_CAPACITY {
+            if let Some(least_recent) = self.layout_cache_order.pop_front() {
+                self.layout_cache.remove(&least_recent);
+            }
+        }
+        self.layout_cache.insert(cache_key, computed_layout.clone());
+        self.layout_cache_order.push_back(cache_key);
+
+        Ok(computed_layout)
+    }
+
+    /// 把一次缓存命中标记为"最近使用"：将键从淘汰队列中现有的位置挪到队尾，这样容量超限时
+    /// `solve_layout` 里 `pop_front` 淘汰的才是真正最久未被访问的条目，而不只是最早插入的
+    fn touch_cache_entry(&mut self, key: (u64, u32, u32)) {
+        if let Some(pos) = self.layout_cache_order.iter().position(|k| *k == key) {
+            self.layout_cache_order.remove(pos);
+        }
+        self.layout_cache_order.push_back(key);
+    }
+
+    /// 在不重建约束系统的前提下调整画布尺寸并重新求解：画布宽高是编辑变量，
+    /// `suggest_value` 只需要增量地调整 simplex 解，比 `solve_layout` 重新构建整棵树
+    /// 便宜得多，适合动画或交互式缩放这类需要反复重新布局的场景
+    pub fn resize_canvas(&mut self, width: f32, height: f32) -> Result<ComputedLayout, SolverError> {
+        self.solver
+            .suggest_value(self.canvas_vars.width, width as f64)?;
+        self.solver
+            .suggest_value(self.canvas_vars.height, height as f64)?;
+
+        let mut computed_layout = ComputedLayout::new(Size { width, height });
+        self.extract_results(&self.last_elements, &mut computed_layout)?;
+
         Ok(computed_layout)
     }
 
@@ -279,11 +717,16 @@ impl LayoutSolver {
         self.solver
             .add_constraint(self.canvas_vars.y | EQ(REQUIRED) | 0.0)?;
 
-        // 画布尺寸
+        // 画布宽高注册为编辑变量（STRONG 强度），而不是 REQUIRED 等式：这样后续调用
+        // resize_canvas 时可以用 suggest_value 增量调整，不需要重新构建整套约束
         self.solver
-            .add_constraint(self.canvas_vars.width | EQ(REQUIRED) | canvas.width as f64)?;
+            .add_edit_variable(self.canvas_vars.width, STRONG)?;
         self.solver
-            .add_constraint(self.canvas_vars.height | EQ(REQUIRED) | canvas.height as f64)?;
+            .add_edit_variable(self.canvas_vars.height, STRONG)?;
+        self.solver
+            .suggest_value(self.canvas_vars.width, canvas.width as f64)?;
+        self.solver
+            .suggest_value(self.canvas_vars.height, canvas.height as f64)?;
 
         // 计算画布的中心点和右下角
         self.solver.add_constraint(
@@ -376,6 +819,13 @@ impl LayoutSolver {
                 } => {
                     self.add_hstack_constraints(element.id(), children, properties)?;
                 }
+                Element::Grid {
+                    children,
+                    properties,
+                    ..
+                } => {
+                    self.add_grid_constraints(element.id(), children, properties)?;
+                }
                 _ => {}
             }
 
@@ -600,6 +1050,15 @@ impl LayoutSolver {
                     .add_constraint(vars.height | LE(strength) | (*value as f64))?;
             }
 
+            ConstraintType::Fill { .. } => {
+                // Fill 不转换成单变量约束：它只在 add_vstack_constraints/add_hstack_constraints
+                // 里、结合同一个 stack 内所有子元素的权重一起处理（按比例分配剩余空间）
+            }
+
+            ConstraintType::GridPosition { .. } => {
+                // 同 Fill：只在 add_grid_constraints 里结合子元素在 Grid 中的行列位置处理
+            }
+
             _ => {
                 // 其他约束类型的实现
                 return Err(SolverError::InvalidConstraint(format!(
@@ -612,85 +1071,206 @@ impl LayoutSolver {
         Ok(())
     }
 
-    /// 添加垂直堆叠约束
-    fn add_vstack_constraints(
-        &mut self,
-        stack_id: &ElementId,
-        children: &[Element],
-        properties: &StackProperties,
-    ) -> Result<(), SolverError> {
-        if children.is_empty() {
-            return Ok(());
+    /// 提取子元素的 Fill 权重：显式的 `Fill { weight }` 约束优先，否则 `Spacer` 默认权重为 1，
+    /// 其余元素视为固定尺寸（不参与比例分配）
+    fn fill_weight(element: &Element) -> Option<f32> {
+        for constraint in element.constraints() {
+            if let ConstraintType::Fill { weight } = &constraint.constraint_type {
+                return Some(*weight);
+            }
         }
 
-        let stack_vars = self
-            .variables
-            .get(stack_id)
-            .ok_or_else(|| SolverError::ElementNotFound(stack_id.clone()))?;
+        match element {
+            Element::Spacer { .. } => Some(1.0),
+            _ => None,
+        }
+    }
 
-        // 垂直排列：每个子元素的顶部等于前一个元素的底部加间距
-        for (i, child) in children.iter().enumerate() {
-            let child_vars = self
-                .variables
-                .get(child.id())
-                .ok_or_else(|| SolverError::ElementNotFound(child.id().clone()))?;
+    /// 判断子元素在给定主轴方向上是否已经有显式尺寸来源：固定值、相对目标或百分比中的任意
+    /// 一种。用于把"完全没表态"的子元素和"已经有办法确定尺寸"的子元素区分开——前者应当
+    /// 在彼此之间均分剩余空间，后者不需要
+    fn has_explicit_main_size(direction: StackDirection, element: &Element) -> bool {
+        element.constraints().iter().any(|c| match (&c.constraint_type, direction) {
+            (
+                ConstraintType::Width { value, target, percent, .. },
+                StackDirection::Horizontal,
+            ) => value.is_some() || target.is_some() || percent.is_some(),
+            (
+                ConstraintType::Height { value, target, percent, .. },
+                StackDirection::Vertical,
+            ) => value.is_some() || target.is_some() || percent.is_some(),
+            _ => false,
+        })
+    }
+
+    /// 提取子元素在 Grid 中的位置：没有显式 `GridPosition` 约束的子元素落在 (0, 0)，不跨行/列
+    fn grid_position(element: &Element) -> (u32, u32, u32, u32) {
+        for constraint in element.constraints() {
+            if let ConstraintType::GridPosition { row, col, row_span, col_span } =
+                &constraint.constraint_type
+            {
+                return (*row, *col, (*row_span).max(1), (*col_span).max(1));
+            }
+        }
+        (0, 0, 1, 1)
+    }
+
+    /// 沿一个轴（行或列）为轨道分配起止变量：Fixed/Percent 轨道尺寸是 REQUIRED 等式，
+    /// Fraction 轨道通过共享的"单位"变量以 WEAK 等式按权重分配剩余空间（同 Fill 的思路），
+    /// 轨道之间按 spacing 顺序相连，首尾分别钉在容器的起点和终点（容器尺寸 = 所有轨道之和）
+    fn add_grid_tracks(
+        &mut self,
+        tracks: &[GridTrack],
+        container_start: Variable,
+        container_end: Variable,
+        container_length: Variable,
+        spacing: f32,
+    ) -> Result<Vec<(Variable, Variable)>, SolverError> {
+        let has_fraction = tracks
+            .iter()
+            .any(|t| matches!(t, GridTrack::Fraction(weight) if *weight > 0.0));
+        let unit = if has_fraction { Some(Variable::new()) } else { None };
+
+        let mut track_vars = Vec::with_capacity(tracks.len());
+        for (i, track) in tracks.iter().enumerate() {
+            let start = Variable::new();
+            let end = Variable::new();
 
             if i == 0 {
-                // 第一个元素顶部对齐到容器顶部
-                self.solver
-                    .add_constraint(child_vars.y | EQ(REQUIRED) | stack_vars.y)?;
+                self.solver.add_constraint(start | EQ(REQUIRED) | container_start)?;
             } else {
-                // 其他元素顶部等于前一个元素底部加间距
-                let prev_child = &children[i - 1];
-                let prev_vars = self
-                    .variables
-                    .get(prev_child.id())
-                    .ok_or_else(|| SolverError::ElementNotFound(prev_child.id().clone()))?;
-
-                self.solver.add_constraint(
-                    child_vars.y | EQ(REQUIRED) | (prev_vars.bottom + properties.spacing as f64),
-                )?;
+                let (_, prev_end) = track_vars[i - 1];
+                self.solver
+                    .add_constraint(start | EQ(REQUIRED) | (prev_end + spacing as f64))?;
             }
 
-            // 水平对齐
-            match properties.alignment {
-                Alignment::Leading => {
+            match track {
+                GridTrack::Fixed(value) => {
                     self.solver
-                        .add_constraint(child_vars.x | EQ(REQUIRED) | stack_vars.x)?;
+                        .add_constraint(end | EQ(REQUIRED) | (start + *value as f64))?;
                 }
-                Alignment::Center => {
-                    self.solver
-                        .add_constraint(child_vars.center_x | EQ(REQUIRED) | stack_vars.center_x)?;
+                GridTrack::Percent(value) => {
+                    self.solver.add_constraint(
+                        end | EQ(REQUIRED) | (start + container_length * (*value as f64 / 100.0)),
+                    )?;
                 }
-                Alignment::Trailing => {
+                GridTrack::Fraction(weight) => {
+                    let unit_var = unit.unwrap_or_else(Variable::new);
                     self.solver
-                        .add_constraint(child_vars.right | EQ(REQUIRED) | stack_vars.right)?;
+                        .add_constraint(end | EQ(WEAK) | (start + unit_var * *weight as f64))?;
                 }
-                _ => {}
             }
+
+            track_vars.push((start, end));
         }
 
-        // 容器高度等于所有子元素高度加间距
-        if let Some(last_child) = children.last() {
-            let last_vars = self
+        if let Some((_, last_end)) = track_vars.last() {
+            self.solver
+                .add_constraint(*last_end | EQ(REQUIRED) | container_end)?;
+        }
+
+        Ok(track_vars)
+    }
+
+    /// 添加网格约束
+    fn add_grid_constraints(
+        &mut self,
+        grid_id: &ElementId,
+        children: &[Element],
+        properties: &GridProperties,
+    ) -> Result<(), SolverError> {
+        if children.is_empty() || properties.rows.is_empty() || properties.cols.is_empty() {
+            return Ok(());
+        }
+
+        let grid_vars = self
+            .variables
+            .get(grid_id)
+            .ok_or_else(|| SolverError::ElementNotFound(grid_id.clone()))?;
+        let (grid_x, grid_y, grid_right, grid_bottom, grid_width, grid_height) = (
+            grid_vars.x,
+            grid_vars.y,
+            grid_vars.right,
+            grid_vars.bottom,
+            grid_vars.width,
+            grid_vars.height,
+        );
+
+        let row_tracks = self.add_grid_tracks(
+            &properties.rows,
+            grid_y,
+            grid_bottom,
+            grid_height,
+            properties.row_spacing,
+        )?;
+        let col_tracks = self.add_grid_tracks(
+            &properties.cols,
+            grid_x,
+            grid_right,
+            grid_width,
+            properties.col_spacing,
+        )?;
+
+        for child in children {
+            let (row, col, row_span, col_span) = Self::grid_position(child);
+            let row_start_idx = row as usize;
+            let row_end_idx = ((row + row_span - 1) as usize).min(row_tracks.len() - 1);
+            let col_start_idx = col as usize;
+            let col_end_idx = ((col + col_span - 1) as usize).min(col_tracks.len() - 1);
+
+            if row_start_idx >= row_tracks.len() || col_start_idx >= col_tracks.len() {
+                continue;
+            }
+
+            let child_vars = self
                 .variables
-                .get(last_child.id())
-                .ok_or_else(|| SolverError::ElementNotFound(last_child.id().clone()))?;
+                .get(child.id())
+                .ok_or_else(|| SolverError::ElementNotFound(child.id().clone()))?;
 
-            self.solver.add_constraint(
-                stack_vars.height | EQ(REQUIRED) | (last_vars.bottom - stack_vars.y),
-            )?;
+            let (row_start, _) = row_tracks[row_start_idx];
+            let (_, row_end) = row_tracks[row_end_idx];
+            let (col_start, _) = col_tracks[col_start_idx];
+            let (_, col_end) = col_tracks[col_end_idx];
+
+            self.solver.add_constraint(child_vars.y | EQ(REQUIRED) | row_start)?;
+            self.solver.add_constraint(child_vars.bottom | EQ(REQUIRED) | row_end)?;
+            self.solver.add_constraint(child_vars.x | EQ(REQUIRED) | col_start)?;
+            self.solver.add_constraint(child_vars.right | EQ(REQUIRED) | col_end)?;
         }
 
         Ok(())
     }
 
+    /// 添加垂直堆叠约束
+    fn add_vstack_constraints(
+        &mut self,
+        stack_id: &ElementId,
+        children: &[Element],
+        properties: &StackProperties,
+    ) -> Result<(), SolverError> {
+        self.add_stack_constraints(stack_id, children, properties, StackDirection::Vertical)
+    }
+
     /// 添加水平堆叠约束
     fn add_hstack_constraints(
         &mut self,
         stack_id: &ElementId,
         children: &[Element],
         properties: &StackProperties,
+    ) -> Result<(), SolverError> {
+        self.add_stack_constraints(stack_id, children, properties, StackDirection::Horizontal)
+    }
+
+    /// 沿 `direction` 指定的主轴排布子元素：依次把每个子元素的主轴起点钉在前一个元素的
+    /// 主轴终点加间距处，交叉轴上按 `alignment` 对齐，容器的主轴尺寸收敛为子元素尺寸之和
+    /// （见 `AxisVars` 上的说明）。垂直/水平两个方向的排布逻辑完全一致，只是主轴/交叉轴
+    /// 对应的变量和 `Alignment` 取值不同，所以 vstack/hstack 共享这一份实现
+    fn add_stack_constraints(
+        &mut self,
+        stack_id: &ElementId,
+        children: &[Element],
+        properties: &StackProperties,
+        direction: StackDirection,
     ) -> Result<(), SolverError> {
         if children.is_empty() {
             return Ok(());
@@ -700,59 +1280,212 @@ impl LayoutSolver {
             .variables
             .get(stack_id)
             .ok_or_else(|| SolverError::ElementNotFound(stack_id.clone()))?;
+        let stack_axis = AxisVars::from_direction(direction, stack_vars);
+        let main_padding_start = direction.main_padding_start(&properties.padding) as f64;
+        let main_padding_end = direction.main_padding_end(&properties.padding) as f64;
+        let cross_padding_start = direction.cross_padding_start(&properties.padding) as f64;
+        let cross_padding_end = direction.cross_padding_end(&properties.padding) as f64;
+
+        // `distribution` 独立控制两件事：子元素主轴尺寸怎么分配权重（FillEqually/
+        // FillProportionally 只是把下面这套已有的"unit * weight"机制换一份权重表），以及
+        // 相邻子元素之间的主轴间距怎么来（EqualSpacing/EqualCentering 用共享的 gap 变量
+        // 代替字面量 spacing，让求解器反推出能填满容器的间距）
+        let weights: Vec<Option<f32>> = match properties.distribution {
+            Distribution::FillEqually => children.iter().map(|_| Some(1.0)).collect(),
+            Distribution::FillProportionally => children
+                .iter()
+                .map(|child| Some(Self::fill_weight(child).unwrap_or(1.0)))
+                .collect(),
+            Distribution::Fill | Distribution::EqualSpacing | Distribution::EqualCentering => {
+                children.iter().map(Self::fill_weight).collect()
+            }
+        };
+        let has_fill = weights.iter().any(|w| matches!(w, Some(weight) if *weight > 0.0));
+        let unit = if has_fill { Some(Variable::new()) } else { None };
+
+        // EqualSpacing 按边到边间距反推，EqualCentering 按中心到中心间距反推；两者都只在
+        // 容器主轴尺寸能钉死成自顶向下推导出的可用空间时才有意义，否则退化为普通 spacing
+        let gap = match properties.distribution {
+            Distribution::EqualSpacing | Distribution::EqualCentering => {
+                let gap_var = Variable::new();
+                self.solver
+                    .add_constraint(gap_var | EQ(WEAK) | (properties.spacing as f64))?;
+                Some(gap_var)
+            }
+            _ => None,
+        };
 
-        // 水平排列：每个子元素的左边等于前一个元素的右边加间距
+        // 主轴排列：每个子元素的主轴起点等于前一个元素的主轴终点加间距
         for (i, child) in children.iter().enumerate() {
             let child_vars = self
                 .variables
                 .get(child.id())
                 .ok_or_else(|| SolverError::ElementNotFound(child.id().clone()))?;
+            let child_axis = AxisVars::from_direction(direction, child_vars);
+
+            let child_margin = child.margin();
+            let child_main_margin_start = direction.main_margin_start(&child_margin) as f64;
+            let child_cross_margin_start = direction.cross_margin_start(&child_margin) as f64;
+            let child_cross_margin_end = direction.cross_margin_end(&child_margin) as f64;
 
             if i == 0 {
-                // 第一个元素左边对齐到容器左边
-                self.solver
-                    .add_constraint(child_vars.x | EQ(REQUIRED) | stack_vars.x)?;
+                // 第一个元素主轴起点对齐到容器主轴起点，留出起始边内边距，再加上它自己的
+                // 外边距——margin 不和容器 padding 合并折叠，两者都原样生效
+                self.solver.add_constraint(
+                    child_axis.start
+                        | EQ(REQUIRED)
+                        | (stack_axis.start + main_padding_start + child_main_margin_start),
+                )?;
             } else {
-                // 其他元素左边等于前一个元素右边加间距
+                // 其他元素主轴起点等于前一个元素主轴终点加间距，再加上前一个元素的外边距
+                // 终止边和这个元素外边距起始边——两个相邻元素的 margin 直接相加，不折叠
                 let prev_child = &children[i - 1];
                 let prev_vars = self
                     .variables
                     .get(prev_child.id())
                     .ok_or_else(|| SolverError::ElementNotFound(prev_child.id().clone()))?;
+                let prev_axis = AxisVars::from_direction(direction, prev_vars);
+                let prev_main_margin_end = direction.main_margin_end(&prev_child.margin()) as f64;
 
-                self.solver.add_constraint(
-                    child_vars.x | EQ(REQUIRED) | (prev_vars.right + properties.spacing as f64),
-                )?;
+                match (properties.distribution, gap) {
+                    (Distribution::EqualCentering, Some(gap_var)) => {
+                        // 中心到中心距离相等：下一个元素的主轴中心 = 上一个元素的主轴
+                        // 中心 + 共享间距变量，边距仍然原样叠加在间距上
+                        self.solver.add_constraint(
+                            (child_axis.start + child_axis.size * 0.5)
+                                | EQ(REQUIRED)
+                                | (prev_axis.start + prev_axis.size * 0.5
+                                    + gap_var
+                                    + prev_main_margin_end
+                                    + child_main_margin_start),
+                        )?;
+                    }
+                    (Distribution::EqualSpacing, Some(gap_var)) => {
+                        self.solver.add_constraint(
+                            child_axis.start
+                                | EQ(REQUIRED)
+                                | (prev_axis.end
+                                    + gap_var
+                                    + prev_main_margin_end
+                                    + child_main_margin_start),
+                        )?;
+                    }
+                    _ => {
+                        self.solver.add_constraint(
+                            child_axis.start
+                                | EQ(REQUIRED)
+                                | (prev_axis.end
+                                    + properties.spacing as f64
+                                    + prev_main_margin_end
+                                    + child_main_margin_start),
+                        )?;
+                    }
+                }
             }
 
-            // 垂直对齐
-            match properties.alignment {
-                Alignment::Top => {
+            if let (Some(weight), Some(unit_var)) = (weights[i], unit) {
+                if weight > 0.0 {
                     self.solver
-                        .add_constraint(child_vars.y | EQ(REQUIRED) | stack_vars.y)?;
+                        .add_constraint(child_axis.size | EQ(WEAK) | (unit_var * weight as f64))?;
                 }
-                Alignment::Center => {
-                    self.solver
-                        .add_constraint(child_vars.center_y | EQ(REQUIRED) | stack_vars.center_y)?;
+            }
+
+            if let Element::Spacer { min_length, priority, .. } = child {
+                let strength = self.priority_to_strength(*priority);
+                self.solver
+                    .add_constraint(child_axis.size | GE(strength) | (*min_length as f64))?;
+            }
+
+            // 交叉轴对齐：居中对齐时元素的外边距在两侧同时占用空间，和整体居中的语义
+            // 冲突（到底该偏向哪一侧没有唯一答案），这里和大多数布局引擎一样对居中对齐
+            // 的子元素忽略交叉轴 margin
+            match direction.cross_alignment(properties.alignment) {
+                Some(CrossAlignment::Leading) => {
+                    self.solver.add_constraint(
+                        child_axis.cross_start
+                            | EQ(REQUIRED)
+                            | (stack_axis.cross_start + cross_padding_start + child_cross_margin_start),
+                    )?;
                 }
-                Alignment::Bottom => {
-                    self.solver
-                        .add_constraint(child_vars.bottom | EQ(REQUIRED) | stack_vars.bottom)?;
+                Some(CrossAlignment::Center) => {
+                    self.solver.add_constraint(
+                        child_axis.cross_center | EQ(REQUIRED) | stack_axis.cross_center,
+                    )?;
                 }
-                _ => {}
+                Some(CrossAlignment::Trailing) => {
+                    self.solver.add_constraint(
+                        child_axis.cross_end
+                            | EQ(REQUIRED)
+                            | (stack_axis.cross_end - cross_padding_end - child_cross_margin_end),
+                    )?;
+                }
+                None => {}
             }
         }
 
-        // 容器宽度等于所有子元素宽度加间距
+        // 既没有 Fill 权重、也没有任何显式主轴尺寸来源（固定值/相对目标/百分比）的子元素，
+        // 两两之间用 WEAK 等式绑定主轴尺寸，让剩余空间在它们之间均分——否则它们会各自按
+        // 内在尺寸（文本/图片）或 0 求解，表现不像一个真正的 flex 容器
+        let mut prev_unsized_size: Option<Variable> = None;
+        for (i, child) in children.iter().enumerate() {
+            let is_unsized = weights[i].is_none()
+                && !matches!(child, Element::Spacer { .. })
+                && !Self::has_explicit_main_size(direction, child);
+            if !is_unsized {
+                continue;
+            }
+
+            let child_vars = self
+                .variables
+                .get(child.id())
+                .ok_or_else(|| SolverError::ElementNotFound(child.id().clone()))?;
+            let size_var = AxisVars::from_direction(direction, child_vars).size;
+
+            if let Some(prev_size) = prev_unsized_size {
+                self.solver.add_constraint(size_var | EQ(WEAK) | prev_size)?;
+            }
+            prev_unsized_size = Some(size_var);
+        }
+
+        // 容器主轴尺寸优先等于所有子元素尺寸加间距，但不能超出自顶向下推导出的可用空间：
+        // 内容总和只是 STRONG 偏好，真正的上限由 box_constraints 的 max 以 REQUIRED 约束
+        // 钉死，超出的部分由 Fill 子元素的 WEAK 权重约束负责让出空间
         if let Some(last_child) = children.last() {
             let last_vars = self
                 .variables
                 .get(last_child.id())
                 .ok_or_else(|| SolverError::ElementNotFound(last_child.id().clone()))?;
+            let last_axis = AxisVars::from_direction(direction, last_vars);
+            let last_main_margin_end = direction.main_margin_end(&last_child.margin()) as f64;
 
+            // `last_axis.end - stack_axis.start` 已经包含了第一个子元素起点里的
+            // `main_padding_start`（见上面 i == 0 分支），这里只需要再补上末尾的
+            // `main_padding_end`，不能重复加一次 `main_padding_start`
             self.solver.add_constraint(
-                stack_vars.width | EQ(REQUIRED) | (last_vars.right - stack_vars.x),
+                stack_axis.size
+                    | EQ(STRONG)
+                    | (last_axis.end - stack_axis.start + main_padding_end + last_main_margin_end),
             )?;
+
+            if let Some(bounds) = self.box_constraints.get(stack_id) {
+                let max_main = direction.main_max(bounds);
+                if max_main.is_finite() {
+                    match properties.distribution {
+                        // EqualSpacing/EqualCentering 需要容器主轴尺寸精确等于可用空间，
+                        // 间距/中心距才能靠上面的 gap 变量反推撑满；其余分布方式仍然只
+                        // 把可用空间当作上限，容器按内容尺寸收缩（content-hugging）
+                        Distribution::EqualSpacing | Distribution::EqualCentering => {
+                            self.solver
+                                .add_constraint(stack_axis.size | EQ(REQUIRED) | (max_main as f64))?;
+                        }
+                        _ => {
+                            self.solver.add_constraint(
+                                stack_axis.size | LE(REQUIRED) | (max_main as f64),
+                            )?;
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -790,13 +1523,32 @@ impl LayoutSolver {
         Ok(())
     }
 
-    /// 将优先级转换为Cassowary强度
+    /// 将优先级转换为Cassowary强度。四个固定档位直接映射到 cassowary 的强度常量，
+    /// `Custom` 则把调用方算好的原始强度裁剪到 `[0, REQUIRED]` 后传下去（通常由 [`blended_strength`] 生成，
+    /// 用来在同一档位内的多个软约束之间分出主次）
     fn priority_to_strength(&self, priority: Priority) -> f64 {
         match priority {
             Priority::Required => REQUIRED,
             Priority::High => STRONG,
             Priority::Medium => MEDIUM,
             Priority::Low => WEAK,
+            Priority::Custom(strength) => strength.clamp(0.0, REQUIRED),
         }
     }
 }
+
+/// 在 `priority` 对应的固定档位内按 `weight`（通常落在 `(0.0, 1.0]`）细分出一个更精确
+/// 的 Cassowary 强度，使用 cassowary 标准的 `strength::create(a, b, c, w)` 公式。结果可以直接包进
+/// `Priority::Custom` 使用，用来在多个同档位的软约束之间分出主次，而不必新增更多固定档位
+///
+/// `Priority::Required` 本身不参与权重细分——它必须始终是满强度的硬约束
+/// `Priority::Custom` 视为已经算好的强度，原样返回
+pub fn blended_strength(priority: Priority, weight: f64) -> f64 {
+    match priority {
+        Priority::Required => REQUIRED,
+        Priority::High => create(1.0, 0.0, 0.0, weight),
+        Priority::Medium => create(0.0, 1.0, 0.0, weight),
+        Priority::Low => create(0.0, 0.0, 1.0, weight),
+        Priority::Custom(strength) => strength,
+    }
+}
@@ -0,0 +1,213 @@
+//! 段落级文本排版
+//!
+//! 旧版 `render_text` 直接对 `content.chars()` 从左到右按 `advance_width` 累加，既不认识
+//! 组合标记（变音符号要叠在基字符上，不能单独占一个前进宽度），也不处理双向文字
+//! （阿拉伯语、希伯来语在视觉上要整段反过来），更没有换行——超出边界框的文字直接画出框外。
+//!
+//! 这个模块把“怎么把一段文字摆进一个矩形”单独拆出来：先按 grapheme cluster（而不是
+//! `char`）切分，避免把“基字符 + 组合标记”拆成两列；再用 `unicode-bidi` 按段落算出
+//! 双向层级，贪心按词换行（单词本身比边界框还宽就按字符硬断），最后对每一行按视觉顺序
+//! 重排。产出的 [`PositionedGlyph`] 列表里的坐标都是相对边界框左上角的局部坐标，
+//! `x` 已经按 `TextAlignment`（含 `Justified` 的词间距展开）算好，`y` 是行顶部的偏移量，
+//! 调用方只需要加上边界框原点、换算成基线，就能逐个栅格化绘制。
+
+use crate::layout::TextAlignment;
+use unicode_bidi::BidiInfo;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 一个已经定位好的字符：`ch` 和前一个 [`PositionedGlyph`] 共享同一个 `x`/`y` 时，
+/// 说明它们属于同一个 grapheme cluster（组合标记叠在基字符上，不单独前进）
+#[derive(Debug, Clone, Copy)]
+pub struct PositionedGlyph {
+    pub ch: char,
+    /// 相对边界框左上角的 x 偏移（像素）
+    pub x: f32,
+    /// 相对边界框左上角的 y 偏移（像素），即这一行顶部的位置
+    pub y: f32,
+}
+
+/// 换行策略：对应 [`crate::layout::LineBreakMode`]，决定 [`layout_paragraph`] 怎么切行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    /// 按词贪心换行，单词本身超宽时在词内按字符硬断
+    Word,
+    /// 忽略词边界，每个 grapheme cluster 都是一个可能的断行点
+    Char,
+    /// 不换行，整段文字当作一行（可能超出边界框宽度）
+    NoWrap,
+}
+
+/// 按 `max_width`/`wrap_mode` 把一段文字排进若干行，应用双向重排和 `alignment`
+/// （含 `Justified` 的词间距展开），再把每个字符换算成相对边界框左上角的坐标。
+/// `max_lines` 非空时超出的行会被直接丢弃。`measure` 用来量一个 grapheme cluster
+/// 的前进宽度，由调用方提供（通常是按字体 metrics 累加 cluster 里每个字符的宽度）。
+pub fn layout_paragraph(
+    text: &str,
+    max_width: f32,
+    line_height: f32,
+    alignment: TextAlignment,
+    wrap_mode: WrapMode,
+    max_lines: Option<u32>,
+    mut measure: impl FnMut(&str) -> f32,
+) -> Vec<PositionedGlyph> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let clusters: Vec<(usize, &str)> = text.grapheme_indices(true).collect();
+    let mut lines = wrap_clusters(&clusters, max_width, wrap_mode, &mut measure);
+
+    if let Some(max_lines) = max_lines {
+        lines.truncate(max_lines as usize);
+    }
+
+    let bidi_info = BidiInfo::new(text, None);
+    let para = match bidi_info.paragraphs.first() {
+        Some(para) => para,
+        None => return Vec::new(),
+    };
+
+    let mut glyphs = Vec::new();
+    for (line_index, byte_range) in lines.iter().enumerate() {
+        let visual_line = bidi_info.reorder_line(para, byte_range.clone());
+        let line_top = line_index as f32 * line_height;
+        layout_line(&visual_line, max_width, alignment, line_top, &mut measure, &mut glyphs);
+    }
+
+    glyphs
+}
+
+/// 贪心按词（或按字符）把 grapheme cluster 序列切成若干行，返回每一行在原文里的
+/// 字节范围（之后交给 `unicode-bidi` 按这个范围做视觉重排）
+fn wrap_clusters(
+    clusters: &[(usize, &str)],
+    max_width: f32,
+    wrap_mode: WrapMode,
+    measure: &mut impl FnMut(&str) -> f32,
+) -> Vec<std::ops::Range<usize>> {
+    if clusters.is_empty() {
+        return Vec::new();
+    }
+    let text_len = clusters.last().map(|(i, s)| i + s.len()).unwrap_or(0);
+
+    if matches!(wrap_mode, WrapMode::NoWrap) {
+        return vec![0..text_len];
+    }
+
+    let mut lines = Vec::new();
+    let mut line_start = clusters[0].0;
+    let mut line_width = 0.0f32;
+    // `break_end`：按词换行时，当前行应该在哪里结束（上一个词尾，不含空白）；
+    // `break_start`：下一行应该从哪里开始（跳过紧随其后的空白）
+    let mut break_end: Option<usize> = None;
+    let mut break_start: Option<usize> = None;
+    let mut prev_was_whitespace = false;
+
+    for &(byte_index, cluster) in clusters {
+        let is_whitespace = cluster.chars().all(|c| c.is_whitespace());
+        let cluster_width = measure(cluster);
+
+        if line_width + cluster_width > max_width && byte_index > line_start {
+            let broke_at_word_boundary = wrap_mode == WrapMode::Word
+                && break_end.is_some()
+                && break_start.is_some();
+
+            if broke_at_word_boundary {
+                lines.push(line_start..break_end.unwrap());
+                line_start = break_start.unwrap();
+            } else {
+                // 按字符硬断：Word 模式下这个词本身就比边界框宽，没有可用的词边界断点；
+                // Char 模式本来就是逐字符断行
+                lines.push(line_start..byte_index);
+                line_start = byte_index;
+            }
+
+            line_width = measure_range(clusters, line_start, byte_index, measure);
+            break_end = None;
+            break_start = None;
+        }
+
+        line_width += cluster_width;
+
+        if is_whitespace {
+            if !prev_was_whitespace {
+                break_end = Some(byte_index);
+            }
+            break_start = Some(byte_index + cluster.len());
+        }
+
+        prev_was_whitespace = is_whitespace;
+    }
+
+    lines.push(line_start..text_len);
+    lines
+}
+
+/// 重新量 `[start, end)` 范围内 cluster 的总宽度；按词边界断行之后，新行的已用宽度
+/// 不能简单复用旧的累加值（行首变了），要从新行首重新累计一遍
+fn measure_range(
+    clusters: &[(usize, &str)],
+    start: usize,
+    end: usize,
+    measure: &mut impl FnMut(&str) -> f32,
+) -> f32 {
+    clusters
+        .iter()
+        .filter(|&&(b, c)| b >= start && b + c.len() <= end)
+        .map(|&(_, c)| measure(c))
+        .sum()
+}
+
+/// 把一行已经按视觉顺序重排好的文字展开成坐标：`Justified` 把多出来的空间平均分摊到
+/// 词间空隙，其余对齐方式只是整体左右移动，和原来单行渲染时的逻辑一致
+fn layout_line(
+    visual_line: &str,
+    max_width: f32,
+    alignment: TextAlignment,
+    line_top: f32,
+    measure: &mut impl FnMut(&str) -> f32,
+    out: &mut Vec<PositionedGlyph>,
+) {
+    let line_clusters: Vec<&str> = visual_line.graphemes(true).collect();
+    let widths: Vec<f32> = line_clusters.iter().map(|c| measure(c)).collect();
+    let natural_width: f32 = widths.iter().sum();
+
+    let words: Vec<&str> = visual_line.split_whitespace().collect();
+
+    if alignment == TextAlignment::Justified && words.len() > 1 && natural_width < max_width {
+        let extra = max_width - natural_width;
+        let gap_count = line_clusters
+            .iter()
+            .filter(|c| c.chars().all(|ch| ch.is_whitespace()))
+            .count()
+            .max(1);
+        let extra_per_gap = extra / gap_count as f32;
+
+        let mut x = 0.0f32;
+        for (cluster, width) in line_clusters.iter().zip(widths.iter()) {
+            let is_whitespace = cluster.chars().all(|c| c.is_whitespace());
+            for ch in cluster.chars() {
+                out.push(PositionedGlyph { ch, x, y: line_top });
+            }
+            x += width;
+            if is_whitespace {
+                x += extra_per_gap;
+            }
+        }
+        return;
+    }
+
+    let x_offset = match alignment {
+        TextAlignment::Leading | TextAlignment::Justified => 0.0,
+        TextAlignment::Center => ((max_width - natural_width) / 2.0).max(0.0),
+        TextAlignment::Trailing => (max_width - natural_width).max(0.0),
+    };
+
+    let mut x = x_offset;
+    for (cluster, width) in line_clusters.iter().zip(widths.iter()) {
+        for ch in cluster.chars() {
+            out.push(PositionedGlyph { ch, x, y: line_top });
+        }
+        x += width;
+    }
+}
@@ -0,0 +1,109 @@
+//! DSL 文件热重载
+//!
+//! 走 Alacritty 热加载配置文件的路子：用文件系统事件驱动而不是轮询，一阵连续的编辑器
+//! 保存通过防抖合并成一次重新解析。[`DslWatcher::spawn`] 开始监听后立即推一次初始解析
+//! 结果，此后每次文件变化（防抖之后）都会重新解析并推送；重新解析失败只上报这次的
+//! `DslError`，上一次解析成功的 `Layout` 仍然留在 [`DslWatcher::last_good`] 里，调用方
+//! 不需要自己维护"最后一个能用的布局"。
+
+use crate::dsl::{DslError, DslParser};
+use crate::layout::Layout;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "hot-reload")]
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// 监听单个 JSON/YAML 布局文件，文件变化时重新解析并把结果推给消费者
+pub struct DslWatcher {
+    last_good: Arc<Mutex<Option<Layout>>>,
+    #[cfg(feature = "hot-reload")]
+    _watcher: RecommendedWatcher,
+}
+
+impl DslWatcher {
+    /// 开始监听 `path`：文件变化后等待 `debounce` 时间窗口内不再有新事件才重新解析一次,
+    /// 解析结果（成功的 `Layout` 或失败的 `DslError`）通过返回的 `Receiver` 推送。
+    /// 调用后会立即解析一次并推送，这样消费者不用等第一次文件变化就能拿到初始布局。
+    #[cfg(feature = "hot-reload")]
+    pub fn spawn(
+        path: impl AsRef<Path>,
+        debounce: Duration,
+    ) -> Result<(Self, Receiver<Result<Layout, DslError>>), DslError> {
+        let path = path.as_ref().to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        let last_good = Arc::new(Mutex::new(None));
+
+        let initial = Self::parse_path(&path);
+        if let Ok(layout) = &initial {
+            *last_good.lock().unwrap() = Some(layout.clone());
+        }
+        let _ = tx.send(initial);
+
+        let (raw_tx, raw_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = raw_tx.send(res);
+        })
+        .map_err(|e| DslError::ValidationError(format!("Failed to start file watcher: {}", e)))?;
+        watcher
+            .watch(&path, RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                DslError::ValidationError(format!("Failed to watch {}: {}", path.display(), e))
+            })?;
+
+        let last_good_for_thread = Arc::clone(&last_good);
+        let watch_path = path.clone();
+        std::thread::spawn(move || loop {
+            if raw_rx.recv().is_err() {
+                break;
+            }
+            // 防抖：吸收掉窗口期内紧跟着到来的所有事件，只在安静下来之后重新解析一次
+            while raw_rx.recv_timeout(debounce).is_ok() {}
+
+            let result = Self::parse_path(&watch_path);
+            if let Ok(layout) = &result {
+                *last_good_for_thread.lock().unwrap() = Some(layout.clone());
+            }
+            if tx.send(result).is_err() {
+                break;
+            }
+        });
+
+        Ok((
+            Self {
+                last_good,
+                _watcher: watcher,
+            },
+            rx,
+        ))
+    }
+
+    /// 未启用 `hot-reload` feature 时的占位实现，给出明确的错误而不是悄悄什么都不做
+    #[cfg(not(feature = "hot-reload"))]
+    pub fn spawn(
+        _path: impl AsRef<Path>,
+        _debounce: Duration,
+    ) -> Result<(Self, Receiver<Result<Layout, DslError>>), DslError> {
+        Err(DslError::ValidationError(
+            "DslWatcher requires the `hot-reload` feature to be enabled".to_string(),
+        ))
+    }
+
+    /// 最近一次成功解析得到的 `Layout`；当最新一次重新解析失败时，这里仍然是上一次
+    /// 成功解析的结果，不会被失败覆盖
+    pub fn last_good(&self) -> Option<Layout> {
+        self.last_good.lock().unwrap().clone()
+    }
+
+    /// 按文件扩展名选择 JSON 还是 YAML 解析路径，扩展名不认识的一律按 JSON 处理
+    fn parse_path(path: &PathBuf) -> Result<Layout, DslError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                DslParser::load_yaml_file(path)
+            }
+            _ => DslParser::load_json_file(path),
+        }
+    }
+}
@@ -6,7 +6,7 @@ use crate::layout::node::*;
 use crate::error::*;
 use taffy::prelude::*;
 use taffy::geometry::Size as TaffySize;
-use crate::types::{Size as MySize, Rect as MyRect, Point as MyPoint};
+use crate::types::{Size as MySize, Rect as MyRect, Point as MyPoint, FontTransform};
 use std::collections::HashMap;
 use image::GenericImageView;
 use rusttype::{Font, Scale};
@@ -29,6 +29,9 @@ pub struct LayoutEngine {
     taffy: Taffy,
     /// 节点 ID 映射
     node_map: HashMap<Node, LayoutNode>,
+    /// 构建 Taffy 树时，容器声明的文本继承默认值按前序遍历压入/弹出的级联栈；
+    /// 只在 `compute_layout` 运行期间非空，调用方可以在回调/测量逻辑里查询当前生效样式
+    text_style_stack: Vec<TextStyleRefinement>,
 }
 
 impl LayoutEngine {
@@ -37,8 +40,15 @@ impl LayoutEngine {
         Self {
             taffy: Taffy::new(),
             node_map: HashMap::new(),
+            text_style_stack: Vec::new(),
         }
     }
+
+    /// 查询当前遍历位置的有效文本继承值（祖先容器声明的 `text_style` 叠加后的结果）。
+    /// 只在 `compute_layout` 的遍历过程中有意义，遍历结束后栈为空，返回默认值
+    pub fn current_text_style_refinement(&self) -> TextStyleRefinement {
+        TextStyleRefinement::resolve_cascade(&self.text_style_stack)
+    }
     
     /// 计算布局
     pub fn compute_layout(
@@ -49,9 +59,10 @@ impl LayoutEngine {
         // 清理之前的状态
         self.taffy.clear();
         self.node_map.clear();
-        
+        self.text_style_stack.clear();
+
         // 构建 Taffy 节点树
-        let root_id = self.build_taffy_tree_with_size(root_node, Some(available_space))?;
+        let root_id = self.build_taffy_tree_with_size(root_node, Some(available_space), None)?;
         
         // 计算布局
         self.taffy.compute_layout(
@@ -65,11 +76,22 @@ impl LayoutEngine {
         // 提取布局结果
         self.extract_layout_result(root_id)
     }
-    
-    /// 构建 Taffy 节点树
-    fn build_taffy_tree_with_size(&mut self, node: &LayoutNode, container_size: Option<MySize>) -> Result<Node> {
+
+    /// 构建 Taffy 节点树。`distribution` 是父容器（如果有）声明的剩余空间分配策略，
+    /// 用于覆盖本节点自己声明的 `flex_grow`——容器自身的 `justify_content` 覆盖则完全
+    /// 在 [`LayoutNode::get_taffy_style`] 里就地处理，不需要在这里额外传递
+    fn build_taffy_tree_with_size(
+        &mut self,
+        node: &LayoutNode,
+        container_size: Option<MySize>,
+        distribution: Option<Distribution>,
+    ) -> Result<Node> {
         let mut style = node.get_taffy_style();
-        
+
+        if let Some(distribution) = distribution {
+            style.flex_grow = distribution.resolve_flex_grow(style.flex_grow);
+        }
+
         // 如果是根容器且没有明确设置尺寸，使用可用空间
          if let (Some(size), LayoutNode::Container { .. }) = (container_size, node) {
              if style.size.width == Dimension::Auto {
@@ -79,40 +101,64 @@ impl LayoutEngine {
                  style.size.height = Dimension::Points(size.height);
              }
          }
-        
+
         match node {
-            LayoutNode::Container { children, .. } => {
-                // 递归构建子节点
+            LayoutNode::Container { children, style: container_style } => {
+                // 按 `order` 稳定排序后再构建子节点（Taffy 本身不认识 `order`，
+                // 布局顺序就是子节点在树里的顺序，所以要在这里先排好）
+                let mut ordered_children: Vec<&LayoutNode> = children.iter().collect();
+                ordered_children.sort_by_key(|child| child.order());
+
+                // 本容器声明的文本继承默认值入栈，子孙 Text 节点据此解析有效样式，
+                // 离开这棵子树前再出栈，不影响兄弟容器
+                self.text_style_stack.push(container_style.text_style.clone());
+
                 let mut child_ids = Vec::new();
-                for child in children {
-                    let child_id = self.build_taffy_tree_with_size(child, None)?;
+                for child in ordered_children {
+                    let child_id = self.build_taffy_tree_with_size(child, None, container_style.distribution)?;
                     child_ids.push(child_id);
                 }
-                
+
+                self.text_style_stack.pop();
+
                 // 创建容器节点
                 let node_id = self.taffy.new_with_children(style, &child_ids)
                     .map_err(|e| FlexRenderError::layout_error(format!("创建容器节点失败: {:?}", e)))?;
-                
+
                 self.node_map.insert(node_id, node.clone());
                 Ok(node_id)
             },
             LayoutNode::Text { content, style: text_style } => {
+                // 合并祖先容器声明的文本继承默认值，得到这个节点实际生效的样式，
+                // 布局测量和渲染都应该用这份解析结果而不是节点自己声明的原始样式
+                let resolved_text_style = self.current_text_style_refinement().apply_to(text_style.clone());
+
                 // 对于文本节点，需要测量文本尺寸
-                let measured_style = self.create_text_style_with_measurement(style, content, text_style)?;
-                
+                let measured_style = self.create_text_style_with_measurement(style, content, &resolved_text_style)?;
+
                 let node_id = self.taffy.new_leaf(measured_style)
                     .map_err(|e| FlexRenderError::layout_error(format!("创建文本节点失败: {:?}", e)))?;
-                
-                self.node_map.insert(node_id, node.clone());
+
+                self.node_map.insert(node_id, LayoutNode::Text {
+                    content: content.clone(),
+                    style: resolved_text_style,
+                });
                 Ok(node_id)
             },
             LayoutNode::Image { src, style: image_style } => {
                 // 对于图片节点，需要获取图片尺寸
                 let measured_style = self.create_image_style_with_measurement(style, src, image_style)?;
-                
+
                 let node_id = self.taffy.new_leaf(measured_style)
                     .map_err(|e| FlexRenderError::layout_error(format!("创建图片节点失败: {:?}", e)))?;
-                
+
+                self.node_map.insert(node_id, node.clone());
+                Ok(node_id)
+            },
+            LayoutNode::Spacer { .. } => {
+                let node_id = self.taffy.new_leaf(style)
+                    .map_err(|e| FlexRenderError::layout_error(format!("创建占位节点失败: {:?}", e)))?;
+
                 self.node_map.insert(node_id, node.clone());
                 Ok(node_id)
             },
@@ -126,15 +172,24 @@ impl LayoutEngine {
         content: &str,
         text_style: &TextStyle,
     ) -> Result<Style> {
+        // 只有显式声明的定长宽度才能在构树阶段就知道约束是多少，据此在测量时就把文本
+        // 换行、得到正确的换行高度；百分比/Auto 宽度要等 Taffy 布局阶段才能解析出实际
+        // 像素值，这里无法预先换行，保持原来的无约束单行测量（和 Taffy 自身 auto 尺寸
+        // 节点的处理方式一致）
+        let available_width = match style.size.width {
+            Dimension::Points(width) => Some(width),
+            _ => None,
+        };
+
         // 如果尺寸是自动的，需要测量文本
-        let measured_size = self.measure_text(content, text_style)?;
+        let measured_size = self.measure_text(content, text_style, available_width)?;
         if style.size.width == auto() {
             style.size.width = points(measured_size.width);
         }
         if style.size.height == auto() {
             style.size.height = points(measured_size.height);
         }
-        
+
         Ok(style)
     }
     
@@ -157,20 +212,30 @@ impl LayoutEngine {
         Ok(style)
     }
     
-    /// 测量文本尺寸
-    fn measure_text(&self, content: &str, text_style: &TextStyle) -> Result<MySize> {
+    /// 测量文本尺寸。`available_width` 为 `Some` 时表示已知的主轴宽度约束，测量前先按
+    /// 这个宽度把文本贪心换行，返回的高度是换行后的实际行数乘以行高，而不是永远当作单行
+    /// 处理——否则声明了定宽的多行文本会在布局阶段被量出一个偏矮的盒子，等渲染阶段
+    /// （[`crate::render::canvas::Canvas::wrap_lines`]）再换行时文字就会溢出盒子边界
+    fn measure_text(
+        &self,
+        content: &str,
+        text_style: &TextStyle,
+        available_width: Option<f32>,
+    ) -> Result<MySize> {
         // 使用与渲染时相同的字体度量算法
         let font_manager = crate::resource::font_manager::get_font_manager();
-        let font_data = {
+        let fallbacks: Vec<&str> = text_style.font_fallbacks.iter().map(String::as_str).collect();
+        let font_props = crate::resource::font_manager::FontProperties {
+            weight: text_style.font_weight.to_number(),
+            style: text_style.font_style,
+            stretch: 1.0,
+        };
+        let font_group = {
             let mut manager = font_manager.lock().unwrap();
-            manager.load_font(&text_style.font_family)
-                .or_else(|_| manager.get_default_font())
+            manager.create_font_group_with_properties(&text_style.font_family, &fallbacks, font_props)
                 .map_err(|e| FlexRenderError::render_error(format!("获取字体数据失败: {}", e)))?
         };
-        
-        let font = Font::try_from_bytes(&*font_data)
-            .ok_or_else(|| FlexRenderError::render_error("无效的字体数据".to_string()))?;
-        
+
         // 使用与Canvas相同的DPI处理逻辑（这里假设DPI为1.0）
         let dpi = 1.0;
         let pixel_font_size = if dpi <= 1.0 {
@@ -179,42 +244,183 @@ impl LayoutEngine {
             text_style.font_size * dpi / 72.0
         };
         let scale = Scale::uniform(pixel_font_size);
-        
-        // 计算文本宽度
-        let lines: Vec<&str> = content.lines().collect();
+
+        let primary_font = font_group.primary()
+            .ok_or_else(|| FlexRenderError::render_error("字体组为空".to_string()))?;
+        let primary_font = Font::try_from_bytes(&primary_font.data)
+            .ok_or_else(|| FlexRenderError::render_error("无效的字体数据".to_string()))?;
+
+        // 有宽度约束时先按这个宽度贪心换行，得到实际会占用的行数；否则（Auto/百分比宽度）
+        // 维持原来的每个换行符各自一行、不做自动换行的单行测量
+        let lines: Vec<String> = match available_width {
+            Some(max_width) if max_width > 0.0 => content
+                .split('\n')
+                .flat_map(|paragraph| {
+                    self.wrap_paragraph_by_word(
+                        paragraph,
+                        &font_group,
+                        &primary_font,
+                        &text_style.font_family,
+                        scale,
+                        dpi,
+                        &text_style.font_features,
+                        max_width,
+                    )
+                })
+                .collect(),
+            _ => content.lines().map(str::to_string).collect(),
+        };
+
+        // 计算文本宽度：将每一行切分为由同一个回退字体覆盖的连续字符片段（run），
+        // 分别测量后再求和，这样混合了 CJK/emoji 的行也能得到正确的宽度。
         let max_line_width = lines.iter()
-            .map(|line| {
-                let glyphs: Vec<_> = font
-                    .layout(line, scale, rusttype::point(0.0, 0.0))
-                    .collect();
-                
-                if glyphs.is_empty() {
-                    0.0
-                } else {
-                    // 找到最右边的字符位置
-                    let last_glyph = glyphs.last().unwrap();
-                    let last_x = last_glyph.position().x;
-                    let last_advance = last_glyph.unpositioned().h_metrics().advance_width;
-                    let text_width_pixels = last_x + last_advance;
-                    
-                    // 将像素宽度转换为逻辑单位
-                    if dpi <= 1.0 {
-                        text_width_pixels
-                    } else {
-                        text_width_pixels * 72.0 / dpi
-                    }
-                }
-            })
+            .map(|line| self.measure_line_with_fallback(line, &font_group, &primary_font, &text_style.font_family, scale, dpi, &text_style.font_features))
             .fold(0.0, f32::max);
-        
+
         let line_height = text_style.font_size * text_style.line_height;
         let total_height = lines.len() as f32 * line_height;
-        
 
-        
-        Ok(MySize::new(max_line_width, total_height))
+        // 90/270 度旋转会让文本块的包围盒整体转置，宽高需要互换，
+        // Taffy 才能为竖排文字保留正确形状的盒子；180 度旋转只是翻转，尺寸不变。
+        match text_style.font_transform {
+            FontTransform::Rotate90 | FontTransform::Rotate270 => {
+                Ok(MySize::new(total_height, max_line_width))
+            }
+            FontTransform::None | FontTransform::Rotate180 => {
+                Ok(MySize::new(max_line_width, total_height))
+            }
+        }
     }
-    
+
+    /// 测量一行文本的宽度，按字符所需的回退字体切分为多个 run 分别测量
+    fn measure_line_with_fallback(
+        &self,
+        line: &str,
+        font_group: &crate::resource::font_manager::FontGroup,
+        primary_font: &Font,
+        primary_family: &str,
+        scale: Scale,
+        dpi: f32,
+        font_features: &[(String, u32)],
+    ) -> f32 {
+        if line.is_empty() {
+            return 0.0;
+        }
+
+        // 为 run 中的每个字符找到一个实际要用的字体（找不到覆盖字符的字体时退回主字体）
+        let fallback_fonts: HashMap<String, Font> = font_group.fonts.iter()
+            .filter_map(|info| Font::try_from_bytes(&info.data).map(|f| (info.family.clone(), f)))
+            .collect();
+
+        let mut total_width_pixels = 0.0f32;
+        let mut run = String::new();
+        let mut run_family: Option<String> = None;
+
+        let mut flush_run = |run: &str, family: &Option<String>, total: &mut f32| {
+            if run.is_empty() {
+                return;
+            }
+            let font_bytes = family.as_ref()
+                .and_then(|f| font_group.fonts.iter().find(|info| &info.family == f))
+                .map(|info| info.data.as_slice());
+            let font = family.as_ref()
+                .and_then(|f| fallback_fonts.get(f))
+                .unwrap_or(primary_font);
+
+            // shaping 开启时优先走 HarfBuzz，得到的 advance 已包含 kerning/连字；
+            // 否则（或 shaping 失败）退回到 rusttype 的朴素逐字形布局。
+            if let Some(bytes) = font_bytes {
+                let direction = crate::layout::shaping::detect_direction(run);
+                if let Ok(shaped) = crate::layout::shaping::shape_run(
+                    bytes,
+                    font.units_per_em() as f32,
+                    scale.x,
+                    run,
+                    direction,
+                    font_features,
+                ) {
+                    if !shaped.glyphs.is_empty() {
+                        *total += shaped.total_advance;
+                        return;
+                    }
+                }
+            }
+
+            // 退回到逐字形求和：advance 从共享的 GlyphCache 读取，
+            // 这样布局阶段和渲染阶段对同一个字形得到的是同一份数据，不会重复计算。
+            let glyph_cache = crate::resource::glyph_cache::get_glyph_cache();
+            let font_id = family.clone().unwrap_or_else(|| primary_family.to_string());
+            let mut run_width = 0.0f32;
+            for c in run.chars() {
+                let glyph_id = font.glyph(c).id().0;
+                let cached = glyph_cache.glyph(&font_id, font, glyph_id);
+                run_width += cached.advance * scale.x;
+            }
+            *total += run_width;
+        };
+
+        for c in line.chars() {
+            let family = font_group.font_for_char(c).map(|info| info.family.clone());
+            if family != run_family && !run.is_empty() {
+                flush_run(&run, &run_family, &mut total_width_pixels);
+                run.clear();
+            }
+            run_family = family;
+            run.push(c);
+        }
+        flush_run(&run, &run_family, &mut total_width_pixels);
+
+        // 将像素宽度转换为逻辑单位
+        if dpi <= 1.0 {
+            total_width_pixels
+        } else {
+            total_width_pixels * 72.0 / dpi
+        }
+    }
+
+    /// 在限定宽度下按单词贪心换行，用于 [`measure_text`](Self::measure_text) 估算文字实际需要
+    /// 多少行。单个词本身就超宽时整词单独成行，不做字符级硬断行——这里只需要给布局阶段一个
+    /// 合理的高度估算，真正逐字符精确换行由渲染阶段的 `Canvas::wrap_lines` 负责
+    #[allow(clippy::too_many_arguments)]
+    fn wrap_paragraph_by_word(
+        &self,
+        paragraph: &str,
+        font_group: &crate::resource::font_manager::FontGroup,
+        primary_font: &Font,
+        primary_family: &str,
+        scale: Scale,
+        dpi: f32,
+        font_features: &[(String, u32)],
+        max_width: f32,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+
+        for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            let candidate_width = self.measure_line_with_fallback(
+                &candidate, font_group, primary_font, primary_family, scale, dpi, font_features,
+            );
+
+            if current.is_empty() || candidate_width <= max_width {
+                current = candidate;
+            } else {
+                lines.push(std::mem::take(&mut current));
+                current = word.to_string();
+            }
+        }
+
+        if !current.is_empty() || paragraph.is_empty() {
+            lines.push(current);
+        }
+
+        lines
+    }
+
     /// 获取图片尺寸
     fn get_image_size(&self, src: &str) -> Result<MySize> {
         // 尝试加载图片获取尺寸
@@ -273,6 +479,14 @@ impl LayoutResult {
         MySize::new(self.layout.size.width, self.layout.size.height)
     }
     
+    /// 获取文本节点的旋转变换；非文本节点返回 `FontTransform::None`
+    pub fn text_transform(&self) -> FontTransform {
+        match &self.node {
+            LayoutNode::Text { style, .. } => style.font_transform,
+            _ => FontTransform::None,
+        }
+    }
+
     /// 获取节点的边界矩形
     pub fn bounds(&self) -> MyRect {
         MyRect::new(
@@ -349,6 +563,45 @@ mod tests {
         assert_eq!(layout_result.children.len(), 1);
     }
     
+    #[test]
+    fn test_order_reorders_children_before_layout() {
+        let mut engine = LayoutEngine::new();
+
+        // 子节点按 `order` 从小到大重新排列，即使声明顺序相反
+        let root = LayoutNode::Container {
+            style: ContainerStyle {
+                width: points(200.0),
+                height: points(100.0),
+                flex_direction: FlexDirection::Row,
+                ..Default::default()
+            },
+            children: vec![
+                LayoutNode::Container {
+                    style: ContainerStyle {
+                        width: points(10.0),
+                        height: points(10.0),
+                        order: 2,
+                        ..Default::default()
+                    },
+                    children: vec![],
+                },
+                LayoutNode::Container {
+                    style: ContainerStyle {
+                        width: points(20.0),
+                        height: points(20.0),
+                        order: 1,
+                        ..Default::default()
+                    },
+                    children: vec![],
+                },
+            ],
+        };
+
+        let layout_result = engine.compute_layout(&root, MySize::new(400.0, 300.0)).unwrap();
+        assert_eq!(layout_result.children[0].size().width, 20.0);
+        assert_eq!(layout_result.children[1].size().width, 10.0);
+    }
+
     #[test]
     fn test_text_measurement() {
         let engine = LayoutEngine::new();
@@ -358,8 +611,26 @@ mod tests {
             ..Default::default()
         };
         
-        let size = engine.measure_text("Hello World", &text_style).unwrap();
+        let size = engine.measure_text("Hello World", &text_style, None).unwrap();
         assert!(size.width > 0.0);
         assert!(size.height > 0.0);
     }
+
+    #[test]
+    fn test_text_measurement_wraps_and_grows_height_under_width_constraint() {
+        let engine = LayoutEngine::new();
+        let text_style = TextStyle {
+            font_size: 16.0,
+            line_height: 1.2,
+            ..Default::default()
+        };
+
+        let unconstrained = engine.measure_text("Hello World", &text_style, None).unwrap();
+        let constrained = engine
+            .measure_text("Hello World", &text_style, Some(unconstrained.width / 2.0))
+            .unwrap();
+
+        // 约束宽度小于单行所需宽度时应当换成多行，高度随之增长
+        assert!(constrained.height > unconstrained.height);
+    }
 }
\ No newline at end of file
@@ -4,7 +4,9 @@
 
 pub mod node;
 pub mod engine;
+pub mod shaping;
 
 // 重新导出主要类型
 pub use node::*;
-pub use engine::*;
\ No newline at end of file
+pub use engine::*;
+pub use shaping::{ShapedRun, TextDirection};
\ No newline at end of file
@@ -5,12 +5,40 @@
 use crate::types::*;
 use serde::{Deserialize, Serialize};
 use taffy::prelude::Size;
-use taffy::geometry::{Size as TaffySize, Rect as TaffyRect};
+use taffy::geometry::{Size as TaffySize, Rect as TaffyRect, Point as TaffyPoint};
 use taffy::style::{
     Style, Display, FlexDirection, JustifyContent, AlignItems, AlignContent, FlexWrap,
-    Dimension, LengthPercentage, LengthPercentageAuto
+    Dimension, LengthPercentage, LengthPercentageAuto, Overflow as TaffyOverflow
 };
 
+fn default_flex_shrink() -> f32 {
+    1.0
+}
+
+fn default_flex_basis() -> Dimension {
+    Dimension::Auto
+}
+
+fn default_spacer_min_length() -> Dimension {
+    Dimension::Points(0.0)
+}
+
+fn default_spacer_flex_grow() -> f32 {
+    1.0
+}
+
+/// 以绝对像素值构造 `Dimension`（等价于 `Dimension::Points`），配合 `with_width`/`with_height`
+/// 等样式构建器使用，省去调用方直接拼 taffy 枚举
+pub fn length(value: f32) -> Dimension {
+    Dimension::Points(value)
+}
+
+/// 以父容器百分比构造 `Dimension`（等价于 `Dimension::Percent`，取值 0.0–1.0 表示 0%–100%）。
+/// 百分比相对父容器已解析尺寸计算；若父容器自身也是 `Auto`，taffy 会将其当作未定义处理
+pub fn relative(value: f32) -> Dimension {
+    Dimension::Percent(value)
+}
+
 /// 布局节点枚举
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LayoutNode {
@@ -29,6 +57,15 @@ pub enum LayoutNode {
         src: String,
         style: ImageStyle,
     },
+    /// 弹性占位节点：不渲染任何内容，只在父容器的主轴方向上占据剩余空间，用于在
+    /// 兄弟节点之间插入可伸缩间隙（类似 SwiftUI 的 `Spacer`）。`min_length` 给出被压缩到
+    /// 最小时仍保留的主轴尺寸，`flex_grow` 决定多个 Spacer 之间如何分配剩余空间
+    Spacer {
+        #[serde(default = "default_spacer_min_length")]
+        min_length: Dimension,
+        #[serde(default = "default_spacer_flex_grow")]
+        flex_grow: f32,
+    },
 }
 
 /// 容器样式
@@ -42,7 +79,19 @@ pub struct ContainerStyle {
     pub align_content: AlignContent,
     pub flex_wrap: FlexWrap,
     pub gap: TaffySize<LengthPercentage>,
-    
+
+    // 作为子项参与父容器 flex 布局时的属性（当本容器自身是某个 flex 容器的子项时生效）
+    pub flex_grow: f32,
+    #[serde(default = "default_flex_shrink")]
+    pub flex_shrink: f32,
+    #[serde(default = "default_flex_basis")]
+    pub flex_basis: Dimension,
+    #[serde(default)]
+    pub align_self: Option<AlignItems>,
+    /// 子项在兄弟节点间的视觉顺序；数值越小越靠前，由布局引擎在构建 Taffy 树之前排序
+    #[serde(default)]
+    pub order: i32,
+
     // 尺寸属性
     pub width: Dimension,
     pub height: Dimension,
@@ -56,11 +105,50 @@ pub struct ContainerStyle {
     pub margin: TaffyRect<LengthPercentageAuto>,
     
     // 视觉样式
-    pub background: Option<Color>,
+    pub background: Option<Background>,
     pub border_width: f32,
     pub border_color: Color,
     pub border_radius: f32,
     pub opacity: f32,
+
+    // 投影阴影（建模自截图美化工具的阴影功能，画在容器本体之下）
+    pub shadow_blur: f32,
+    pub shadow_color: Color,
+    pub shadow_offset: Point,
+    /// 阴影轮廓相对容器本体的外扩/内缩量（像素，负值收缩），在模糊之前应用，等价于 CSS `box-shadow` 的 spread
+    #[serde(default)]
+    pub shadow_spread: f32,
+
+    // 溢出裁剪，横纵轴独立控制
+    #[serde(default)]
+    pub overflow_x: Overflow,
+    #[serde(default)]
+    pub overflow_y: Overflow,
+
+    /// 子项在主轴上的剩余空间分配策略；`None` 保持 taffy 的默认行为（完全由各子项
+    /// 自己声明的 `flex_grow`/`justify_content` 决定），`Some` 时按 [`Distribution`]
+    /// 描述的规则覆盖子项的有效 `flex_grow` 或本容器的有效 `justify_content`
+    #[serde(default)]
+    pub distribution: Option<Distribution>,
+
+    /// 声明给文本后代继承的排版默认值；参见 [`resolve_text_styles`]
+    #[serde(default)]
+    pub text_style: TextStyleRefinement,
+
+    /// 样式表选择器用的 id/class，见 `parser::stylesheet`；id 选择器优先级高于 class
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+
+    /// 把本容器标记为重复器：渲染时按 `repeat` 指向的数组变量展开出 N 份子树副本，
+    /// 见 `parser::template`
+    #[serde(default)]
+    pub repeat: Option<RepeatBinding>,
+    /// 条件渲染：取值指向一个变量名，渲染时该变量为假值则整个节点（含子树）被丢弃；
+    /// 见 `parser::template`
+    #[serde(default)]
+    pub when: Option<String>,
 }
 
 impl Default for ContainerStyle {
@@ -73,7 +161,13 @@ impl Default for ContainerStyle {
             align_content: AlignContent::FlexStart,
             flex_wrap: FlexWrap::NoWrap,
             gap: TaffySize::zero(),
-            
+
+            flex_grow: 0.0,
+            flex_shrink: default_flex_shrink(),
+            flex_basis: Dimension::Auto,
+            align_self: None,
+            order: 0,
+
             width: Dimension::Auto,
             height: Dimension::Auto,
             min_width: Dimension::Auto,
@@ -89,10 +183,210 @@ impl Default for ContainerStyle {
             border_color: Color::black(),
             border_radius: 0.0,
             opacity: 1.0,
+
+            shadow_blur: 0.0,
+            shadow_color: Color::new(0, 0, 0, 128),
+            shadow_offset: Point::new(0.0, 0.0),
+            shadow_spread: 0.0,
+
+            overflow_x: Overflow::Visible,
+            overflow_y: Overflow::Visible,
+
+            distribution: None,
+            text_style: TextStyleRefinement::default(),
+
+            id: None,
+            class: None,
+
+            repeat: None,
+            when: None,
         }
     }
 }
 
+impl ContainerStyle {
+    /// 两个轴都裁剪：超出解析后的边框盒（扣除 padding/border 之后）的子内容会被裁掉
+    pub fn clip() -> Self {
+        Self {
+            overflow_x: Overflow::Clip,
+            overflow_y: Overflow::Clip,
+            ..Default::default()
+        }
+    }
+
+    /// 仅横轴裁剪，纵轴仍然可见
+    pub fn clip_x() -> Self {
+        Self {
+            overflow_x: Overflow::Clip,
+            ..Default::default()
+        }
+    }
+
+    /// 仅纵轴裁剪，横轴仍然可见
+    pub fn clip_y() -> Self {
+        Self {
+            overflow_y: Overflow::Clip,
+            ..Default::default()
+        }
+    }
+
+    /// 两个轴都不裁剪（默认行为），仅作为语义化入口与 `clip()` 对称
+    pub fn visible() -> Self {
+        Self::default()
+    }
+
+    /// 设置宽度，配合 [`length`]/[`relative`] 等构造函数使用，避免直接拼 `Dimension` 枚举
+    pub fn with_width(mut self, width: Dimension) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// 设置高度
+    pub fn with_height(mut self, height: Dimension) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// 设置最小宽度
+    pub fn with_min_width(mut self, min_width: Dimension) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    /// 设置最小高度
+    pub fn with_min_height(mut self, min_height: Dimension) -> Self {
+        self.min_height = min_height;
+        self
+    }
+
+    /// 设置最大宽度
+    pub fn with_max_width(mut self, max_width: Dimension) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// 设置最大高度
+    pub fn with_max_height(mut self, max_height: Dimension) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    /// 宽高都铺满父容器，等价于 `with_width(relative(1.0)).with_height(relative(1.0))`
+    pub fn size_full(self) -> Self {
+        self.with_width(relative(1.0)).with_height(relative(1.0))
+    }
+
+    /// 按 `shares` 份额参与父 flex 容器主轴剩余空间的分配，类似 CSS Grid 的 `fr` 单位：
+    /// 兄弟节点分别声明 `fraction(1)`/`fraction(2)` 时按 1:2 瓜分主轴剩余空间。
+    /// 实现上等价于把 `flex_grow` 设为 `shares`、`flex_basis` 归零，让尺寸完全由剩余空间的
+    /// 分配结果决定，而不是由 `width`/`height` 本身决定
+    pub fn fraction(mut self, shares: f32) -> Self {
+        self.flex_grow = shares;
+        self.flex_basis = length(0.0);
+        self
+    }
+
+    /// 结合 `distribution`（若有）得到实际生效的 `justify_content`
+    pub fn effective_justify_content(&self) -> JustifyContent {
+        match self.distribution {
+            Some(distribution) => distribution.resolve_justify_content(self.justify_content),
+            None => self.justify_content,
+        }
+    }
+}
+
+/// 容器单个轴上的溢出处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Overflow {
+    /// 子内容可以画出容器边界之外，不做任何裁剪
+    #[default]
+    Visible,
+    /// 裁剪超出边界的内容；不提供滚动，语义上对应 CSS 的 `overflow: clip`
+    Clip,
+    /// 裁剪超出边界的内容；语义上对应 CSS 的 `overflow: hidden`。渲染效果与 `Clip`
+    /// 相同，区别仅在于（未来若支持滚动/聚焦）`Hidden` 允许程序性滚动而 `Clip` 不允许
+    Hidden,
+}
+
+impl Overflow {
+    /// 是否需要裁剪：`Visible` 之外的两种取值都需要
+    pub fn clips(self) -> bool {
+        !matches!(self, Overflow::Visible)
+    }
+
+    fn to_taffy(self) -> TaffyOverflow {
+        match self {
+            Overflow::Visible => TaffyOverflow::Visible,
+            Overflow::Clip | Overflow::Hidden => TaffyOverflow::Hidden,
+        }
+    }
+}
+
+/// 容器在主轴上分配剩余空间的策略，复用 taffy 已有的 `flex_grow`/`justify_content`
+/// 实现，而不是另起一套排版算法——每个变体只是这两个既有机制的一种预设组合
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Distribution {
+    /// 没有显式声明 `flex_grow` 的子项平分剩余空间，已经声明了非零 `flex_grow` 的子项
+    /// 保留自己的权重
+    Fill,
+    /// 忽略子项各自声明的 `flex_grow`，强制所有子项按 1:1 平分剩余空间
+    FillEqually,
+    /// 按子项各自声明的 `flex_grow` 权重比例分配剩余空间；等价于 taffy 的默认行为，
+    /// 这个变体只是让分配策略在样式里显式可读
+    FillProportionally,
+    /// 子项之间等距分布，两端不留间隙，等价于 CSS `justify-content: space-between`
+    EqualSpacing,
+    /// 每个子项两侧留出相同的间隙（首尾边界处的间隙是子项之间的一半），等价于 CSS
+    /// `justify-content: space-around`
+    EqualCentering,
+}
+
+impl Distribution {
+    /// 把策略应用到单个子项已经声明的 `flex_grow` 上，得到它在布局时实际生效的 `flex_grow`
+    pub(crate) fn resolve_flex_grow(self, declared: f32) -> f32 {
+        match self {
+            Distribution::FillEqually => 1.0,
+            Distribution::Fill if declared <= 0.0 => 1.0,
+            _ => declared,
+        }
+    }
+
+    /// 把策略应用到容器自身声明的 `justify_content` 上；只有两个等距分布变体会覆盖它
+    fn resolve_justify_content(self, declared: JustifyContent) -> JustifyContent {
+        match self {
+            Distribution::EqualSpacing => JustifyContent::SpaceBetween,
+            Distribution::EqualCentering => JustifyContent::SpaceAround,
+            _ => declared,
+        }
+    }
+}
+
+/// `ContainerStyle::repeat` 的绑定信息：数据源变量名与每次迭代绑定的局部变量名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatBinding {
+    /// 指向一个数组类型变量的名字，见 `parser::template::TemplateProcessor::apply_variables`
+    pub source: String,
+    /// 每次迭代中当前元素绑定的局部变量名，供子树内 `{{item.field}}` 引用
+    #[serde(default = "default_repeat_item_name")]
+    pub item: String,
+}
+
+fn default_repeat_item_name() -> String {
+    "item".to_string()
+}
+
+fn default_min_font_size() -> f32 {
+    8.0
+}
+
+fn default_max_font_size() -> f32 {
+    96.0
+}
+
+fn default_tab_width() -> f32 {
+    40.0
+}
+
 /// 文本样式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextStyle {
@@ -100,20 +394,76 @@ pub struct TextStyle {
     pub font_family: String,
     pub font_size: f32,
     pub font_weight: FontWeight,
+    /// 字体样式（正常/斜体/倾斜体），决定 `measure_text` 实际请求的字体面
+    #[serde(default)]
+    pub font_style: crate::resource::font_manager::FontStyle,
     pub line_height: f32,
     pub letter_spacing: f32,
-    
+    /// 当主字体缺少某个字符的字形时依次尝试的回退字体（如 CJK、emoji 字体）
+    #[serde(default)]
+    pub font_fallbacks: Vec<String>,
+    /// 开启 `shaping` feature 时喂给 HarfBuzz 的 OpenType 特性开关，例如 `("liga", 1)`
+    /// 启用标准连字、`("calt", 0)` 关闭上下文替换；未开启该 feature 时被忽略
+    #[serde(default)]
+    pub font_features: Vec<(String, u32)>,
+
     // 文本属性
     pub color: Color,
     pub text_align: TextAlign,
     pub text_decoration: TextDecoration,
     pub text_transform: TextTransform,
-    
+    /// 文本旋转变换（竖排轴标签等场景），布局与渲染都需要感知这个值
+    #[serde(default)]
+    pub font_transform: FontTransform,
+    /// 文本块在边界框内的纵向对齐方式
+    #[serde(default)]
+    pub vertical_align: VerticalAlign,
+    /// 字号自适应策略：是否允许为了塞进边界框而缩小/放大字号
+    #[serde(default)]
+    pub resize: TextResize,
+    /// `resize` 为 `NoLarger`/`Max` 时允许缩小到的最小字号
+    #[serde(default = "default_min_font_size")]
+    pub min_font_size: f32,
+    /// `resize` 为 `Max` 时允许放大到的最大字号
+    #[serde(default = "default_max_font_size")]
+    pub max_font_size: f32,
+    /// 制表符展开到下一个整数倍位置所用的步进宽度（逻辑单位/像素）
+    #[serde(default = "default_tab_width")]
+    pub tab_width: f32,
+    /// 文字放不下边界框时的处理方式：裁剪或省略号截断；默认 `Visible` 保持现有的允许溢出行为
+    #[serde(default)]
+    pub overflow: TextOverflow,
+    /// 最多显示的行数，超出的行会被丢弃；配合 `overflow` 用省略号截断保留的最后一行
+    #[serde(default)]
+    pub max_lines: Option<u32>,
+
     // 布局属性
     pub width: Dimension,
     pub height: Dimension,
     pub padding: TaffyRect<LengthPercentage>,
     pub margin: TaffyRect<LengthPercentageAuto>,
+
+    // 作为子项参与父容器 flex 布局时的属性
+    #[serde(default)]
+    pub flex_grow: f32,
+    #[serde(default = "default_flex_shrink")]
+    pub flex_shrink: f32,
+    #[serde(default = "default_flex_basis")]
+    pub flex_basis: Dimension,
+    #[serde(default)]
+    pub align_self: Option<AlignItems>,
+    #[serde(default)]
+    pub order: i32,
+
+    /// 样式表选择器用的 id/class，见 `parser::stylesheet`；id 选择器优先级高于 class
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+
+    /// 条件渲染：取值指向一个变量名，渲染时该变量为假值则本节点被丢弃；见 `parser::template`
+    #[serde(default)]
+    pub when: Option<String>,
 }
 
 impl Default for TextStyle {
@@ -122,22 +472,70 @@ impl Default for TextStyle {
             font_family: "Arial".to_string(),
             font_size: 16.0,
             font_weight: FontWeight::Normal,
+            font_style: crate::resource::font_manager::FontStyle::Normal,
             line_height: 1.2,
             letter_spacing: 0.0,
-            
+            font_fallbacks: Vec::new(),
+            font_features: Vec::new(),
+
             color: Color::black(),
             text_align: TextAlign::Left,
             text_decoration: TextDecoration::None,
             text_transform: TextTransform::None,
-            
+            font_transform: FontTransform::None,
+            vertical_align: VerticalAlign::Top,
+            resize: TextResize::None,
+            min_font_size: default_min_font_size(),
+            max_font_size: default_max_font_size(),
+            tab_width: default_tab_width(),
+            overflow: TextOverflow::Visible,
+            max_lines: None,
+
             width: Dimension::Auto,
             height: Dimension::Auto,
             padding: TaffyRect::zero(),
             margin: TaffyRect::auto(),
+
+            flex_grow: 0.0,
+            flex_shrink: default_flex_shrink(),
+            flex_basis: default_flex_basis(),
+            align_self: None,
+            order: 0,
+
+            id: None,
+            class: None,
+            when: None,
         }
     }
 }
 
+impl TextStyle {
+    /// 设置宽度，配合 [`length`]/[`relative`] 等构造函数使用
+    pub fn with_width(mut self, width: Dimension) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// 设置高度
+    pub fn with_height(mut self, height: Dimension) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// 宽高都铺满父容器，等价于 `with_width(relative(1.0)).with_height(relative(1.0))`
+    pub fn size_full(self) -> Self {
+        self.with_width(relative(1.0)).with_height(relative(1.0))
+    }
+
+    /// 按 `shares` 份额参与父 flex 容器主轴剩余空间的分配，见
+    /// [`ContainerStyle::fraction`]
+    pub fn fraction(mut self, shares: f32) -> Self {
+        self.flex_grow = shares;
+        self.flex_basis = length(0.0);
+        self
+    }
+}
+
 /// 图片样式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageStyle {
@@ -154,6 +552,28 @@ pub struct ImageStyle {
     // 视觉效果
     pub opacity: f32,
     pub border_radius: f32,
+
+    // 作为子项参与父容器 flex 布局时的属性
+    #[serde(default)]
+    pub flex_grow: f32,
+    #[serde(default = "default_flex_shrink")]
+    pub flex_shrink: f32,
+    #[serde(default = "default_flex_basis")]
+    pub flex_basis: Dimension,
+    #[serde(default)]
+    pub align_self: Option<AlignItems>,
+    #[serde(default)]
+    pub order: i32,
+
+    /// 样式表选择器用的 id/class，见 `parser::stylesheet`；id 选择器优先级高于 class
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+
+    /// 条件渲染：取值指向一个变量名，渲染时该变量为假值则本节点被丢弃；见 `parser::template`
+    #[serde(default)]
+    pub when: Option<String>,
 }
 
 impl Default for ImageStyle {
@@ -161,18 +581,55 @@ impl Default for ImageStyle {
         Self {
             object_fit: ObjectFit::Fill,
             object_position: Point::new(0.5, 0.5), // 居中
-            
+
             width: Dimension::Auto,
             height: Dimension::Auto,
             padding: TaffyRect::zero(),
             margin: TaffyRect::auto(),
-            
+
             opacity: 1.0,
             border_radius: 0.0,
+
+            flex_grow: 0.0,
+            flex_shrink: default_flex_shrink(),
+            flex_basis: default_flex_basis(),
+            align_self: None,
+            order: 0,
+
+            id: None,
+            class: None,
+            when: None,
         }
     }
 }
 
+impl ImageStyle {
+    /// 设置宽度，配合 [`length`]/[`relative`] 等构造函数使用
+    pub fn with_width(mut self, width: Dimension) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// 设置高度
+    pub fn with_height(mut self, height: Dimension) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// 宽高都铺满父容器，等价于 `with_width(relative(1.0)).with_height(relative(1.0))`
+    pub fn size_full(self) -> Self {
+        self.with_width(relative(1.0)).with_height(relative(1.0))
+    }
+
+    /// 按 `shares` 份额参与父 flex 容器主轴剩余空间的分配，见
+    /// [`ContainerStyle::fraction`]
+    pub fn fraction(mut self, shares: f32) -> Self {
+        self.flex_grow = shares;
+        self.flex_basis = length(0.0);
+        self
+    }
+}
+
 /// 文本装饰
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TextDecoration {
@@ -191,6 +648,220 @@ pub enum TextTransform {
     Capitalize,
 }
 
+/// 矩形四边各自独立的精化覆盖：只覆盖声明了的边，未声明的边保持基准值不变。
+/// 用于 [`StyleRefinement::padding`]，这样级联样式可以只覆盖 `padding.left`
+/// 而不必连带覆盖其余三边
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RectRefinement<T> {
+    pub left: Option<T>,
+    pub right: Option<T>,
+    pub top: Option<T>,
+    pub bottom: Option<T>,
+}
+
+impl<T: Copy> RectRefinement<T> {
+    /// 用 `other` 中非空的边覆盖 `self` 对应边
+    pub fn refine(&mut self, other: &RectRefinement<T>) {
+        if other.left.is_some() {
+            self.left = other.left;
+        }
+        if other.right.is_some() {
+            self.right = other.right;
+        }
+        if other.top.is_some() {
+            self.top = other.top;
+        }
+        if other.bottom.is_some() {
+            self.bottom = other.bottom;
+        }
+    }
+
+    /// 把已精化的各边叠加到一个完整的 `TaffyRect` 基准值上，未覆盖的边保留 `base`
+    pub fn apply_to(&self, base: TaffyRect<T>) -> TaffyRect<T> {
+        TaffyRect {
+            left: self.left.unwrap_or(base.left),
+            right: self.right.unwrap_or(base.right),
+            top: self.top.unwrap_or(base.top),
+            bottom: self.bottom.unwrap_or(base.bottom),
+        }
+    }
+}
+
+/// 级联样式精化（参照 GPUI 的 `Refineable` 思路）：字段全部是 `Option`（或逐边 `Option`），
+/// 只有声明了的字段才会在 [`refine`](StyleRefinement::refine) 时覆盖已继承的值，
+/// 这样子节点不用在每个节点上重复声明和父节点一样的 `color`/`font_size` 等属性
+#[derive(Debug, Clone, Default)]
+pub struct StyleRefinement {
+    pub color: Option<Color>,
+    pub font_size: Option<f32>,
+    pub opacity: Option<f32>,
+    pub text_align: Option<TextAlign>,
+    pub padding: RectRefinement<LengthPercentage>,
+    pub margin: RectRefinement<LengthPercentageAuto>,
+    pub background: Option<Background>,
+    pub display: Option<Display>,
+    pub flex_direction: Option<FlexDirection>,
+    pub border_radius: Option<f32>,
+}
+
+impl StyleRefinement {
+    /// 用 `other` 中非空的字段覆盖 `self` 对应字段，空字段保持不变；
+    /// 按「父 -> 子」逐层调用即可把继承链叠加成子节点最终生效的样式
+    pub fn refine(&mut self, other: &StyleRefinement) {
+        if other.color.is_some() {
+            self.color = other.color;
+        }
+        if other.font_size.is_some() {
+            self.font_size = other.font_size;
+        }
+        if other.opacity.is_some() {
+            self.opacity = other.opacity;
+        }
+        if other.text_align.is_some() {
+            self.text_align = other.text_align;
+        }
+        self.padding.refine(&other.padding);
+        self.margin.refine(&other.margin);
+        if other.background.is_some() {
+            self.background = other.background.clone();
+        }
+        if other.display.is_some() {
+            self.display = other.display;
+        }
+        if other.flex_direction.is_some() {
+            self.flex_direction = other.flex_direction;
+        }
+        if other.border_radius.is_some() {
+            self.border_radius = other.border_radius;
+        }
+    }
+
+    /// 按「最先的在最底层、最后的在最上层」的顺序依次叠加一串精化，返回最终生效的样式。
+    /// 等价于对每个元素依次调用 [`refine`](StyleRefinement::refine)，但省去调用方手动维护
+    /// 累加变量的样板代码
+    pub fn resolve_cascade(cascade: &[StyleRefinement]) -> StyleRefinement {
+        let mut resolved = StyleRefinement::default();
+        for refinement in cascade {
+            resolved.refine(refinement);
+        }
+        resolved
+    }
+}
+
+/// 容器声明给文本后代继承的排版默认值；字段全部是 `Option`，只有声明了的字段才会在
+/// [`resolve_text_styles`] 遍历到的 `Text` 节点上生效
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TextStyleRefinement {
+    pub font_family: Option<String>,
+    pub font_size: Option<f32>,
+    pub font_weight: Option<FontWeight>,
+    pub color: Option<Color>,
+    pub line_height: Option<f32>,
+    pub letter_spacing: Option<f32>,
+}
+
+impl TextStyleRefinement {
+    /// 用 `other` 中非空的字段覆盖 `self` 对应字段
+    pub fn refine(&mut self, other: &TextStyleRefinement) {
+        if other.font_family.is_some() {
+            self.font_family = other.font_family.clone();
+        }
+        if other.font_size.is_some() {
+            self.font_size = other.font_size;
+        }
+        if other.font_weight.is_some() {
+            self.font_weight = other.font_weight;
+        }
+        if other.color.is_some() {
+            self.color = other.color;
+        }
+        if other.line_height.is_some() {
+            self.line_height = other.line_height;
+        }
+        if other.letter_spacing.is_some() {
+            self.letter_spacing = other.letter_spacing;
+        }
+    }
+
+    /// 按「最先的在最底层（最远的祖先）、最后的在最上层（最近的祖先）」的顺序叠加一串
+    /// 继承栈，得到某个 `Text` 节点在该祖先链下生效的继承值
+    pub fn resolve_cascade(stack: &[TextStyleRefinement]) -> TextStyleRefinement {
+        let mut resolved = TextStyleRefinement::default();
+        for refinement in stack {
+            resolved.refine(refinement);
+        }
+        resolved
+    }
+
+    /// 把继承值叠加到一个具体的 `TextStyle` 上。由于 `TextStyle` 本身没有 `Option` 字段，
+    /// 这里把「等于 `TextStyle::default()` 对应字段」视为节点没有显式声明，从而允许继承值
+    /// 覆盖——节点如果把某个字段显式设成了和默认值相同的值，会被当作未设置
+    pub fn apply_to(&self, mut style: TextStyle) -> TextStyle {
+        let default = TextStyle::default();
+        if let Some(font_family) = &self.font_family {
+            if style.font_family == default.font_family {
+                style.font_family = font_family.clone();
+            }
+        }
+        if let Some(font_size) = self.font_size {
+            if style.font_size == default.font_size {
+                style.font_size = font_size;
+            }
+        }
+        if let Some(font_weight) = self.font_weight {
+            if style.font_weight == default.font_weight {
+                style.font_weight = font_weight;
+            }
+        }
+        if let Some(color) = self.color {
+            if style.color == default.color {
+                style.color = color;
+            }
+        }
+        if let Some(line_height) = self.line_height {
+            if style.line_height == default.line_height {
+                style.line_height = line_height;
+            }
+        }
+        if let Some(letter_spacing) = self.letter_spacing {
+            if style.letter_spacing == default.letter_spacing {
+                style.letter_spacing = letter_spacing;
+            }
+        }
+        style
+    }
+}
+
+/// 沿树自顶向下解析文本样式继承：容器节点在 `ContainerStyle::text_style` 中声明的
+/// 排版默认值会被压入一个显式的级联栈，子孙 `Text` 节点据此合并出各自的有效样式，
+/// 退出容器子树时再弹栈。返回一棵 `Text` 节点样式都已解析完毕的新树，其余节点原样克隆
+pub fn resolve_text_styles(root: &LayoutNode) -> LayoutNode {
+    let mut stack: Vec<TextStyleRefinement> = Vec::new();
+    resolve_text_styles_in(root, &mut stack)
+}
+
+fn resolve_text_styles_in(node: &LayoutNode, stack: &mut Vec<TextStyleRefinement>) -> LayoutNode {
+    match node {
+        LayoutNode::Container { style, children } => {
+            stack.push(style.text_style.clone());
+            let resolved_children = children
+                .iter()
+                .map(|child| resolve_text_styles_in(child, stack))
+                .collect();
+            stack.pop();
+            LayoutNode::Container {
+                style: style.clone(),
+                children: resolved_children,
+            }
+        }
+        LayoutNode::Text { content, style } => LayoutNode::Text {
+            content: content.clone(),
+            style: TextStyleRefinement::resolve_cascade(stack).apply_to(style.clone()),
+        },
+        LayoutNode::Image { .. } | LayoutNode::Spacer { .. } => node.clone(),
+    }
+}
+
 impl LayoutNode {
     /// 获取节点的样式信息（用于布局计算）
     pub fn get_taffy_style(&self) -> Style {
@@ -199,12 +870,16 @@ impl LayoutNode {
                 Style {
                     display: style.display,
                     flex_direction: style.flex_direction,
-                    justify_content: Some(style.justify_content),
+                    justify_content: Some(style.effective_justify_content()),
                      align_items: Some(style.align_items),
                      align_content: Some(style.align_content),
                     flex_wrap: style.flex_wrap,
                     gap: style.gap,
-                    
+                    overflow: TaffyPoint {
+                        x: style.overflow_x.to_taffy(),
+                        y: style.overflow_y.to_taffy(),
+                    },
+
                     size: Size {
                         width: style.width,
                         height: style.height,
@@ -220,7 +895,12 @@ impl LayoutNode {
                     
                     padding: style.padding,
                     margin: style.margin,
-                    
+
+                    flex_grow: style.flex_grow,
+                    flex_shrink: style.flex_shrink,
+                    flex_basis: style.flex_basis,
+                    align_self: style.align_self,
+
                     ..Default::default()
                 }
             },
@@ -232,6 +912,12 @@ impl LayoutNode {
                     },
                     padding: style.padding,
                     margin: style.margin,
+
+                    flex_grow: style.flex_grow,
+                    flex_shrink: style.flex_shrink,
+                    flex_basis: style.flex_basis,
+                    align_self: style.align_self,
+
                     ..Default::default()
                 }
             },
@@ -243,12 +929,41 @@ impl LayoutNode {
                     },
                     padding: style.padding,
                     margin: style.margin,
+
+                    flex_grow: style.flex_grow,
+                    flex_shrink: style.flex_shrink,
+                    flex_basis: style.flex_basis,
+                    align_self: style.align_self,
+
+                    ..Default::default()
+                }
+            },
+            LayoutNode::Spacer { min_length, flex_grow } => {
+                Style {
+                    min_size: Size {
+                        width: *min_length,
+                        height: *min_length,
+                    },
+                    flex_grow: *flex_grow,
+                    flex_shrink: 0.0,
+                    flex_basis: *min_length,
+
                     ..Default::default()
                 }
             },
         }
     }
-    
+
+    /// 获取节点的 `order`：子节点按此值从小到大排序后再参与布局，数值相同则保持原有相对顺序
+    pub fn order(&self) -> i32 {
+        match self {
+            LayoutNode::Container { style, .. } => style.order,
+            LayoutNode::Text { style, .. } => style.order,
+            LayoutNode::Image { style, .. } => style.order,
+            LayoutNode::Spacer { .. } => 0,
+        }
+    }
+
     /// 获取子节点
     pub fn children(&self) -> &[LayoutNode] {
         match self {
@@ -278,6 +993,25 @@ mod tests {
         assert_eq!(style.opacity, 1.0);
     }
     
+    #[test]
+    fn test_style_refinement_only_overwrites_some_fields() {
+        let mut inherited = StyleRefinement {
+            color: Some(Color::black()),
+            font_size: Some(16.0),
+            ..Default::default()
+        };
+
+        let own = StyleRefinement {
+            font_size: Some(24.0),
+            ..Default::default()
+        };
+
+        inherited.refine(&own);
+
+        assert_eq!(inherited.color, Some(Color::black()));
+        assert_eq!(inherited.font_size, Some(24.0));
+    }
+
     #[test]
     fn test_text_style_default() {
         let style = TextStyle::default();
@@ -304,7 +1038,30 @@ mod tests {
             content: "World".to_string(),
             style: TextStyle::default(),
         };
-        
+
         assert_eq!(text.children().len(), 0);
     }
+
+    #[test]
+    fn test_size_full_sets_both_axes_to_relative_100_percent() {
+        let style = ContainerStyle::default().size_full();
+        assert_eq!(style.width, relative(1.0));
+        assert_eq!(style.height, relative(1.0));
+    }
+
+    #[test]
+    fn test_fraction_sets_flex_grow_and_zeroes_basis() {
+        let style = ContainerStyle::default().fraction(2.0);
+        assert_eq!(style.flex_grow, 2.0);
+        assert_eq!(style.flex_basis, length(0.0));
+    }
+
+    #[test]
+    fn test_with_width_and_height_builders() {
+        let style = ContainerStyle::default()
+            .with_width(length(200.0))
+            .with_height(relative(0.5));
+        assert_eq!(style.width, length(200.0));
+        assert_eq!(style.height, relative(0.5));
+    }
 }
\ No newline at end of file
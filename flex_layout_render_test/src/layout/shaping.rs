@@ -0,0 +1,172 @@
+//! 复杂文字排版（shaping）
+//!
+//! `measure_text` 默认逐字形累加 rusttype 给出的 advance，它只会从左到右按水平 metrics
+//! 简单地累加 advance，没有 kerning、没有 GSUB 连字、也无法正确处理阿拉伯语、希伯来语、
+//! 天城文或泰文这类需要重排和组合标记定位的复杂文字。本模块在 `shaping` feature 开启时，
+//! 通过 HarfBuzz 对每个 run 进行真正的 shaping，并把结果（定位后的字形 id/偏移）保留下来，
+//! 供渲染阶段复用，避免重新排版一次。
+
+use crate::error::*;
+
+/// 书写方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// 一个已定位的字形
+#[derive(Debug, Clone, Copy)]
+pub struct PositionedGlyph {
+    /// 字形 id（在具体字体内部有效）
+    pub glyph_id: u32,
+    /// 相对 run 起点的 x 偏移（像素）
+    pub x_offset: f32,
+    /// 相对 run 起点的 y 偏移（像素）
+    pub y_offset: f32,
+    /// 该字形的前进宽度（像素）
+    pub x_advance: f32,
+    /// 该字形的纵向前进宽度（像素），横排文本通常为 0，竖排/上下文相关的字体才会非零
+    pub y_advance: f32,
+}
+
+/// 一个 run（使用同一种字体、同一方向）的 shaping 结果
+#[derive(Debug, Clone)]
+pub struct ShapedRun {
+    /// 定位后的字形序列
+    pub glyphs: Vec<PositionedGlyph>,
+    /// 整个 run 的总前进宽度（像素）
+    pub total_advance: f32,
+}
+
+impl ShapedRun {
+    fn empty() -> Self {
+        Self {
+            glyphs: Vec::new(),
+            total_advance: 0.0,
+        }
+    }
+}
+
+/// 根据 run 中出现的 Unicode 范围粗略判断书写方向
+///
+/// 阿拉伯语和希伯来语按 RTL 处理，其余按 LTR 处理；真正的双向（bidi）重排
+/// 留给上层按段落调用 `unicode-bidi` 完成，这里只决定单个 run 内部的 shaping 方向。
+pub fn detect_direction(text: &str) -> TextDirection {
+    let is_rtl = text.chars().any(|c| {
+        let cp = c as u32;
+        (0x0590..=0x05FF).contains(&cp) // Hebrew
+            || (0x0600..=0x06FF).contains(&cp) // Arabic
+            || (0x0750..=0x077F).contains(&cp) // Arabic Supplement
+    });
+
+    if is_rtl {
+        TextDirection::RightToLeft
+    } else {
+        TextDirection::LeftToRight
+    }
+}
+
+#[cfg(feature = "shaping")]
+mod harfbuzz_backend {
+    use super::*;
+
+    /// 把一个 4 字符的 OpenType 特性标签（不足 4 字符用空格补齐）解析成 HarfBuzz 的 `Tag`
+    fn parse_tag(tag: &str) -> harfbuzz_rs::Tag {
+        let mut bytes = [b' '; 4];
+        for (slot, b) in bytes.iter_mut().zip(tag.as_bytes().iter()) {
+            *slot = *b;
+        }
+        harfbuzz_rs::Tag::new(
+            bytes[0] as char,
+            bytes[1] as char,
+            bytes[2] as char,
+            bytes[3] as char,
+        )
+    }
+
+    /// 用 HarfBuzz 对一个 run 进行 shaping，返回定位后的字形及总宽度
+    ///
+    /// `font_bytes` 是解析出的字体文件原始数据，`units_per_em` 用于把 HarfBuzz
+    /// 返回的字体单位换算成像素：`advance_px = advance_font_units / units_per_em * pixel_size`。
+    /// `features` 是 `TextStyle::font_features` 透传过来的 OpenType 特性开关（如 `("liga", 1)`），
+    /// 整个 run 全范围生效。
+    pub fn shape_run(
+        font_bytes: &[u8],
+        units_per_em: f32,
+        pixel_size: f32,
+        text: &str,
+        direction: TextDirection,
+        features: &[(String, u32)],
+    ) -> Result<ShapedRun> {
+        let face = harfbuzz_rs::Face::from_bytes(font_bytes, 0);
+        let hb_font = harfbuzz_rs::Font::new(face);
+
+        let mut buffer = harfbuzz_rs::UnicodeBuffer::new().add_str(text);
+        buffer = buffer.set_direction(match direction {
+            TextDirection::LeftToRight => harfbuzz_rs::Direction::Ltr,
+            TextDirection::RightToLeft => harfbuzz_rs::Direction::Rtl,
+        });
+
+        let hb_features: Vec<harfbuzz_rs::Feature> = features
+            .iter()
+            .map(|(tag, value)| harfbuzz_rs::Feature::new(parse_tag(tag), *value, 0..text.len() as u32))
+            .collect();
+
+        let output = harfbuzz_rs::shape(&hb_font, buffer, &hb_features);
+        let positions = output.get_glyph_positions();
+        let infos = output.get_glyph_infos();
+
+        let scale = pixel_size / units_per_em;
+        let mut glyphs = Vec::with_capacity(positions.len());
+        let mut total_advance = 0.0f32;
+
+        for (pos, info) in positions.iter().zip(infos.iter()) {
+            let x_advance = pos.x_advance as f32 * scale;
+            glyphs.push(PositionedGlyph {
+                glyph_id: info.codepoint,
+                x_offset: pos.x_offset as f32 * scale,
+                y_offset: pos.y_offset as f32 * scale,
+                x_advance,
+                y_advance: pos.y_advance as f32 * scale,
+            });
+            total_advance += x_advance;
+        }
+
+        Ok(ShapedRun {
+            glyphs,
+            total_advance,
+        })
+    }
+}
+
+#[cfg(feature = "shaping")]
+pub use harfbuzz_backend::shape_run;
+
+/// `shaping` feature 关闭时的占位实现：调用方应退回到现有的 rusttype 测量路径
+#[cfg(not(feature = "shaping"))]
+pub fn shape_run(
+    _font_bytes: &[u8],
+    _units_per_em: f32,
+    _pixel_size: f32,
+    _text: &str,
+    _direction: TextDirection,
+    _features: &[(String, u32)],
+) -> Result<ShapedRun> {
+    Ok(ShapedRun::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_direction_ltr() {
+        assert_eq!(detect_direction("Hello"), TextDirection::LeftToRight);
+    }
+
+    #[test]
+    fn test_detect_direction_rtl() {
+        assert_eq!(detect_direction("مرحبا"), TextDirection::RightToLeft);
+    }
+}
@@ -51,6 +51,7 @@ pub mod parser;
 pub mod layout;
 pub mod render;
 pub mod resource;
+pub mod server;
 
 // 重新导出主要类型
 pub use error::{FlexRenderError, Result};
@@ -71,6 +72,7 @@ pub struct FlexRenderer {
     root_node: layout::node::LayoutNode,
     variables: TemplateVariables,
     template_processor: TemplateProcessor,
+    parse_warnings: Vec<String>,
 }
 
 impl FlexRenderer {
@@ -102,14 +104,16 @@ impl FlexRenderer {
     /// let renderer = FlexRenderer::from_yaml(yaml_content).unwrap();
     /// ```
     pub fn from_yaml(yaml_content: &str) -> Result<Self> {
-        let (template_config, root_node) = YamlParser::parse(yaml_content)?;
+        let mut parse_warnings = Vec::new();
+        let (template_config, root_node) = YamlParser::parse_lenient(yaml_content, &mut parse_warnings)?;
         let template_processor = TemplateProcessor::new()?;
-        
+
         Ok(Self {
             template_config,
             root_node,
             variables: TemplateVariables::new(),
             template_processor,
+            parse_warnings,
         })
     }
     
@@ -130,6 +134,22 @@ impl FlexRenderer {
         let content = std::fs::read_to_string(path)?;
         Self::from_yaml(&content)
     }
+
+    /// 直接用已经解析好的模板配置和根节点构造渲染器，跳过 YAML 解析
+    ///
+    /// 供需要缓存解析结果、反复用不同变量渲染同一模板的调用方使用（例如 [`crate::server`] 的
+    /// 渲染 worker），避免每次请求都重新跑一遍 YAML 解析
+    pub fn from_parts(template_config: TemplateConfig, root_node: layout::node::LayoutNode) -> Result<Self> {
+        let template_processor = TemplateProcessor::new()?;
+
+        Ok(Self {
+            template_config,
+            root_node,
+            variables: TemplateVariables::new(),
+            template_processor,
+            parse_warnings: Vec::new(),
+        })
+    }
     
     /// 设置模板变量
     ///
@@ -168,28 +188,32 @@ impl FlexRenderer {
     /// let image = renderer.render().unwrap();
     /// ```
     pub fn render(&self) -> Result<RgbaImage> {
-        // 应用模板变量
-        let processed_node = self.apply_template_variables(&self.root_node)?;
-        
-        // 计算布局
-        let mut layout_engine = LayoutEngine::new();
-        let available_space = Size {
-            width: self.template_config.width,
-            height: self.template_config.height,
-        };
-        let computed_layout = layout_engine.compute_layout(&processed_node, available_space)?;
-        
+        let computed_layout = self.compute_layout_result()?;
+
         // 渲染到画布
         let canvas_size = Size::new(self.template_config.width, self.template_config.height);
         let mut canvas = Canvas::new(canvas_size, self.template_config.background, 1.0);
-        
+
         // 使用渲染器渲染布局
         let mut renderer = crate::render::renderer::Renderer::new()?;
         renderer.render(&computed_layout, &mut canvas)?;
-        
+
         Ok(canvas.to_image_clone())
     }
-    
+
+    /// 应用当前变量并计算布局，不渲染像素。`render`、`render_svg` 和 [`crate::server`]
+    /// 的布局查询模式都基于这一步的结果
+    pub fn compute_layout_result(&self) -> Result<layout::engine::LayoutResult> {
+        let processed_node = self.apply_template_variables(&self.root_node)?;
+
+        let mut layout_engine = LayoutEngine::new();
+        let available_space = Size {
+            width: self.template_config.width,
+            height: self.template_config.height,
+        };
+        layout_engine.compute_layout(&processed_node, available_space)
+    }
+
     /// 渲染模板并保存到文件
     ///
     /// # 参数
@@ -209,7 +233,55 @@ impl FlexRenderer {
         image.save(path)?;
         Ok(())
     }
-    
+
+    /// 渲染模板为 SVG 文档（矢量、分辨率无关、文字可选中），复用和 `render` 相同的布局结果
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use flex_layout_render::FlexRenderer;
+    ///
+    /// let renderer = FlexRenderer::from_yaml("...").unwrap();
+    /// let svg = renderer.render_svg().unwrap();
+    /// ```
+    pub fn render_svg(&self) -> Result<String> {
+        use crate::render::backend::DrawBackend;
+
+        let processed_node = self.apply_template_variables(&self.root_node)?;
+
+        let mut layout_engine = LayoutEngine::new();
+        let available_space = Size {
+            width: self.template_config.width,
+            height: self.template_config.height,
+        };
+        let computed_layout = layout_engine.compute_layout(&processed_node, available_space)?;
+
+        let svg_size = Size::new(self.template_config.width, self.template_config.height);
+        let mut backend = crate::render::svg_backend::SvgBackend::new(svg_size);
+
+        let mut renderer = crate::render::renderer::Renderer::new()?;
+        renderer.render_to_backend(&computed_layout, &mut backend)?;
+        backend.finish()?;
+
+        Ok(backend.into_svg())
+    }
+
+    /// 渲染模板为 SVG 并保存到文件
+    ///
+    /// # 示例
+    ///
+    /// ```rust,no_run
+    /// use flex_layout_render::FlexRenderer;
+    ///
+    /// let renderer = FlexRenderer::from_yaml("...").unwrap();
+    /// renderer.render_svg_to_file("output.svg").unwrap();
+    /// ```
+    pub fn render_svg_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let svg = self.render_svg()?;
+        std::fs::write(path, svg)?;
+        Ok(())
+    }
+
     /// 获取模板配置信息
     pub fn template_config(&self) -> &TemplateConfig {
         &self.template_config
@@ -234,6 +306,11 @@ impl FlexRenderer {
     pub fn validate_variables(&self) -> Result<Vec<String>> {
         self.template_processor.check_required_variables(&self.root_node, &self.variables)
     }
+
+    /// 解析模板时被回退为默认值的字段警告（宽松解析的副产物，不影响渲染结果）
+    pub fn parse_warnings(&self) -> &[String] {
+        &self.parse_warnings
+    }
 }
 
 #[cfg(test)]
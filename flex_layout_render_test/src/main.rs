@@ -18,7 +18,7 @@ fn main() {
                 .long("input")
                 .value_name("FILE")
                 .help("Input YAML template file")
-                .required(true),
+                .required_unless_present("serve"),
         )
         .arg(
             Arg::new("output")
@@ -26,7 +26,7 @@ fn main() {
                 .long("output")
                 .value_name("FILE")
                 .help("Output image file")
-                .required(true),
+                .required_unless_present("serve"),
         )
         .arg(
             Arg::new("variables")
@@ -55,8 +55,24 @@ fn main() {
                 .help("List all template variables")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("serve")
+                .long("serve")
+                .value_name("SOCKET_PATH")
+                .help("Run as a long-lived daemon accepting render jobs over a Unix domain socket instead of doing one render and exiting")
+                .required(false),
+        )
         .get_matches();
 
+    if let Some(socket_path) = matches.get_one::<String>("serve") {
+        println!("Listening on Unix domain socket '{}'...", socket_path);
+        if let Err(e) = flex_layout_render::server::serve_unix_socket(socket_path) {
+            eprintln!("Error running daemon: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
     let input_file = matches.get_one::<String>("input").unwrap();
     let output_file = matches.get_one::<String>("output").unwrap();
     
@@ -107,14 +123,24 @@ fn main() {
     
     // 验证模式
     if matches.get_flag("validate") {
+        let parse_warnings = renderer.parse_warnings();
+        if !parse_warnings.is_empty() {
+            println!("⚠ Fields skipped during parsing (fell back to defaults):");
+            for warning in parse_warnings {
+                println!("  - {}", warning);
+            }
+        }
+
         match renderer.validate_variables() {
             Ok(missing) => {
-                if missing.is_empty() {
+                if missing.is_empty() && parse_warnings.is_empty() {
                     println!("✓ Template validation passed. All variables are set.");
                 } else {
-                    println!("⚠ Missing variables:");
-                    for var in missing {
-                        println!("  - {}", var);
+                    if !missing.is_empty() {
+                        println!("⚠ Missing variables:");
+                        for var in missing {
+                            println!("  - {}", var);
+                        }
                     }
                     process::exit(1);
                 }
@@ -4,7 +4,9 @@
 
 pub mod yaml_parser;
 pub mod template;
+pub mod stylesheet;
 
 // 重新导出主要类型
 pub use yaml_parser::{YamlParser, TemplateConfig};
-pub use template::TemplateProcessor;
\ No newline at end of file
+pub use template::TemplateProcessor;
+pub use stylesheet::{StylesheetParser, StylesheetRule, apply_stylesheet};
\ No newline at end of file
@@ -0,0 +1,515 @@
+//! 小型 CSS/Less 风格样式表解析
+//!
+//! 把一段形如 `.card { padding: 8px 12px; background: #fff; }` 的文本解析成
+//! 选择器 -> [`StyleRefinement`] 的有序列表，再由 [`apply_stylesheet`] 遍历布局树，
+//! 按 `id`/`class`（`id` 选择器优先于 `class`，同一优先级内后声明的规则覆盖先声明的）
+//! 把解析结果应用到每个节点自己的样式上。设计目标是让模板作者能写一小段样式文本，
+//! 而不必手写 JSON/YAML 里的每一个样式字段。
+
+use crate::error::{FlexRenderError, Result};
+use crate::layout::node::*;
+use crate::types::{Background, Color};
+use taffy::style::{Display, FlexDirection, LengthPercentage, LengthPercentageAuto};
+
+/// 一条解析出的规则：选择器原文（`.card` / `#header`）及其对应的样式精化
+pub type StylesheetRule = (String, StyleRefinement);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Ident,
+    Colon,
+    Semicolon,
+    BraceOpen,
+    BraceClose,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    text: String,
+    line: usize,
+    column: usize,
+}
+
+/// 把样式表源码切分成 token 流，记录每个 token 的行列位置供报错使用
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>> {
+        let mut tokens = Vec::new();
+
+        loop {
+            // 跳过空白与 `/* ... */` 注释
+            while let Some(&c) = self.chars.peek() {
+                if c.is_whitespace() {
+                    self.advance();
+                } else if c == '/' {
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    if lookahead.peek() == Some(&'*') {
+                        self.advance();
+                        self.advance();
+                        loop {
+                            match self.advance() {
+                                None => break,
+                                Some('*') if self.chars.peek() == Some(&'/') => {
+                                    self.advance();
+                                    break;
+                                }
+                                _ => {}
+                            }
+                        }
+                    } else {
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+
+            let (line, column) = (self.line, self.column);
+            let Some(&c) = self.chars.peek() else {
+                break;
+            };
+
+            match c {
+                ':' => {
+                    self.advance();
+                    tokens.push(Token { kind: TokenKind::Colon, text: ":".to_string(), line, column });
+                }
+                ';' => {
+                    self.advance();
+                    tokens.push(Token { kind: TokenKind::Semicolon, text: ";".to_string(), line, column });
+                }
+                '{' => {
+                    self.advance();
+                    tokens.push(Token { kind: TokenKind::BraceOpen, text: "{".to_string(), line, column });
+                }
+                '}' => {
+                    self.advance();
+                    tokens.push(Token { kind: TokenKind::BraceClose, text: "}".to_string(), line, column });
+                }
+                _ => {
+                    let mut text = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_whitespace() || matches!(c, ':' | ';' | '{' | '}') {
+                            break;
+                        }
+                        text.push(c);
+                        self.advance();
+                    }
+                    if text.is_empty() {
+                        return Err(FlexRenderError::parse_error(
+                            format!("无法识别的字符 '{}'", c),
+                            line,
+                            column,
+                        ));
+                    }
+                    tokens.push(Token { kind: TokenKind::Ident, text, line, column });
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+/// 样式表解析器：把 token 流解析成有序的选择器规则列表
+pub struct StylesheetParser;
+
+impl StylesheetParser {
+    /// 解析一段样式表源码，返回按源码中出现顺序排列的规则列表
+    pub fn parse(source: &str) -> Result<Vec<StylesheetRule>> {
+        let tokens = Tokenizer::new(source).tokenize()?;
+        let mut rules = Vec::new();
+        let mut pos = 0;
+
+        while pos < tokens.len() {
+            // 选择器可以由多个 ident token 组成（如 `.card .title`），原样拼接保留空格
+            let mut selector_parts = Vec::new();
+            while pos < tokens.len() && tokens[pos].kind != TokenKind::BraceOpen {
+                if tokens[pos].kind != TokenKind::Ident {
+                    return Err(FlexRenderError::parse_error(
+                        format!("选择器中出现意外的 '{}'", tokens[pos].text),
+                        tokens[pos].line,
+                        tokens[pos].column,
+                    ));
+                }
+                selector_parts.push(tokens[pos].text.clone());
+                pos += 1;
+            }
+            if pos >= tokens.len() {
+                break;
+            }
+            let selector = selector_parts.join(" ");
+            pos += 1; // 跳过 '{'
+
+            let mut refinement = StyleRefinement::default();
+            while pos < tokens.len() && tokens[pos].kind != TokenKind::BraceClose {
+                let prop_token = &tokens[pos];
+                if prop_token.kind != TokenKind::Ident {
+                    return Err(FlexRenderError::parse_error(
+                        format!("期望属性名，却遇到 '{}'", prop_token.text),
+                        prop_token.line,
+                        prop_token.column,
+                    ));
+                }
+                let property = prop_token.text.clone();
+                pos += 1;
+
+                if pos >= tokens.len() || tokens[pos].kind != TokenKind::Colon {
+                    return Err(FlexRenderError::parse_error(
+                        format!("属性 '{}' 后缺少 ':'", property),
+                        prop_token.line,
+                        prop_token.column,
+                    ));
+                }
+                pos += 1; // 跳过 ':'
+
+                let mut values = Vec::new();
+                while pos < tokens.len()
+                    && tokens[pos].kind != TokenKind::Semicolon
+                    && tokens[pos].kind != TokenKind::BraceClose
+                {
+                    values.push(tokens[pos].clone());
+                    pos += 1;
+                }
+                if values.is_empty() {
+                    return Err(FlexRenderError::parse_error(
+                        format!("属性 '{}' 缺少取值", property),
+                        prop_token.line,
+                        prop_token.column,
+                    ));
+                }
+
+                apply_declaration(&mut refinement, &property, &values)?;
+
+                if pos < tokens.len() && tokens[pos].kind == TokenKind::Semicolon {
+                    pos += 1;
+                }
+            }
+            if pos >= tokens.len() {
+                return Err(FlexRenderError::parse_error(
+                    format!("选择器 '{}' 的规则缺少闭合的 '}}'", selector),
+                    tokens.last().map(|t| t.line).unwrap_or(0),
+                    tokens.last().map(|t| t.column).unwrap_or(0),
+                ));
+            }
+            pos += 1; // 跳过 '}'
+
+            rules.push((selector, refinement));
+        }
+
+        Ok(rules)
+    }
+}
+
+/// 把一条声明（属性名 + 取值 token 序列）应用到正在构建的 [`StyleRefinement`] 上
+fn apply_declaration(refinement: &mut StyleRefinement, property: &str, values: &[Token]) -> Result<()> {
+    match property {
+        "display" => {
+            refinement.display = Some(match values[0].text.as_str() {
+                "flex" => Display::Flex,
+                "grid" => Display::Grid,
+                "none" => Display::None,
+                other => {
+                    return Err(unknown_value(&values[0], "display", other));
+                }
+            });
+        }
+        "flex-direction" => {
+            refinement.flex_direction = Some(match values[0].text.as_str() {
+                "row" => FlexDirection::Row,
+                "column" => FlexDirection::Column,
+                "row-reverse" => FlexDirection::RowReverse,
+                "column-reverse" => FlexDirection::ColumnReverse,
+                other => {
+                    return Err(unknown_value(&values[0], "flex-direction", other));
+                }
+            });
+        }
+        "padding" => {
+            refinement.padding = parse_rect_shorthand(values, parse_length_percentage)?;
+        }
+        "margin" => {
+            refinement.margin = parse_rect_shorthand(values, parse_length_percentage_auto)?;
+        }
+        "background" => {
+            refinement.background = Some(Background::Color(Color::from_hex(&values[0].text)?));
+        }
+        "color" => {
+            refinement.color = Some(Color::from_hex(&values[0].text)?);
+        }
+        "font-size" => {
+            refinement.font_size = Some(parse_px(&values[0])?);
+        }
+        "border-radius" => {
+            refinement.border_radius = Some(parse_px(&values[0])?);
+        }
+        "opacity" => {
+            refinement.opacity = Some(values[0].text.parse::<f32>().map_err(|_| {
+                FlexRenderError::parse_error(
+                    format!("无效的 opacity 取值: {}", values[0].text),
+                    values[0].line,
+                    values[0].column,
+                )
+            })?);
+        }
+        "text-align" => {
+            use crate::types::TextAlign;
+            refinement.text_align = Some(match values[0].text.as_str() {
+                "left" => TextAlign::Left,
+                "center" => TextAlign::Center,
+                "right" => TextAlign::Right,
+                "justify" => TextAlign::Justify,
+                other => {
+                    return Err(unknown_value(&values[0], "text-align", other));
+                }
+            });
+        }
+        other => {
+            return Err(FlexRenderError::parse_error(
+                format!("未知的样式属性: {}", other),
+                values[0].line,
+                values[0].column,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn unknown_value(token: &Token, property: &str, value: &str) -> FlexRenderError {
+    FlexRenderError::parse_error(
+        format!("属性 '{}' 不支持取值 '{}'", property, value),
+        token.line,
+        token.column,
+    )
+}
+
+/// 解析形如 `8px` 的长度并以点数形式返回，不接受 `%`/`auto`（用于 font-size/border-radius）
+fn parse_px(token: &Token) -> Result<f32> {
+    token
+        .text
+        .strip_suffix("px")
+        .ok_or_else(|| FlexRenderError::parse_error(
+            format!("期望形如 '12px' 的取值，实际为 '{}'", token.text),
+            token.line,
+            token.column,
+        ))?
+        .parse::<f32>()
+        .map_err(|_| FlexRenderError::parse_error(
+            format!("无效的数值: {}", token.text),
+            token.line,
+            token.column,
+        ))
+}
+
+/// 解析 `padding`/内边距一类的长度取值：`px` 映射到 `LengthPercentage::Points`，
+/// `%` 映射到 `LengthPercentage::Percent`
+fn parse_length_percentage(token: &Token) -> Result<LengthPercentage> {
+    if let Some(digits) = token.text.strip_suffix("px") {
+        let value = digits.parse::<f32>().map_err(|_| invalid_length(token))?;
+        Ok(LengthPercentage::Points(value))
+    } else if let Some(digits) = token.text.strip_suffix('%') {
+        let value = digits.parse::<f32>().map_err(|_| invalid_length(token))?;
+        Ok(LengthPercentage::Percent(value / 100.0))
+    } else {
+        Err(invalid_length(token))
+    }
+}
+
+/// 同 [`parse_length_percentage`]，额外支持 `auto`（用于 `margin`）
+fn parse_length_percentage_auto(token: &Token) -> Result<LengthPercentageAuto> {
+    if token.text == "auto" {
+        return Ok(LengthPercentageAuto::Auto);
+    }
+    if let Some(digits) = token.text.strip_suffix("px") {
+        let value = digits.parse::<f32>().map_err(|_| invalid_length(token))?;
+        Ok(LengthPercentageAuto::Points(value))
+    } else if let Some(digits) = token.text.strip_suffix('%') {
+        let value = digits.parse::<f32>().map_err(|_| invalid_length(token))?;
+        Ok(LengthPercentageAuto::Percent(value / 100.0))
+    } else {
+        Err(invalid_length(token))
+    }
+}
+
+fn invalid_length(token: &Token) -> FlexRenderError {
+    FlexRenderError::parse_error(
+        format!("无效的长度单位: '{}'（支持 px/%/auto）", token.text),
+        token.line,
+        token.column,
+    )
+}
+
+/// 按 CSS 的 1–4 值简写规则展开 `padding`/`margin`：
+/// 1 个值四边通用；2 个值为（上下、左右）；3 个值为（上、左右、下）；4 个值为（上、右、下、左）
+fn parse_rect_shorthand<T: Copy>(
+    values: &[Token],
+    parse_one: impl Fn(&Token) -> Result<T>,
+) -> Result<RectRefinement<T>> {
+    let parsed = values
+        .iter()
+        .map(&parse_one)
+        .collect::<Result<Vec<_>>>()?;
+
+    let (top, right, bottom, left) = match parsed.len() {
+        1 => (parsed[0], parsed[0], parsed[0], parsed[0]),
+        2 => (parsed[0], parsed[1], parsed[0], parsed[1]),
+        3 => (parsed[0], parsed[1], parsed[2], parsed[1]),
+        4 => (parsed[0], parsed[1], parsed[2], parsed[3]),
+        _ => {
+            return Err(FlexRenderError::parse_error(
+                "padding/margin 简写最多支持 4 个取值",
+                values[0].line,
+                values[0].column,
+            ));
+        }
+    };
+
+    Ok(RectRefinement {
+        left: Some(left),
+        right: Some(right),
+        top: Some(top),
+        bottom: Some(bottom),
+    })
+}
+
+/// 某个节点自身声明的 `id`/`class`
+fn node_id_class(node: &LayoutNode) -> (Option<&str>, Option<&str>) {
+    match node {
+        LayoutNode::Container { style, .. } => (style.id.as_deref(), style.class.as_deref()),
+        LayoutNode::Text { style, .. } => (style.id.as_deref(), style.class.as_deref()),
+        LayoutNode::Image { style, .. } => (style.id.as_deref(), style.class.as_deref()),
+        LayoutNode::Spacer { .. } => (None, None),
+    }
+}
+
+/// 解析出节点对 `rules` 最终生效的样式：class 选择器按源码顺序先叠加，
+/// id 选择器再叠加在其上，因此 id 的优先级总是高于 class；同一层级内后声明的规则覆盖先声明的
+fn resolve_node_style(rules: &[StylesheetRule], node: &LayoutNode) -> StyleRefinement {
+    let (id, class) = node_id_class(node);
+
+    let mut cascade = Vec::new();
+    if let Some(class) = class {
+        let class_selector = format!(".{}", class);
+        cascade.extend(
+            rules
+                .iter()
+                .filter(|(selector, _)| selector == &class_selector)
+                .map(|(_, refinement)| refinement.clone()),
+        );
+    }
+    if let Some(id) = id {
+        let id_selector = format!("#{}", id);
+        cascade.extend(
+            rules
+                .iter()
+                .filter(|(selector, _)| selector == &id_selector)
+                .map(|(_, refinement)| refinement.clone()),
+        );
+    }
+
+    StyleRefinement::resolve_cascade(&cascade)
+}
+
+/// 把 `refinement` 中声明了的字段应用到具体的 `ContainerStyle` 上，未声明的字段保持不变
+fn apply_to_container_style(style: &mut ContainerStyle, refinement: &StyleRefinement) {
+    if let Some(display) = refinement.display {
+        style.display = display;
+    }
+    if let Some(flex_direction) = refinement.flex_direction {
+        style.flex_direction = flex_direction;
+    }
+    if let Some(background) = &refinement.background {
+        style.background = Some(background.clone());
+    }
+    if let Some(opacity) = refinement.opacity {
+        style.opacity = opacity;
+    }
+    if let Some(border_radius) = refinement.border_radius {
+        style.border_radius = border_radius;
+    }
+    style.padding = refinement.padding.apply_to(style.padding);
+    style.margin = refinement.margin.apply_to(style.margin);
+}
+
+fn apply_to_text_style(style: &mut TextStyle, refinement: &StyleRefinement) {
+    if let Some(color) = refinement.color {
+        style.color = color;
+    }
+    if let Some(font_size) = refinement.font_size {
+        style.font_size = font_size;
+    }
+    if let Some(text_align) = refinement.text_align {
+        style.text_align = text_align;
+    }
+    style.padding = refinement.padding.apply_to(style.padding);
+    style.margin = refinement.margin.apply_to(style.margin);
+}
+
+fn apply_to_image_style(style: &mut ImageStyle, refinement: &StyleRefinement) {
+    if let Some(opacity) = refinement.opacity {
+        style.opacity = opacity;
+    }
+    if let Some(border_radius) = refinement.border_radius {
+        style.border_radius = border_radius;
+    }
+    style.padding = refinement.padding.apply_to(style.padding);
+    style.margin = refinement.margin.apply_to(style.margin);
+}
+
+/// 把解析出的样式表规则应用到整棵布局树上，返回一棵样式已解析完毕的新树。
+/// 每个节点按自己的 `id`/`class` 独立匹配规则，不做级联继承（继承由
+/// [`resolve_text_styles`](crate::layout::node::resolve_text_styles) 另行负责）
+pub fn apply_stylesheet(root: &LayoutNode, rules: &[StylesheetRule]) -> LayoutNode {
+    let refinement = resolve_node_style(rules, root);
+
+    match root {
+        LayoutNode::Container { style, children } => {
+            let mut style = style.clone();
+            apply_to_container_style(&mut style, &refinement);
+            let children = children
+                .iter()
+                .map(|child| apply_stylesheet(child, rules))
+                .collect();
+            LayoutNode::Container { style, children }
+        }
+        LayoutNode::Text { content, style } => {
+            let mut style = style.clone();
+            apply_to_text_style(&mut style, &refinement);
+            LayoutNode::Text { content: content.clone(), style }
+        }
+        LayoutNode::Image { src, style } => {
+            let mut style = style.clone();
+            apply_to_image_style(&mut style, &refinement);
+            LayoutNode::Image { src: src.clone(), style }
+        }
+        // Spacer 没有 id/class，样式表规则对它无从匹配，原样保留
+        LayoutNode::Spacer { .. } => root.clone(),
+    }
+}
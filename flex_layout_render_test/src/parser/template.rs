@@ -1,10 +1,11 @@
 //! 模板变量处理模块
 //!
-//! 支持 {{variable}} 语法的变量替换功能。
+//! 支持 {{variable}} 语法的变量替换功能，以及数组变量驱动的节点重复（`ContainerStyle::repeat`）
+//! 和条件渲染（`when`）。
 
 use crate::error::{FlexRenderError, Result};
 use crate::types::TemplateVariables;
-use crate::layout::node::LayoutNode;
+use crate::layout::node::{ContainerStyle, LayoutNode, RepeatBinding};
 use handlebars::Handlebars;
 use regex::Regex;
 use serde_json::Value;
@@ -20,7 +21,8 @@ impl TemplateProcessor {
     /// 创建新的模板处理器
     pub fn new() -> Result<Self> {
         let handlebars = Handlebars::new();
-        let simple_regex = Regex::new(r"\{\{\s*([a-zA-Z_][a-zA-Z0-9_]*)\s*\}\}")
+        // 变量名允许用 `.` 分隔（如 `item.name`），用于引用 repeat 迭代中绑定的局部变量字段
+        let simple_regex = Regex::new(r"\{\{\s*([a-zA-Z_][a-zA-Z0-9_.]*)\s*\}\}")
             .map_err(|e| FlexRenderError::RenderError(format!("正则表达式编译失败: {}", e)))?;
         
         Ok(Self {
@@ -29,7 +31,11 @@ impl TemplateProcessor {
         })
     }
     
-    /// 应用模板变量到布局节点
+    /// 应用模板变量到布局节点：替换文本/图片中的 `{{variable}}`，按 `repeat` 展开数组绑定的
+    /// 重复子树，并丢弃 `when` 绑定变量为假值的节点。
+    ///
+    /// 根节点没有父节点可供丢弃或展开，因此根节点自身的 `when`/`repeat` 总是被忽略——
+    /// 这两个标记只在作为某个容器的子节点时才生效。
     pub fn apply_variables(
         &self,
         node: &LayoutNode,
@@ -39,9 +45,9 @@ impl TemplateProcessor {
             LayoutNode::Container { style, children } => {
                 let mut processed_children = Vec::new();
                 for child in children {
-                    processed_children.push(self.apply_variables(child, variables)?);
+                    processed_children.extend(self.expand_node(child, variables)?);
                 }
-                
+
                 Ok(LayoutNode::Container {
                     style: style.clone(),
                     children: processed_children,
@@ -49,7 +55,7 @@ impl TemplateProcessor {
             }
             LayoutNode::Text { content, style } => {
                 let processed_content = self.replace_variables(content, variables)?;
-                
+
                 Ok(LayoutNode::Text {
                     content: processed_content,
                     style: style.clone(),
@@ -57,15 +63,114 @@ impl TemplateProcessor {
             }
             LayoutNode::Image { src, style } => {
                 let processed_src = self.replace_variables(src, variables)?;
-                
+
                 Ok(LayoutNode::Image {
                     src: processed_src,
                     style: style.clone(),
                 })
             }
+            // Spacer 没有文本/路径内容需要替换模板变量，原样保留
+            LayoutNode::Spacer { .. } => Ok(node.clone()),
         }
     }
-    
+
+    /// 展开一个子节点：先判断 `when` 是否为假（为假则返回空列表，整棵子树被丢弃），
+    /// 再判断容器是否带 `repeat` 标记（带则按数组元素展开出 N 份副本），否则按普通节点递归处理
+    fn expand_node(&self, node: &LayoutNode, variables: &TemplateVariables) -> Result<Vec<LayoutNode>> {
+        if !self.when_passes(node, variables) {
+            return Ok(Vec::new());
+        }
+
+        match node {
+            LayoutNode::Container { style, children } => {
+                if let Some(repeat) = &style.repeat {
+                    return self.expand_repeat(repeat, children, style, variables);
+                }
+
+                let mut processed_children = Vec::new();
+                for child in children {
+                    processed_children.extend(self.expand_node(child, variables)?);
+                }
+                Ok(vec![LayoutNode::Container {
+                    style: style.clone(),
+                    children: processed_children,
+                }])
+            }
+            LayoutNode::Text { content, style } => {
+                let processed_content = self.replace_variables(content, variables)?;
+                Ok(vec![LayoutNode::Text {
+                    content: processed_content,
+                    style: style.clone(),
+                }])
+            }
+            LayoutNode::Image { src, style } => {
+                let processed_src = self.replace_variables(src, variables)?;
+                Ok(vec![LayoutNode::Image {
+                    src: processed_src,
+                    style: style.clone(),
+                }])
+            }
+            LayoutNode::Spacer { .. } => Ok(vec![node.clone()]),
+        }
+    }
+
+    /// 按 `repeat.source` 指向的数组变量展开 `children` 一次每个元素，元素本身绑定为
+    /// `repeat.item` 局部变量（与父作用域合并），供子树内 `{{item.field}}` 引用。
+    /// 缺失或为 `null` 的数组视为空数组（展开为零个子节点），非数组值则报错。
+    fn expand_repeat(
+        &self,
+        repeat: &RepeatBinding,
+        children: &[LayoutNode],
+        style: &ContainerStyle,
+        variables: &TemplateVariables,
+    ) -> Result<Vec<LayoutNode>> {
+        let items: &[Value] = match variables.get(&repeat.source) {
+            None | Some(Value::Null) => &[],
+            Some(Value::Array(items)) => items,
+            Some(other) => {
+                return Err(FlexRenderError::render_error(format!(
+                    "重复器变量 '{}' 必须是数组，实际为: {}",
+                    repeat.source, other
+                )));
+            }
+        };
+
+        let mut expanded_children = Vec::new();
+        for item in items {
+            let mut scope = variables.clone();
+            scope.insert(repeat.item.clone(), item.clone());
+            for child in children {
+                expanded_children.extend(self.expand_node(child, &scope)?);
+            }
+        }
+
+        // 重复器容器本身保留，只是替换为展开后的子节点；清掉 repeat 标记避免重复展开
+        let mut expanded_style = style.clone();
+        expanded_style.repeat = None;
+        Ok(vec![LayoutNode::Container {
+            style: expanded_style,
+            children: expanded_children,
+        }])
+    }
+
+    /// 判断节点的 `when` 绑定是否通过：未设置 `when` 视为通过；变量缺失或取假值视为不通过
+    fn when_passes(&self, node: &LayoutNode, variables: &TemplateVariables) -> bool {
+        let when = match node {
+            LayoutNode::Container { style, .. } => &style.when,
+            LayoutNode::Text { style, .. } => &style.when,
+            LayoutNode::Image { style, .. } => &style.when,
+            // Spacer 没有 `when` 字段，视为未声明，总是通过
+            LayoutNode::Spacer { .. } => &None,
+        };
+
+        match when {
+            None => true,
+            Some(var_name) => lookup_variable(variables, var_name)
+                .map(is_truthy)
+                .unwrap_or(false),
+        }
+    }
+
     /// 替换字符串中的模板变量
     fn replace_variables(
         &self,
@@ -74,12 +179,12 @@ impl TemplateProcessor {
     ) -> Result<String> {
         // 首先检查是否有缺失的变量
         let mut has_missing_vars = false;
-        
+
         // 使用简单的正则表达式替换
         let result = self.simple_regex.replace_all(template, |caps: &regex::Captures| {
             let var_name = &caps[1];
-            
-            match variables.get(var_name) {
+
+            match lookup_variable(variables, var_name) {
                 Some(value) => self.value_to_string(value),
                 None => {
                     has_missing_vars = true;
@@ -156,7 +261,15 @@ impl TemplateProcessor {
         missing: &mut Vec<String>,
     ) -> Result<()> {
         match node {
-            LayoutNode::Container { children, .. } => {
+            LayoutNode::Container { style, children } => {
+                if let Some(repeat) = &style.repeat {
+                    if lookup_variable(variables, &repeat.source).is_none()
+                        && !missing.contains(&repeat.source)
+                    {
+                        missing.push(repeat.source.clone());
+                    }
+                    // 数组元素字段（`item.field`）只有在实际渲染时才知道，不在此处校验
+                }
                 for child in children {
                     self.collect_missing_variables(child, variables, missing)?;
                 }
@@ -164,7 +277,7 @@ impl TemplateProcessor {
             LayoutNode::Text { content, .. } => {
                 let required_vars = self.validate_template(content)?;
                 for var in required_vars {
-                    if !variables.contains_key(&var) && !missing.contains(&var) {
+                    if lookup_variable(variables, &var).is_none() && !missing.contains(&var) {
                         missing.push(var);
                     }
                 }
@@ -172,11 +285,12 @@ impl TemplateProcessor {
             LayoutNode::Image { src, .. } => {
                 let required_vars = self.validate_template(src)?;
                 for var in required_vars {
-                    if !variables.contains_key(&var) && !missing.contains(&var) {
+                    if lookup_variable(variables, &var).is_none() && !missing.contains(&var) {
                         missing.push(var);
                     }
                 }
             }
+            LayoutNode::Spacer { .. } => {}
         }
         Ok(())
     }
@@ -188,6 +302,28 @@ impl Default for TemplateProcessor {
     }
 }
 
+/// 按 `.` 分隔路径查找变量，支持 `item.name` 这样引用 repeat 迭代中绑定的局部变量字段
+fn lookup_variable<'a>(variables: &'a TemplateVariables, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = variables.get(parts.next()?)?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+/// JS 风格的真值判断，用于 `when` 条件渲染
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Null => false,
+        Value::Number(n) => n.as_f64().map_or(true, |f| f != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,4 +408,144 @@ mod tests {
             panic!("Expected container node");
         }
     }
+
+    #[test]
+    fn test_repeat_expands_array_into_children() {
+        let processor = TemplateProcessor::new().unwrap();
+        let mut variables = HashMap::new();
+        variables.insert("products".to_string(), json!([
+            {"name": "Widget"},
+            {"name": "Gadget"},
+        ]));
+
+        let repeater = LayoutNode::Container {
+            style: ContainerStyle {
+                repeat: Some(RepeatBinding {
+                    source: "products".to_string(),
+                    item: "item".to_string(),
+                }),
+                ..ContainerStyle::default()
+            },
+            children: vec![LayoutNode::Text {
+                content: "{{item.name}}".to_string(),
+                style: TextStyle::default(),
+            }],
+        };
+
+        let root = LayoutNode::Container {
+            style: ContainerStyle::default(),
+            children: vec![repeater],
+        };
+
+        let result = processor.apply_variables(&root, &variables).unwrap();
+
+        if let LayoutNode::Container { children, .. } = result {
+            assert_eq!(children.len(), 1);
+            if let LayoutNode::Container { children: items, .. } = &children[0] {
+                assert_eq!(items.len(), 2);
+                if let LayoutNode::Text { content, .. } = &items[0] {
+                    assert_eq!(content, "Widget");
+                } else {
+                    panic!("Expected text node");
+                }
+                if let LayoutNode::Text { content, .. } = &items[1] {
+                    assert_eq!(content, "Gadget");
+                } else {
+                    panic!("Expected text node");
+                }
+            } else {
+                panic!("Expected expanded repeater container");
+            }
+        } else {
+            panic!("Expected container node");
+        }
+    }
+
+    #[test]
+    fn test_repeat_missing_array_yields_no_children() {
+        let processor = TemplateProcessor::new().unwrap();
+        let variables = HashMap::new();
+
+        let repeater = LayoutNode::Container {
+            style: ContainerStyle {
+                repeat: Some(RepeatBinding {
+                    source: "missing".to_string(),
+                    item: "item".to_string(),
+                }),
+                ..ContainerStyle::default()
+            },
+            children: vec![LayoutNode::Text {
+                content: "{{item.name}}".to_string(),
+                style: TextStyle::default(),
+            }],
+        };
+
+        let root = LayoutNode::Container {
+            style: ContainerStyle::default(),
+            children: vec![repeater],
+        };
+
+        let result = processor.apply_variables(&root, &variables).unwrap();
+
+        if let LayoutNode::Container { children, .. } = result {
+            if let LayoutNode::Container { children: items, .. } = &children[0] {
+                assert!(items.is_empty());
+            } else {
+                panic!("Expected expanded repeater container");
+            }
+        } else {
+            panic!("Expected container node");
+        }
+    }
+
+    #[test]
+    fn test_repeat_non_array_value_is_error() {
+        let processor = TemplateProcessor::new().unwrap();
+        let mut variables = HashMap::new();
+        variables.insert("products".to_string(), json!("not an array"));
+
+        let repeater = LayoutNode::Container {
+            style: ContainerStyle {
+                repeat: Some(RepeatBinding {
+                    source: "products".to_string(),
+                    item: "item".to_string(),
+                }),
+                ..ContainerStyle::default()
+            },
+            children: vec![],
+        };
+
+        let root = LayoutNode::Container {
+            style: ContainerStyle::default(),
+            children: vec![repeater],
+        };
+
+        assert!(processor.apply_variables(&root, &variables).is_err());
+    }
+
+    #[test]
+    fn test_when_false_drops_node() {
+        let processor = TemplateProcessor::new().unwrap();
+        let mut variables = HashMap::new();
+        variables.insert("show_banner".to_string(), json!(false));
+
+        let root = LayoutNode::Container {
+            style: ContainerStyle::default(),
+            children: vec![LayoutNode::Text {
+                content: "Banner".to_string(),
+                style: TextStyle {
+                    when: Some("show_banner".to_string()),
+                    ..TextStyle::default()
+                },
+            }],
+        };
+
+        let result = processor.apply_variables(&root, &variables).unwrap();
+
+        if let LayoutNode::Container { children, .. } = result {
+            assert!(children.is_empty());
+        } else {
+            panic!("Expected container node");
+        }
+    }
 }
\ No newline at end of file
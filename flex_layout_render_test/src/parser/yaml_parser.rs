@@ -8,7 +8,8 @@ use crate::error::*;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 use taffy::style::{
-    Dimension, Display, FlexDirection, JustifyContent, AlignItems
+    Dimension, Display, FlexDirection, JustifyContent, AlignItems, AlignContent, FlexWrap,
+    LengthPercentage
 };
 // use std::collections::HashMap; // 暂时未使用
 
@@ -39,20 +40,45 @@ impl Default for TemplateConfig {
     }
 }
 
+/// 记录一次宽松解析过程中被忽略/回退为默认值的字段，`path` 形如 `container.children[0].font_weight`
+fn warn_unrecognized(warnings: &mut Vec<String>, path: &str, raw: &str) {
+    let message = format!("字段 `{}` 的值 `{}` 无法识别，已回退为默认值", path, raw);
+    log::warn!("{}", message);
+    warnings.push(message);
+}
+
 /// YAML 解析器
 pub struct YamlParser;
 
 impl YamlParser {
-    /// 解析 YAML 内容
+    /// 解析 YAML 内容。内部复用 [`Self::parse_lenient`]，遇到无法识别的字段取值时
+    /// 同样回退为默认值而不会中止，只是这里把收集到的 `warnings` 丢弃；需要拿到
+    /// 这些提示信息（例如 `--validate`）时请直接调用 [`Self::parse_lenient`]
     pub fn parse(yaml_content: &str) -> Result<(TemplateConfig, LayoutNode)> {
+        let mut warnings = Vec::new();
+        let (config, node) = Self::parse_lenient(yaml_content, &mut warnings)?;
+        Ok((config, node))
+    }
+
+    /// 宽松解析 YAML 内容：无法识别的字段取值不会中止解析，而是回退为默认值并通过
+    /// `warnings` 返回累积的提示信息（同时也会通过 `log::warn!` 记录）
+    pub fn parse_lenient(
+        yaml_content: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<(TemplateConfig, LayoutNode)> {
         let value: Value = serde_yaml::from_str(yaml_content)?;
-        
+
         let template_config = Self::parse_template_config(&value)?;
-        let root_node = Self::parse_node(&value["container"])?;
-        
+        let root_node = Self::parse_node(
+            &value["container"],
+            &StyleRefinement::default(),
+            "container",
+            warnings,
+        )?;
+
         Ok((template_config, root_node))
     }
-    
+
     /// 解析模板配置
     fn parse_template_config(value: &Value) -> Result<TemplateConfig> {
         let template = &value["template"];
@@ -89,8 +115,13 @@ impl YamlParser {
         })
     }
     
-    /// 解析布局节点
-    fn parse_node(value: &Value) -> Result<LayoutNode> {
+    /// 解析布局节点，`inherited` 是从祖先节点逐层叠加下来的级联样式，`path` 用于在警告信息中定位节点
+    fn parse_node(
+        value: &Value,
+        inherited: &StyleRefinement,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<LayoutNode> {
         if value.is_null() {
             return Err(FlexRenderError::parse_error(
                 "节点值为空",
@@ -98,13 +129,18 @@ impl YamlParser {
                 0,
             ));
         }
-        
+
+        // 把本节点自己声明的级联字段叠加到继承值上，子节点再继续基于这份结果继承
+        let mut resolved = inherited.clone();
+        resolved.refine(&Self::parse_own_refinement(value, path, warnings)?);
+
         let node_type = value["type"].as_str().unwrap_or("container");
-        
+
         match node_type {
-            "container" => Self::parse_container(value),
-            "text" => Self::parse_text(value),
-            "image" => Self::parse_image(value),
+            "container" => Self::parse_container(value, &resolved, path, warnings),
+            "text" => Self::parse_text(value, &resolved, path, warnings),
+            "image" => Self::parse_image(value, &resolved, path, warnings),
+            "spacer" => Self::parse_spacer(value),
             _ => Err(FlexRenderError::parse_error(
                 format!("未知的节点类型: {}", node_type),
                 0,
@@ -112,23 +148,92 @@ impl YamlParser {
             )),
         }
     }
-    
+
+    /// 解析本节点自己声明的级联样式字段（不含继承），供 [`parse_node`] 叠加到继承链上
+    fn parse_own_refinement(
+        value: &Value,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<StyleRefinement> {
+        let mut refinement = StyleRefinement::default();
+
+        if let Some(color_str) = value["color"].as_str() {
+            refinement.color = Some(Color::from_hex(color_str)?);
+        }
+
+        if let Some(font_size) = value["font_size"].as_f64() {
+            refinement.font_size = Some(font_size as f32);
+        }
+
+        if let Some(opacity) = value["opacity"].as_f64() {
+            refinement.opacity = Some(opacity as f32);
+        }
+
+        if let Some(text_align) = value["text_align"].as_str() {
+            refinement.text_align = Some(match text_align.to_lowercase().as_str() {
+                "left" => TextAlign::Left,
+                "center" => TextAlign::Center,
+                "right" => TextAlign::Right,
+                "justify" => TextAlign::Justify,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.text_align", path), text_align);
+                    TextAlign::Left
+                }
+            });
+        }
+
+        if let Some(padding) = value["padding"].as_f64() {
+            let padding = LengthPercentage::Points(padding as f32);
+            refinement.padding = RectRefinement {
+                left: Some(padding),
+                right: Some(padding),
+                top: Some(padding),
+                bottom: Some(padding),
+            };
+        }
+
+        if let Some(bg_str) = value["background"].as_str() {
+            refinement.background = Some(Background::Color(Color::from_hex(bg_str)?));
+        }
+
+        Ok(refinement)
+    }
+
     /// 解析容器节点
-    fn parse_container(value: &Value) -> Result<LayoutNode> {
-        let style = Self::parse_container_style(value)?;
+    fn parse_container(
+        value: &Value,
+        inherited: &StyleRefinement,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<LayoutNode> {
+        let mut style = Self::parse_container_style(value, path, warnings)?;
+        if let Some(background) = &inherited.background {
+            style.background = Some(background.clone());
+        }
+        if let Some(opacity) = inherited.opacity {
+            style.opacity = opacity;
+        }
+        style.padding = inherited.padding.apply_to(style.padding);
+
         let mut children = Vec::new();
-        
+
         if let Some(children_value) = value["children"].as_sequence() {
-            for child_value in children_value {
-                children.push(Self::parse_node(child_value)?);
+            for (index, child_value) in children_value.iter().enumerate() {
+                let child_path = format!("{}.children[{}]", path, index);
+                children.push(Self::parse_node(child_value, inherited, &child_path, warnings)?);
             }
         }
-        
+
         Ok(LayoutNode::Container { style, children })
     }
-    
+
     /// 解析文本节点
-    fn parse_text(value: &Value) -> Result<LayoutNode> {
+    fn parse_text(
+        value: &Value,
+        inherited: &StyleRefinement,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<LayoutNode> {
         let content = value["content"]
             .as_str()
             .ok_or_else(|| FlexRenderError::parse_error(
@@ -137,14 +242,42 @@ impl YamlParser {
                 0,
             ))?
             .to_string();
-            
-        let style = Self::parse_text_style(value)?;
-        
+
+        let mut style = Self::parse_text_style(value, path, warnings)?;
+        if let Some(color) = inherited.color {
+            style.color = color;
+        }
+        if let Some(font_size) = inherited.font_size {
+            style.font_size = font_size;
+        }
+        if let Some(text_align) = inherited.text_align {
+            style.text_align = text_align;
+        }
+        style.padding = inherited.padding.apply_to(style.padding);
+
         Ok(LayoutNode::Text { content, style })
     }
-    
+
+    /// 解析弹性占位节点：既不读取级联样式也不接受子节点，只有 `min_length`/`flex_grow`
+    /// 两个自己的属性，缺省时分别回退到 `LayoutNode::Spacer` 自己的默认值（0 像素/权重 1）
+    fn parse_spacer(value: &Value) -> Result<LayoutNode> {
+        let min_length = value["min_length"]
+            .as_f64()
+            .map(|v| Dimension::Points(v as f32))
+            .unwrap_or(Dimension::Points(0.0));
+
+        let flex_grow = value["flex_grow"].as_f64().map(|v| v as f32).unwrap_or(1.0);
+
+        Ok(LayoutNode::Spacer { min_length, flex_grow })
+    }
+
     /// 解析图片节点
-    fn parse_image(value: &Value) -> Result<LayoutNode> {
+    fn parse_image(
+        value: &Value,
+        inherited: &StyleRefinement,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<LayoutNode> {
         let src = value["src"]
             .as_str()
             .ok_or_else(|| FlexRenderError::parse_error(
@@ -153,74 +286,136 @@ impl YamlParser {
                 0,
             ))?
             .to_string();
-            
-        let style = Self::parse_image_style(value)?;
-        
+
+        let mut style = Self::parse_image_style(value, path, warnings)?;
+        if let Some(opacity) = inherited.opacity {
+            style.opacity = opacity;
+        }
+        style.padding = inherited.padding.apply_to(style.padding);
+
         Ok(LayoutNode::Image { src, style })
     }
-    
+
     /// 解析容器样式
-    fn parse_container_style(value: &Value) -> Result<ContainerStyle> {
+    fn parse_container_style(
+        value: &Value,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) -> Result<ContainerStyle> {
         let mut style = ContainerStyle::default();
-        
+
         // 解析 display 属性
         if let Some(display) = value["display"].as_str() {
-            style.display = match display {
+            style.display = match display.to_lowercase().as_str() {
                 "flex" => Display::Flex,
                 "grid" => Display::Grid,
                 "none" => Display::None,
-                _ => Display::Flex,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.display", path), display);
+                    Display::Flex
+                }
             };
         }
-        
+
         // 解析 flex_direction 属性
         if let Some(flex_direction) = value["flex_direction"].as_str() {
-            style.flex_direction = match flex_direction {
+            style.flex_direction = match flex_direction.to_lowercase().as_str() {
                 "row" => FlexDirection::Row,
                 "column" => FlexDirection::Column,
                 "row-reverse" => FlexDirection::RowReverse,
                 "column-reverse" => FlexDirection::ColumnReverse,
-                _ => FlexDirection::Column,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.flex_direction", path), flex_direction);
+                    FlexDirection::Column
+                }
             };
         }
-        
+
         // 解析 justify_content 属性
         if let Some(justify_content) = value["justify_content"].as_str() {
-            style.justify_content = match justify_content {
+            style.justify_content = match justify_content.to_lowercase().as_str() {
                 "flex-start" => JustifyContent::FlexStart,
                 "flex-end" => JustifyContent::FlexEnd,
                 "center" => JustifyContent::Center,
                 "space-between" => JustifyContent::SpaceBetween,
                 "space-around" => JustifyContent::SpaceAround,
                 "space-evenly" => JustifyContent::SpaceEvenly,
-                _ => JustifyContent::FlexStart,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.justify_content", path), justify_content);
+                    JustifyContent::FlexStart
+                }
             };
         }
-        
+
         // 解析 align_items 属性
         if let Some(align_items) = value["align_items"].as_str() {
-            style.align_items = match align_items {
+            style.align_items = match align_items.to_lowercase().as_str() {
                 "flex-start" => AlignItems::FlexStart,
                 "flex-end" => AlignItems::FlexEnd,
                 "center" => AlignItems::Center,
                 "stretch" => AlignItems::Stretch,
                 "baseline" => AlignItems::Baseline,
-                _ => AlignItems::FlexStart,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.align_items", path), align_items);
+                    AlignItems::FlexStart
+                }
             };
         }
-        
+
+        // 解析 flex_wrap 属性
+        if let Some(flex_wrap) = value["flex_wrap"].as_str() {
+            style.flex_wrap = match flex_wrap.to_lowercase().as_str() {
+                "nowrap" => FlexWrap::NoWrap,
+                "wrap" => FlexWrap::Wrap,
+                "wrap-reverse" => FlexWrap::WrapReverse,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.flex_wrap", path), flex_wrap);
+                    FlexWrap::NoWrap
+                }
+            };
+        }
+
+        // 解析 align_content 属性（多行换行时生效）
+        if let Some(align_content) = value["align_content"].as_str() {
+            style.align_content = match align_content.to_lowercase().as_str() {
+                "flex-start" => AlignContent::FlexStart,
+                "flex-end" => AlignContent::FlexEnd,
+                "center" => AlignContent::Center,
+                "stretch" => AlignContent::Stretch,
+                "space-between" => AlignContent::SpaceBetween,
+                "space-around" => AlignContent::SpaceAround,
+                "space-evenly" => AlignContent::SpaceEvenly,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.align_content", path), align_content);
+                    AlignContent::FlexStart
+                }
+            };
+        }
+
+        // 解析作为 flex 子项时的属性
+        Self::parse_flex_item_style(
+            value,
+            &mut style.flex_grow,
+            &mut style.flex_shrink,
+            &mut style.flex_basis,
+            &mut style.align_self,
+            &mut style.order,
+            path,
+            warnings,
+        );
+
         // 解析尺寸属性
         if let Some(width) = value["width"].as_f64() {
             style.width = Dimension::Points(width as f32);
         }
-        
+
         if let Some(height) = value["height"].as_f64() {
             style.height = Dimension::Points(height as f32);
         }
-        
+
         // 解析背景颜色
         if let Some(bg_str) = value["background"].as_str() {
-            style.background = Some(Color::from_hex(bg_str)?);
+            style.background = Some(Background::Color(Color::from_hex(bg_str)?));
         }
         
         // 解析边框
@@ -235,70 +430,213 @@ impl YamlParser {
         if let Some(border_radius) = value["border_radius"].as_f64() {
             style.border_radius = border_radius as f32;
         }
-        
+
+        // 解析投影阴影（corner_radius 沿用 border_radius，不重复开一个字段）
+        if let Some(shadow_blur) = value["shadow_blur"].as_f64() {
+            style.shadow_blur = shadow_blur as f32;
+        }
+
+        if let Some(shadow_color_str) = value["shadow_color"].as_str() {
+            style.shadow_color = Color::from_hex(shadow_color_str)?;
+        }
+
+        if let Some(shadow_offset) = value["shadow_offset"].as_sequence() {
+            if shadow_offset.len() == 2 {
+                if let (Some(x), Some(y)) = (shadow_offset[0].as_f64(), shadow_offset[1].as_f64()) {
+                    style.shadow_offset = Point::new(x as f32, y as f32);
+                }
+            }
+        }
+
+        if let Some(shadow_spread) = value["shadow_spread"].as_f64() {
+            style.shadow_spread = shadow_spread as f32;
+        }
+
+        // 解析子项剩余空间分配策略；`none`/不写保持默认行为
+        if let Some(distribution) = value["distribution"].as_str() {
+            style.distribution = match distribution.to_lowercase().as_str() {
+                "fill" => Some(Distribution::Fill),
+                "fill-equally" => Some(Distribution::FillEqually),
+                "fill-proportionally" => Some(Distribution::FillProportionally),
+                "equal-spacing" => Some(Distribution::EqualSpacing),
+                "equal-centering" => Some(Distribution::EqualCentering),
+                "none" | "null" => None,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.distribution", path), distribution);
+                    None
+                }
+            };
+        }
+
+        // 解析溢出裁剪：`overflow` 同时设置两个轴，`overflow_x`/`overflow_y` 单独覆盖某一轴
+        if let Some(overflow) = value["overflow"].as_str() {
+            let overflow = Self::parse_overflow(overflow, &format!("{}.overflow", path), warnings);
+            style.overflow_x = overflow;
+            style.overflow_y = overflow;
+        }
+        if let Some(overflow_x) = value["overflow_x"].as_str() {
+            style.overflow_x = Self::parse_overflow(overflow_x, &format!("{}.overflow_x", path), warnings);
+        }
+        if let Some(overflow_y) = value["overflow_y"].as_str() {
+            style.overflow_y = Self::parse_overflow(overflow_y, &format!("{}.overflow_y", path), warnings);
+        }
+
         Ok(style)
     }
-    
+
+    /// 解析单个轴的 `overflow` 取值，未识别的字符串按 `Visible` 处理
+    fn parse_overflow(value: &str, field_path: &str, warnings: &mut Vec<String>) -> Overflow {
+        match value.to_lowercase().as_str() {
+            "clip" => Overflow::Clip,
+            "hidden" => Overflow::Hidden,
+            "visible" => Overflow::Visible,
+            _ => {
+                warn_unrecognized(warnings, field_path, value);
+                Overflow::Visible
+            }
+        }
+    }
+
+    /// 解析节点作为某个 flex 容器子项时共用的属性（三种节点类型都可能是 flex 子项）
+    fn parse_flex_item_style(
+        value: &Value,
+        flex_grow: &mut f32,
+        flex_shrink: &mut f32,
+        flex_basis: &mut Dimension,
+        align_self: &mut Option<AlignItems>,
+        order: &mut i32,
+        path: &str,
+        warnings: &mut Vec<String>,
+    ) {
+        if let Some(grow) = value["flex_grow"].as_f64() {
+            *flex_grow = grow as f32;
+        }
+
+        if let Some(shrink) = value["flex_shrink"].as_f64() {
+            *flex_shrink = shrink as f32;
+        }
+
+        if let Some(basis) = value["flex_basis"].as_f64() {
+            *flex_basis = Dimension::Points(basis as f32);
+        } else if let Some(basis_str) = value["flex_basis"].as_str() {
+            if basis_str.eq_ignore_ascii_case("auto") {
+                *flex_basis = Dimension::Auto;
+            } else {
+                warn_unrecognized(warnings, &format!("{}.flex_basis", path), basis_str);
+            }
+        }
+
+        if let Some(align_self_str) = value["align_self"].as_str() {
+            *align_self = match align_self_str.to_lowercase().as_str() {
+                "flex-start" => Some(AlignItems::FlexStart),
+                "flex-end" => Some(AlignItems::FlexEnd),
+                "center" => Some(AlignItems::Center),
+                "stretch" => Some(AlignItems::Stretch),
+                "baseline" => Some(AlignItems::Baseline),
+                "auto" | "none" | "null" => None,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.align_self", path), align_self_str);
+                    None
+                }
+            };
+        }
+
+        if let Some(order_value) = value["order"].as_i64() {
+            *order = order_value as i32;
+        }
+    }
+
     /// 解析文本样式
-    fn parse_text_style(value: &Value) -> Result<TextStyle> {
+    fn parse_text_style(value: &Value, path: &str, warnings: &mut Vec<String>) -> Result<TextStyle> {
         let mut style = TextStyle::default();
-        
+
+        Self::parse_flex_item_style(
+            value,
+            &mut style.flex_grow,
+            &mut style.flex_shrink,
+            &mut style.flex_basis,
+            &mut style.align_self,
+            &mut style.order,
+            path,
+            warnings,
+        );
+
         // 解析字体属性
         if let Some(font_family) = value["font_family"].as_str() {
             style.font_family = font_family.to_string();
         }
-        
+
         if let Some(font_size) = value["font_size"].as_f64() {
             style.font_size = font_size as f32;
         }
-        
+
         if let Some(font_weight) = value["font_weight"].as_str() {
-            style.font_weight = match font_weight {
+            style.font_weight = match font_weight.to_lowercase().as_str() {
                 "normal" => FontWeight::Normal,
                 "bold" => FontWeight::Bold,
-                _ => FontWeight::Normal,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.font_weight", path), font_weight);
+                    FontWeight::Normal
+                }
             };
         } else if let Some(font_weight) = value["font_weight"].as_u64() {
             style.font_weight = FontWeight::Weight(font_weight as u16);
         }
-        
+
         // 解析颜色
         if let Some(color_str) = value["color"].as_str() {
             style.color = Color::from_hex(color_str)?;
         }
-        
+
         // 解析文本对齐
         if let Some(text_align) = value["text_align"].as_str() {
-            style.text_align = match text_align {
+            style.text_align = match text_align.to_lowercase().as_str() {
                 "left" => TextAlign::Left,
                 "center" => TextAlign::Center,
                 "right" => TextAlign::Right,
                 "justify" => TextAlign::Justify,
-                _ => TextAlign::Left,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.text_align", path), text_align);
+                    TextAlign::Left
+                }
             };
         }
-        
+
         // 解析行高
         if let Some(line_height) = value["line_height"].as_f64() {
             style.line_height = line_height as f32;
         }
-        
+
         Ok(style)
     }
-    
+
     /// 解析图片样式
-    fn parse_image_style(value: &Value) -> Result<ImageStyle> {
+    fn parse_image_style(value: &Value, path: &str, warnings: &mut Vec<String>) -> Result<ImageStyle> {
         let mut style = ImageStyle::default();
-        
+
+        Self::parse_flex_item_style(
+            value,
+            &mut style.flex_grow,
+            &mut style.flex_shrink,
+            &mut style.flex_basis,
+            &mut style.align_self,
+            &mut style.order,
+            path,
+            warnings,
+        );
+
         // 解析 object_fit 属性
         if let Some(object_fit) = value["object_fit"].as_str() {
-            style.object_fit = match object_fit {
+            style.object_fit = match object_fit.to_lowercase().as_str() {
                 "fill" => ObjectFit::Fill,
                 "contain" => ObjectFit::Contain,
                 "cover" => ObjectFit::Cover,
                 "scale-down" => ObjectFit::ScaleDown,
                 "none" => ObjectFit::None,
-                _ => ObjectFit::Fill,
+                _ => {
+                    warn_unrecognized(warnings, &format!("{}.object_fit", path), object_fit);
+                    ObjectFit::Fill
+                }
             };
         }
         
@@ -341,4 +679,36 @@ mod tests {
         assert_eq!(config.height, 800.0);
         assert_eq!(config.dpi, 300.0);
     }
+
+    #[test]
+    fn test_color_and_font_size_cascade_to_descendant_text() {
+        let yaml = "template:\n  width: 400\n  height: 300\n\ncontainer:\n  display: flex\n  color: \"#ff0000\"\n  font_size: 32\n  children:\n    - type: container\n      children:\n        - type: text\n          content: \"Hello\"\n";
+
+        let (_config, root) = YamlParser::parse(yaml).unwrap();
+        let inner_container = &root.children()[0];
+        let text = &inner_container.children()[0];
+
+        match text {
+            LayoutNode::Text { style, .. } => {
+                assert_eq!(style.color, Color::from_hex("#ff0000").unwrap());
+                assert_eq!(style.font_size, 32.0);
+            }
+            _ => panic!("期望文本节点"),
+        }
+    }
+
+    #[test]
+    fn test_own_color_overrides_inherited_color() {
+        let yaml = "template:\n  width: 400\n  height: 300\n\ncontainer:\n  display: flex\n  color: \"#ff0000\"\n  children:\n    - type: text\n      content: \"Hello\"\n      color: \"#0000ff\"\n";
+
+        let (_config, root) = YamlParser::parse(yaml).unwrap();
+        let text = &root.children()[0];
+
+        match text {
+            LayoutNode::Text { style, .. } => {
+                assert_eq!(style.color, Color::from_hex("#0000ff").unwrap());
+            }
+            _ => panic!("期望文本节点"),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+//! 绘制后端抽象
+//!
+//! `Canvas` 把渲染结果直接绑定在光栅图像上，输出永远是位图。把绘制原语抽成一个
+//! `DrawBackend` trait（借鉴 plotters 的 backend 设计），`Renderer` 就只依赖这几个
+//! 方法，同一份布局结果既可以栅格化成 PNG（`Canvas`），也可以发给 `SvgBackend`
+//! 输出分辨率无关、文字可选中的 SVG。
+
+use crate::error::Result;
+use crate::types::{Color, ObjectFit, Point, Rect};
+use image::RgbaImage;
+
+/// 绘制后端：`Renderer` 只通过这几个原语操作画面，具体落地成位图还是矢量由实现决定
+pub trait DrawBackend {
+    /// 绘制填充矩形
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+
+    /// 绘制矩形边框
+    fn stroke_rect(&mut self, rect: Rect, color: Color, width: f32);
+
+    /// 绘制一行文本，`position` 是文字视觉框左上角
+    fn draw_text(
+        &mut self,
+        text: &str,
+        position: Point,
+        font_data: &[u8],
+        font_size: f32,
+        color: Color,
+    ) -> Result<()>;
+
+    /// 绘制图片到目标区域；`image_src` 可以是文件路径、`data:` 内联图片或 `http(s)://` 远程 URL
+    fn draw_image(&mut self, image_src: &str, dest_rect: Rect, object_fit: ObjectFit) -> Result<()>;
+
+    /// 把一张已经准备好的 RGBA 位图混合到画面上（彩色 emoji、贴图等场景）
+    fn blend_image(&mut self, source: &RgbaImage, position: Point);
+
+    /// 收尾：栅格后端通常什么都不用做，矢量后端可以在这里做最终的文档封口
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
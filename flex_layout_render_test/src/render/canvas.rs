@@ -9,8 +9,93 @@ use image::{ImageBuffer, Rgba, RgbaImage};
 use imageproc::drawing::{draw_filled_rect_mut, draw_hollow_rect_mut};
 use imageproc::rect::Rect as ImageRect;
 use rusttype::{Font, Scale};
+use std::collections::HashMap;
 use std::path::Path;
 
+/// `draw_text_aligned` 的排版选项：行距倍数、纵向对齐方式，以及 `resize` 自适应缩放
+/// 策略下允许搜索的字号范围。建模自 `pane` crate 的文本自动缩放行为。
+#[derive(Debug, Clone, Copy)]
+pub struct TextLayoutOptions {
+    /// 行距相对字体自然行高的倍数（行高 = (ascent - descent + line_gap) * line_spacing）
+    pub line_spacing: f32,
+    /// 文本块在边界框内的纵向对齐方式
+    pub vertical_align: VerticalAlign,
+    /// 字号自适应策略
+    pub resize: TextResize,
+    /// `resize` 为 `NoLarger`/`Max` 时允许缩小到的最小字号
+    pub min_font_size: f32,
+    /// `resize` 为 `Max` 时允许放大到的最大字号
+    pub max_font_size: f32,
+    /// 制表符展开到下一个整数倍位置所用的步进宽度（像素）
+    pub tab_width: f32,
+    /// 文字放不下边界框时的处理方式
+    pub overflow: TextOverflow,
+    /// 最多显示的行数，超出的行被丢弃
+    pub max_lines: Option<u32>,
+}
+
+impl Default for TextLayoutOptions {
+    fn default() -> Self {
+        Self {
+            line_spacing: 1.2,
+            vertical_align: VerticalAlign::Top,
+            resize: TextResize::None,
+            min_font_size: 8.0,
+            max_font_size: 96.0,
+            tab_width: 40.0,
+            overflow: TextOverflow::Visible,
+            max_lines: None,
+        }
+    }
+}
+
+/// 一段文本在给定字号下的竖直度量（基线相关）与前进宽度，均为逻辑单位。
+/// 布局求解阶段可以用它在真正渲染前就算出文本元素需要占用的空间。
+#[derive(Debug, Clone, Copy)]
+pub struct TextMetrics {
+    /// 基线以上的高度
+    pub ascent: f32,
+    /// 基线以下的高度（rusttype 约定为负值）
+    pub descent: f32,
+    /// 单行自然行高：`ascent - descent + line_gap`
+    pub line_height: f32,
+    /// 整段文本（单行）的前进宽度
+    pub width: f32,
+}
+
+/// 某个字号下的换行结果，以及渲染/再次测量时需要复用的像素度量
+struct WrappedText {
+    lines: Vec<String>,
+    scale: Scale,
+    line_height_pixels: f32,
+}
+
+/// 一个解析好的字体面，附带逐字符的字形覆盖缓存：同一段多行文本里重复出现的字符
+/// （标点、空格、常见汉字……）不需要每次都重新查一遍 cmap，查过一次的结果记在
+/// `coverage` 里直接复用。`draw_text_aligned` 为主字体和每个回退字体各建一个，
+/// 在整段文本的所有行之间共享。
+struct FontFace<'a> {
+    data: &'a [u8],
+    font: Font<'a>,
+    coverage: HashMap<char, bool>,
+}
+
+impl<'a> FontFace<'a> {
+    fn new(data: &'a [u8], font: Font<'a>) -> Self {
+        Self {
+            data,
+            font,
+            coverage: HashMap::new(),
+        }
+    }
+
+    /// 这个字体能否画出字符 `c`（cmap 里是否有非 `.notdef` 的字形），结果会被缓存
+    fn covers(&mut self, c: char) -> bool {
+        let font = &self.font;
+        *self.coverage.entry(c).or_insert_with(|| font.glyph(c).id().0 != 0)
+    }
+}
+
 /// 渲染画布
 pub struct Canvas {
     /// 图像缓冲区
@@ -120,6 +205,336 @@ impl Canvas {
         }
     }
 
+    /// 绘制圆角填充矩形：先填充中心十字（两个重叠矩形，覆盖除四个圆角方块外的所有像素），
+    /// 再对四个圆角方块逐像素按到圆心的距离算覆盖率做抗锯齿，和 `blend_pixel` 共用同一套
+    /// alpha 混合路径
+    pub fn fill_round_rect(&mut self, rect: Rect, radius: f32, color: Color) {
+        let (x, y) = self.to_pixel_coords(Point::new(rect.x, rect.y));
+        let (width, height) = self.to_pixel_size(Size::new(rect.width, rect.height));
+        let radius_pixels = self.to_pixel_length(radius);
+        let rgba = Rgba([color.r, color.g, color.b, color.a]);
+
+        self.fill_round_rect_pixels(x, y, width, height, radius_pixels, rgba);
+    }
+
+    fn fill_round_rect_pixels(&mut self, x: u32, y: u32, width: u32, height: u32, radius: f32, rgba: Rgba<u8>) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let radius = radius.max(0.0).min(width as f32 / 2.0).min(height as f32 / 2.0);
+        if radius < 0.5 {
+            let image_rect = ImageRect::at(x as i32, y as i32).of_size(width, height);
+            draw_filled_rect_mut(&mut self.image, image_rect, rgba);
+            return;
+        }
+
+        let ri = (radius.ceil() as u32).min(width / 2).min(height / 2);
+
+        // 中心十字：横条和竖条重叠，覆盖除四个圆角方块外的所有区域
+        if height > 2 * ri {
+            let band = ImageRect::at(x as i32, (y + ri) as i32).of_size(width, height - 2 * ri);
+            draw_filled_rect_mut(&mut self.image, band, rgba);
+        }
+        if width > 2 * ri {
+            let band = ImageRect::at((x + ri) as i32, y as i32).of_size(width - 2 * ri, height);
+            draw_filled_rect_mut(&mut self.image, band, rgba);
+        }
+
+        // 四个圆角方块，每个方块内按到对应圆心的距离算覆盖率
+        let corners = [
+            (x, y, x as f32 + radius, y as f32 + radius),
+            (x + width - ri, y, (x + width) as f32 - radius, y as f32 + radius),
+            (x, y + height - ri, x as f32 + radius, (y + height) as f32 - radius),
+            (
+                x + width - ri,
+                y + height - ri,
+                (x + width) as f32 - radius,
+                (y + height) as f32 - radius,
+            ),
+        ];
+
+        for (corner_x, corner_y, center_x, center_y) in corners {
+            for dy in 0..ri {
+                for dx in 0..ri {
+                    let px = corner_x + dx;
+                    let py = corner_y + dy;
+                    let dist = (((px as f32 + 0.5) - center_x).powi(2)
+                        + ((py as f32 + 0.5) - center_y).powi(2))
+                    .sqrt();
+                    let coverage = (radius + 0.5 - dist).clamp(0.0, 1.0);
+                    self.blend_pixel(px, py, rgba, coverage);
+                }
+            }
+        }
+    }
+
+    /// 用 [`Gradient`] 填充一个（可选圆角的）矩形：逐像素求出该像素在渐变轴上的位置
+    /// `t ∈ [0, 1]`，在两个相邻 `ColorStop` 之间做线性插值取色；`radius > 0` 时复用
+    /// `rounded_rect_sdf` 做和 `fill_round_rect`/`draw_shadow` 一样的边缘抗锯齿。
+    pub fn fill_gradient(&mut self, rect: Rect, radius: f32, gradient: &Gradient) {
+        let (x, y) = self.to_pixel_coords(Point::new(rect.x, rect.y));
+        let (width, height) = self.to_pixel_size(Size::new(rect.width, rect.height));
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let radius_pixels = self.to_pixel_length(radius);
+
+        for dy in 0..height {
+            for dx in 0..width {
+                let px = dx as f32 + 0.5;
+                let py = dy as f32 + 0.5;
+
+                let coverage = if radius_pixels > 0.0 {
+                    let dist = Self::rounded_rect_sdf(px, py, width as f32, height as f32, radius_pixels);
+                    (0.5 - dist).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                if coverage <= 0.0 {
+                    continue;
+                }
+
+                let color = self.sample_gradient(gradient, px, py, width as f32, height as f32);
+                let rgba = Rgba([color.r, color.g, color.b, color.a]);
+                self.blend_pixel(x + dx, y + dy, rgba, coverage);
+            }
+        }
+    }
+
+    /// 在矩形局部坐标系 `(px, py) ∈ [0, width] x [0, height]` 下求渐变在该点的颜色
+    fn sample_gradient(&self, gradient: &Gradient, px: f32, py: f32, width: f32, height: f32) -> Color {
+        let t = match gradient {
+            Gradient::Linear { angle, .. } => {
+                let radians = angle.to_radians();
+                let (ux, uy) = (radians.cos(), radians.sin());
+
+                // 把矩形四角投影到渐变轴上，取投影的最小/最大值作为渐变覆盖的完整区间
+                let corners = [(0.0, 0.0), (width, 0.0), (0.0, height), (width, height)];
+                let projections: Vec<f32> = corners.iter().map(|(cx, cy)| cx * ux + cy * uy).collect();
+                let min_proj = projections.iter().cloned().fold(f32::INFINITY, f32::min);
+                let max_proj = projections.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+                let proj = px * ux + py * uy;
+                if max_proj > min_proj {
+                    (proj - min_proj) / (max_proj - min_proj)
+                } else {
+                    0.0
+                }
+            }
+            Gradient::Radial { center, radius, .. } => {
+                let center_x = self.to_pixel_length(center.x);
+                let center_y = self.to_pixel_length(center.y);
+                let radius_pixels = self.to_pixel_length(*radius).max(0.0001);
+                let dist = ((px - center_x).powi(2) + (py - center_y).powi(2)).sqrt();
+                dist / radius_pixels
+            }
+        };
+
+        Self::color_at(gradient, t)
+    }
+
+    /// 在渐变的 `ColorStop` 列表里按位置 `t` 取色：落在两个停止点之间时线性插值，
+    /// 落在第一个/最后一个停止点之外时夹到对应端点颜色
+    fn color_at(gradient: &Gradient, t: f32) -> Color {
+        let stops = match gradient {
+            Gradient::Linear { stops, .. } => stops,
+            Gradient::Radial { stops, .. } => stops,
+        };
+
+        if stops.is_empty() {
+            return Color::transparent();
+        }
+        if stops.len() == 1 {
+            return stops[0].color;
+        }
+
+        let t = t.clamp(stops.first().unwrap().position, stops.last().unwrap().position);
+
+        for window in stops.windows(2) {
+            let (prev, next) = (&window[0], &window[1]);
+            if t >= prev.position && t <= next.position {
+                let span = next.position - prev.position;
+                let local_t = if span > 0.0 { (t - prev.position) / span } else { 0.0 };
+                return prev.color.lerp(next.color, local_t);
+            }
+        }
+
+        stops.last().unwrap().color
+    }
+
+    /// 绘制圆角矩形边框：沿圆角矩形的有符号距离场（直边和圆弧统一处理）取一条宽度为
+    /// `width` 的环带，环带两侧各留半像素做抗锯齿
+    pub fn stroke_round_rect(&mut self, rect: Rect, radius: f32, color: Color, width: f32) {
+        if width <= 0.0 {
+            return;
+        }
+
+        let (x, y) = self.to_pixel_coords(Point::new(rect.x, rect.y));
+        let (rect_width, rect_height) = self.to_pixel_size(Size::new(rect.width, rect.height));
+        if rect_width == 0 || rect_height == 0 {
+            return;
+        }
+
+        let radius_pixels = self.to_pixel_length(radius);
+        let stroke_width_pixels = self.to_pixel_length(width);
+        let rgba = Rgba([color.r, color.g, color.b, color.a]);
+
+        for dy in 0..rect_height {
+            for dx in 0..rect_width {
+                let dist = Self::rounded_rect_sdf(
+                    dx as f32 + 0.5,
+                    dy as f32 + 0.5,
+                    rect_width as f32,
+                    rect_height as f32,
+                    radius_pixels,
+                );
+                let coverage = (stroke_width_pixels / 2.0 + 0.5 - dist.abs()).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend_pixel(x + dx, y + dy, rgba, coverage);
+                }
+            }
+        }
+    }
+
+    /// 绘制圆角矩形的投影阴影：先把轮廓按 `spread` 外扩/内缩（圆角半径跟着一起外扩，
+    /// 保持四角同心），再把这个扩展后的遮罩栅格化到一块带外扩边距的暂存缓冲区，
+    /// 做两遍可分离的一维高斯模糊，按 `offset` 偏移后用阴影颜色的透明度着色合成到画布上。
+    /// 参照截图美化工具里阴影功能的实现思路。
+    pub fn draw_shadow(&mut self, rect: Rect, radius: f32, color: Color, blur: f32, spread: f32, offset: Point) {
+        let (x, y) = self.to_pixel_coords(Point::new(rect.x, rect.y));
+        let (width, height) = self.to_pixel_size(Size::new(rect.width, rect.height));
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let radius_pixels = self.to_pixel_length(radius);
+        let blur_pixels = self.to_pixel_length(blur).max(0.0);
+        let spread_pixels = self.to_pixel_length(spread);
+        let (offset_x_pixels, offset_y_pixels) = self.to_pixel_vector(offset);
+
+        // spread 外扩轮廓：宽高各增加 2 * spread，半径跟着外扩以保持同心圆角
+        let shadow_width = (width as f32 + spread_pixels * 2.0).max(0.0);
+        let shadow_height = (height as f32 + spread_pixels * 2.0).max(0.0);
+        let shadow_radius = (radius_pixels + spread_pixels).max(0.0);
+        let shadow_origin_x = -spread_pixels;
+        let shadow_origin_y = -spread_pixels;
+
+        let pad = (blur_pixels.ceil() as i64 * 3 + 2).clamp(2, 256) as i64;
+        let mask_width = (shadow_width.ceil() as i64 + pad * 2).max(1);
+        let mask_height = (shadow_height.ceil() as i64 + pad * 2).max(1);
+
+        let mut mask = vec![0.0f32; (mask_width * mask_height) as usize];
+        for my in 0..mask_height {
+            for mx in 0..mask_width {
+                let dist = Self::rounded_rect_sdf(
+                    (mx - pad) as f32 + 0.5 - shadow_origin_x,
+                    (my - pad) as f32 + 0.5 - shadow_origin_y,
+                    shadow_width,
+                    shadow_height,
+                    shadow_radius,
+                );
+                mask[(my * mask_width + mx) as usize] = (0.5 - dist).clamp(0.0, 1.0);
+            }
+        }
+
+        if blur_pixels > 0.0 {
+            Self::gaussian_blur_separable(&mut mask, mask_width as usize, mask_height as usize, blur_pixels);
+        }
+
+        let shadow_rgba = Rgba([color.r, color.g, color.b, color.a]);
+        for my in 0..mask_height {
+            for mx in 0..mask_width {
+                let coverage = mask[(my * mask_width + mx) as usize];
+                if coverage <= 0.0 {
+                    continue;
+                }
+                let target_x = x as i64 + (mx - pad) + offset_x_pixels.round() as i64;
+                let target_y = y as i64 + (my - pad) + offset_y_pixels.round() as i64;
+                if target_x < 0 || target_y < 0 {
+                    continue;
+                }
+                self.blend_pixel(target_x as u32, target_y as u32, shadow_rgba, coverage);
+            }
+        }
+    }
+
+    /// 圆角矩形的有符号距离场（像素单位）：矩形占据 `[0, width] x [0, height]`，
+    /// 边界外距离为正、边界内为负，圆角半径为 `radius`。直边和圆弧用同一套公式统一处理，
+    /// 避免圆角和直边交界处出现台阶。
+    fn rounded_rect_sdf(px: f32, py: f32, width: f32, height: f32, radius: f32) -> f32 {
+        let half_width = width / 2.0;
+        let half_height = height / 2.0;
+        let radius = radius.max(0.0).min(half_width).min(half_height);
+
+        let dx = (px - half_width).abs() - (half_width - radius);
+        let dy = (py - half_height).abs() - (half_height - radius);
+
+        let outside = (dx.max(0.0).powi(2) + dy.max(0.0).powi(2)).sqrt();
+        outside + dx.max(dy).min(0.0) - radius
+    }
+
+    /// 把逻辑向量（可正可负，比如阴影偏移）转换成像素向量，和 `to_pixel_coords` 的区别是
+    /// 不会被截断成无符号坐标
+    fn to_pixel_vector(&self, point: Point) -> (f32, f32) {
+        if self.dpi <= 1.0 {
+            (point.x, point.y)
+        } else {
+            let scale = self.dpi / 72.0;
+            (point.x * scale, point.y * scale)
+        }
+    }
+
+    /// 对一块 `width x height` 的浮点缓冲区做两遍一维高斯模糊（先横向再纵向），
+    /// 核的标准差取 `blur_radius / 2`，核半径覆盖到 3 个标准差
+    fn gaussian_blur_separable(buffer: &mut [f32], width: usize, height: usize, blur_radius: f32) {
+        let sigma = (blur_radius / 2.0).max(0.5);
+        let kernel_radius = (sigma * 3.0).ceil() as i32;
+        let mut kernel = Vec::with_capacity((kernel_radius * 2 + 1) as usize);
+        let mut kernel_sum = 0.0f32;
+        for i in -kernel_radius..=kernel_radius {
+            let v = (-(i * i) as f32 / (2.0 * sigma * sigma)).exp();
+            kernel.push(v);
+            kernel_sum += v;
+        }
+        for v in &mut kernel {
+            *v /= kernel_sum;
+        }
+
+        let mut scratch = vec![0.0f32; buffer.len()];
+
+        // 横向一维模糊
+        for row in 0..height {
+            for col in 0..width {
+                let mut sum = 0.0f32;
+                for (k, weight) in kernel.iter().enumerate() {
+                    let offset = k as i32 - kernel_radius;
+                    let sample_col = col as i32 + offset;
+                    if sample_col >= 0 && (sample_col as usize) < width {
+                        sum += buffer[row * width + sample_col as usize] * weight;
+                    }
+                }
+                scratch[row * width + col] = sum;
+            }
+        }
+
+        // 纵向一维模糊
+        for row in 0..height {
+            for col in 0..width {
+                let mut sum = 0.0f32;
+                for (k, weight) in kernel.iter().enumerate() {
+                    let offset = k as i32 - kernel_radius;
+                    let sample_row = row as i32 + offset;
+                    if sample_row >= 0 && (sample_row as usize) < height {
+                        sum += scratch[sample_row as usize * width + col] * weight;
+                    }
+                }
+                buffer[row * width + col] = sum;
+            }
+        }
+    }
+
     /// 绘制文本
     pub fn draw_text(
         &mut self,
@@ -142,19 +557,301 @@ impl Canvas {
         let scale = Scale::uniform(pixel_font_size);
         let (x, y) = self.to_pixel_coords(position);
 
+        // position 给到的是文字视觉框的顶部，笔位置（基线）= 顶部 + ascent
+        let ascent = font.v_metrics(scale).ascent;
+        let baseline_y = y as f32 + ascent;
+
         let rgba = Rgba([color.r, color.g, color.b, color.a]);
 
-        // 使用 rusttype 和 imageproc 绘制文本
+        // 这个简化 API 本来就不走字体/字形缓存（每次都重新 `Font::try_from_bytes`），
+        // 这里同样只建一个一次性的缓存满足签名，不指望跨调用复用
+        let mut glyph_cache = crate::render::glyph_cache::GlyphRasterCache::new();
+        self.draw_shaped_text(text, font_data, &font, scale, x as f32, baseline_y, rgba, &[], &mut glyph_cache, None);
+        Ok(())
+    }
+
+    /// 测量一段文本在给定字号下的竖直度量（基线相关）与前进宽度，均为逻辑单位。
+    /// 布局求解阶段可以用它在真正渲染前就算出文本元素需要占用的空间。
+    pub fn measure_text(&self, text: &str, font_data: &[u8], font_size: f32) -> Result<TextMetrics> {
+        let font = Font::try_from_bytes(font_data)
+            .ok_or_else(|| FlexRenderError::render_error("字体加载失败".to_string()))?;
+
+        let pixel_font_size = self.to_pixel_length(font_size);
+        let scale = Scale::uniform(pixel_font_size);
+        let v_metrics = font.v_metrics(scale);
+        let width_pixels = self.shaped_text_width_pixels(text, font_data, &font, scale, &[]);
+
+        Ok(TextMetrics {
+            ascent: self.to_logical_length(v_metrics.ascent),
+            descent: self.to_logical_length(v_metrics.descent),
+            line_height: self.to_logical_length(self.natural_line_height_pixels(&font, scale)),
+            width: self.to_logical_length(width_pixels),
+        })
+    }
+
+    /// 一行文本的自然行高（像素）：`ascent - descent + line_gap`
+    fn natural_line_height_pixels(&self, font: &Font, scale: Scale) -> f32 {
+        let v_metrics = font.v_metrics(scale);
+        v_metrics.ascent - v_metrics.descent + v_metrics.line_gap
+    }
+
+    /// 绘制一行文本，开启 `shaping` feature 时先用 HarfBuzz 整形拿到带 kerning/连字的
+    /// glyph 位置再逐字形栅格化；未开启时退回现有的 `imageproc::drawing::draw_text_mut` 路径。
+    /// `pen_y` 是基线位置（而不是文字视觉框顶部）。`features` 是喂给 HarfBuzz 的 OpenType
+    /// 特性开关（`TextStyle::font_features`），仅在 `shaping` feature 开启时生效。
+    /// `clip_rect` 同样只在 shaping 路径上生效：覆盖率蒙版字形逐像素裁到矩形内，彩色位图
+    /// 字形只按是否与矩形相交整张取舍；退回 `draw_text_mut` 时不做裁剪。
+    /// 返回整行文本的总前进宽度（像素）。
+    fn draw_shaped_text(
+        &mut self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        scale: Scale,
+        pen_x: f32,
+        pen_y: f32,
+        rgba: Rgba<u8>,
+        features: &[(String, u32)],
+        glyph_cache: &mut crate::render::glyph_cache::GlyphRasterCache,
+        clip_rect: Option<Rect>,
+    ) -> f32 {
+        #[cfg(feature = "shaping")]
+        {
+            let direction = crate::layout::shaping::detect_direction(text);
+            if let Ok(shaped) = crate::layout::shaping::shape_run(
+                font_data,
+                font.units_per_em() as f32,
+                scale.x,
+                text,
+                direction,
+                features,
+            ) {
+                if !shaped.glyphs.is_empty() {
+                    // RTL run 的笔游标从右边缘开始向左推进
+                    let mut cursor_x = match direction {
+                        crate::layout::shaping::TextDirection::LeftToRight => pen_x,
+                        crate::layout::shaping::TextDirection::RightToLeft => {
+                            pen_x + shaped.total_advance
+                        }
+                    };
+                    // 横排文本的 y_advance 通常为 0，这里按通用 shaping 结果累加，
+                    // 让未来支持竖排书写方向的字体也能正确换行推进
+                    let mut cursor_y = pen_y;
+
+                    for glyph in &shaped.glyphs {
+                        let advance = match direction {
+                            crate::layout::shaping::TextDirection::LeftToRight => glyph.x_advance,
+                            crate::layout::shaping::TextDirection::RightToLeft => -glyph.x_advance,
+                        };
+                        let origin_x = match direction {
+                            crate::layout::shaping::TextDirection::LeftToRight => cursor_x,
+                            crate::layout::shaping::TextDirection::RightToLeft => {
+                                cursor_x + advance
+                            }
+                        };
+
+                        let positioned = font
+                            .glyph(rusttype::GlyphId(glyph.glyph_id))
+                            .scaled(scale)
+                            .positioned(rusttype::point(
+                                origin_x + glyph.x_offset,
+                                cursor_y + glyph.y_offset,
+                            ));
+
+                        if let Some(bb) = positioned.pixel_bounding_box() {
+                            // 先看这个字形是否有彩色位图（CBDT/sbix）表示，有就整张混合；
+                            // 否则退回朴素的覆盖率蒙版逐像素着色，蒙版走字形栅格化缓存。
+                            let color_bitmap = crate::render::color_glyph::color_bitmap_for_glyph(
+                                font_data,
+                                glyph.glyph_id,
+                                scale.y,
+                            );
+
+                            if let Some(crate::render::color_glyph::GlyphBitmap::Rgba(bitmap)) = color_bitmap {
+                                let in_clip = match clip_rect {
+                                    Some(clip) => {
+                                        let glyph_rect = Rect::new(
+                                            bb.min.x as f32,
+                                            bb.min.y as f32,
+                                            (bb.max.x - bb.min.x) as f32,
+                                            (bb.max.y - bb.min.y) as f32,
+                                        );
+                                        glyph_rect.intersects(&clip)
+                                    }
+                                    None => true,
+                                };
+                                if in_clip {
+                                    let dest = Point::new(bb.min.x as f32, bb.min.y as f32);
+                                    self.blend_image(&bitmap, dest);
+                                }
+                            } else {
+                                let glyph_pen_x = origin_x + glyph.x_offset;
+                                let glyph_pen_y = cursor_y + glyph.y_offset;
+                                let glyph_id = glyph.glyph_id;
+                                let cached = glyph_cache.get_or_rasterize(
+                                    font_data,
+                                    glyph_id,
+                                    scale.y,
+                                    glyph_pen_x,
+                                    glyph_pen_y,
+                                    |canonical_x, canonical_y| {
+                                        let positioned = font
+                                            .glyph(rusttype::GlyphId(glyph_id))
+                                            .scaled(scale)
+                                            .positioned(rusttype::point(canonical_x, canonical_y));
+                                        let bb = positioned.pixel_bounding_box()?;
+                                        let width = (bb.max.x - bb.min.x).max(0) as u32;
+                                        let height = (bb.max.y - bb.min.y).max(0) as u32;
+                                        if width == 0 || height == 0 {
+                                            return None;
+                                        }
+                                        let mut alpha = vec![0u8; (width * height) as usize];
+                                        positioned.draw(|gx, gy, v| {
+                                            let idx = (gy * width + gx) as usize;
+                                            if idx < alpha.len() {
+                                                alpha[idx] = (v.clamp(0.0, 1.0) * 255.0) as u8;
+                                            }
+                                        });
+                                        Some(crate::render::glyph_cache::RasterizedGlyph {
+                                            width,
+                                            height,
+                                            left: bb.min.x - canonical_x.floor() as i32,
+                                            top: bb.min.y - canonical_y.round() as i32,
+                                            alpha,
+                                        })
+                                    },
+                                );
+
+                                if let Some((base_x, base_y, rasterized)) = cached {
+                                    let width = rasterized.width;
+                                    let left = rasterized.left;
+                                    let top = rasterized.top;
+
+                                    // `Clip` 模式下把这个字形的整张包围盒和裁剪矩形求交，
+                                    // 超出交集范围的像素直接跳过，交集为空就整个字形都不画
+                                    let glyph_rect = Rect::new(
+                                        (base_x + left) as f32,
+                                        (base_y + top) as f32,
+                                        rasterized.width as f32,
+                                        rasterized.height as f32,
+                                    );
+                                    let draw_bounds = match clip_rect {
+                                        Some(clip) => match glyph_rect.intersection(&clip) {
+                                            Some(r) => Some(r),
+                                            None => None,
+                                        },
+                                        None => Some(glyph_rect),
+                                    };
+
+                                    if let Some(draw_bounds) = draw_bounds {
+                                        let x_min = draw_bounds.x.floor() as i32;
+                                        let y_min = draw_bounds.y.floor() as i32;
+                                        let x_max = (draw_bounds.x + draw_bounds.width).ceil() as i32;
+                                        let y_max = (draw_bounds.y + draw_bounds.height).ceil() as i32;
+
+                                        for (idx, a) in rasterized.alpha.iter().enumerate() {
+                                            if *a == 0 {
+                                                continue;
+                                            }
+                                            let gx = (idx as u32 % width) as i32;
+                                            let gy = (idx as u32 / width) as i32;
+                                            let px = base_x + left + gx;
+                                            let py = base_y + top + gy;
+                                            if px < x_min || px >= x_max || py < y_min || py >= y_max {
+                                                continue;
+                                            }
+                                            if px >= 0 && py >= 0 {
+                                                self.blend_pixel(px as u32, py as u32, rgba, *a as f32 / 255.0);
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        cursor_x += advance;
+                        cursor_y += glyph.y_advance;
+                    }
+
+                    return shaped.total_advance;
+                }
+            }
+        }
+
+        let _ = (font_data, features, glyph_cache, clip_rect);
+        // imageproc::drawing::draw_text_mut 内部会再加一次 ascent 把 y 当作视觉框顶部处理，
+        // 这里的 pen_y 是基线，所以要先减掉 ascent 抵消掉
+        let ascent = font.v_metrics(scale).ascent;
         imageproc::drawing::draw_text_mut(
             &mut self.image,
             rgba,
-            x as i32,
-            y as i32,
+            pen_x as i32,
+            (pen_y - ascent) as i32,
             scale,
-            &font,
+            font,
             text,
         );
-        Ok(())
+        self.measure_text_width_pixels(text, font, scale)
+    }
+
+    /// 逐字形覆盖率混合到画布上的单个像素（用于 shaping 路径手动栅格化）
+    fn blend_pixel(&mut self, x: u32, y: u32, color: Rgba<u8>, coverage: f32) {
+        let (width, height) = self.image.dimensions();
+        if x >= width || y >= height || coverage <= 0.0 {
+            return;
+        }
+
+        let pixel = self.image.get_pixel_mut(x, y);
+        let alpha = coverage.clamp(0.0, 1.0) * (color[3] as f32 / 255.0);
+        let inv_alpha = 1.0 - alpha;
+
+        pixel[0] = (color[0] as f32 * alpha + pixel[0] as f32 * inv_alpha) as u8;
+        pixel[1] = (color[1] as f32 * alpha + pixel[1] as f32 * inv_alpha) as u8;
+        pixel[2] = (color[2] as f32 * alpha + pixel[2] as f32 * inv_alpha) as u8;
+        pixel[3] = ((alpha * 255.0) + pixel[3] as f32 * inv_alpha) as u8;
+    }
+
+    /// 计算一行文本的总前进宽度（像素），shaping 开启时使用真实 shaped advance。
+    /// `features` 是喂给 HarfBuzz 的 OpenType 特性开关，换行宽度估算等不依赖精确特性
+    /// 效果的调用点统一传 `&[]`，只有最终绘制前的宽度测量才传入 `TextStyle::font_features`。
+    fn shaped_text_width_pixels(
+        &self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        scale: Scale,
+        features: &[(String, u32)],
+    ) -> f32 {
+        #[cfg(feature = "shaping")]
+        {
+            let direction = crate::layout::shaping::detect_direction(text);
+            if let Ok(shaped) = crate::layout::shaping::shape_run(
+                font_data,
+                font.units_per_em() as f32,
+                scale.x,
+                text,
+                direction,
+                features,
+            ) {
+                if !shaped.glyphs.is_empty() {
+                    return shaped.total_advance;
+                }
+            }
+        }
+
+        let _ = (font_data, features);
+        self.measure_text_width_pixels(text, font, scale)
+    }
+
+    /// 朴素地测量一行文本的像素宽度（rusttype 路径，无 shaping）
+    fn measure_text_width_pixels(&self, text: &str, font: &Font, scale: Scale) -> f32 {
+        let glyphs: Vec<_> = font.layout(text, scale, rusttype::point(0.0, 0.0)).collect();
+        if glyphs.is_empty() {
+            0.0
+        } else {
+            let last_glyph = glyphs.last().unwrap();
+            last_glyph.position().x + last_glyph.unpositioned().h_metrics().advance_width
+        }
     }
 
     /// 测试用：直接绘制文本到指定位置
@@ -179,6 +876,10 @@ impl Canvas {
 
         let (pixel_x, pixel_y) = self.to_pixel_coords(Point::new(x, y));
 
+        // 同 draw_text：传入的 y 是视觉框顶部，基线 = 顶部 + ascent
+        let ascent = font.v_metrics(scale).ascent;
+        let baseline_y = pixel_y as f32 + ascent;
+
         let rgba = Rgba([color.r, color.g, color.b, color.a]);
 
         println!("[DEBUG] 直接绘制文本:");
@@ -190,20 +891,193 @@ impl Canvas {
             font_size, pixel_font_size
         );
 
-        // 使用 rusttype 和 imageproc 绘制文本
-        imageproc::drawing::draw_text_mut(
-            &mut self.image,
-            rgba,
-            pixel_x as i32,
-            pixel_y as i32,
-            scale,
-            &font,
-            text,
+        let mut glyph_cache = crate::render::glyph_cache::GlyphRasterCache::new();
+        self.draw_shaped_text(
+            text, font_data, &font, scale, pixel_x as f32, baseline_y, rgba, &[], &mut glyph_cache, None,
         );
         Ok(())
     }
 
-    /// 绘制带对齐的文本
+    /// 按字符选择合适的字体绘制一行：`primary_face` 缺字形（`.notdef`，glyph id 0）的字符
+    /// 会在 `fallback_faces` 里按顺序找第一个能显示它的字体，把连续同字体的字符合并成一段
+    /// 一起画（减少 shaping 调用次数）。和 `layout/engine.rs` 里 `measure_line_with_fallback`
+    /// 的分段策略保持一致，这样测量出来的宽度和实际画出来的字形才对得上。每个字体面的字形
+    /// 覆盖查询结果缓存在对应的 `FontFace` 里，同一段文本的多行之间共享，不用重复查 cmap。
+    fn draw_line_with_fallback(
+        &mut self,
+        line: &str,
+        primary_face: &mut FontFace,
+        fallback_faces: &mut [FontFace],
+        scale: Scale,
+        pen_x: f32,
+        pen_y: f32,
+        rgba: Rgba<u8>,
+        features: &[(String, u32)],
+        glyph_cache: &mut crate::render::glyph_cache::GlyphRasterCache,
+        clip_rect: Option<Rect>,
+    ) -> f32 {
+        if fallback_faces.is_empty() {
+            return self.draw_shaped_text(line, primary_face.data, &primary_face.font, scale, pen_x, pen_y, rgba, features, glyph_cache, clip_rect);
+        }
+
+        let mut cursor_x = pen_x;
+        let mut run = String::new();
+        let mut run_font_index: Option<usize> = None;
+
+        for c in line.chars() {
+            let char_font_index = if primary_face.covers(c) {
+                None
+            } else {
+                fallback_faces.iter_mut().position(|face| face.covers(c))
+            };
+
+            if !run.is_empty() && char_font_index != run_font_index {
+                let (data, font) = match run_font_index {
+                    None => (primary_face.data, &primary_face.font),
+                    Some(i) => (fallback_faces[i].data, &fallback_faces[i].font),
+                };
+                cursor_x += self.draw_shaped_text(&run, data, font, scale, cursor_x, pen_y, rgba, features, glyph_cache, clip_rect);
+                run.clear();
+            }
+
+            run_font_index = char_font_index;
+            run.push(c);
+        }
+
+        if !run.is_empty() {
+            let (data, font) = match run_font_index {
+                None => (primary_face.data, &primary_face.font),
+                Some(i) => (fallback_faces[i].data, &fallback_faces[i].font),
+            };
+            cursor_x += self.draw_shaped_text(&run, data, font, scale, cursor_x, pen_y, rgba, features, glyph_cache, clip_rect);
+        }
+
+        cursor_x - pen_x
+    }
+
+    /// 计算包含制表符的一行展开后的总宽度（像素）：按 `\t` 切成若干段分别测量宽度，
+    /// 每遇到一个制表符就把累积宽度推进到下一个 `tab_width_pixels` 的整数倍——即使已经
+    /// 对齐在格子上也要前进一整格，和终端/文本编辑器的制表符语义一致
+    fn line_width_with_tabs(
+        &self,
+        line: &str,
+        font_data: &[u8],
+        font: &Font,
+        scale: Scale,
+        features: &[(String, u32)],
+        tab_width_pixels: f32,
+    ) -> f32 {
+        let mut cursor = 0.0f32;
+        for (i, segment) in line.split('\t').enumerate() {
+            if i > 0 {
+                cursor = ((cursor / tab_width_pixels).floor() + 1.0) * tab_width_pixels;
+            }
+            cursor += self.shaped_text_width_pixels(segment, font_data, font, scale, features);
+        }
+        cursor
+    }
+
+    /// 按制表符切分一行并逐段绘制，段之间按 [`Canvas::line_width_with_tabs`] 同样的规则
+    /// 跳到下一个制表位；返回整行（含制表符跳格）的总前进宽度
+    fn draw_line_with_tabs(
+        &mut self,
+        line: &str,
+        primary_face: &mut FontFace,
+        fallback_faces: &mut [FontFace],
+        scale: Scale,
+        pen_x: f32,
+        pen_y: f32,
+        rgba: Rgba<u8>,
+        features: &[(String, u32)],
+        glyph_cache: &mut crate::render::glyph_cache::GlyphRasterCache,
+        clip_rect: Option<Rect>,
+        tab_width_pixels: f32,
+    ) -> f32 {
+        let mut cursor_x = pen_x;
+        for (i, segment) in line.split('\t').enumerate() {
+            if i > 0 {
+                let relative = cursor_x - pen_x;
+                let next_stop = ((relative / tab_width_pixels).floor() + 1.0) * tab_width_pixels;
+                cursor_x = pen_x + next_stop;
+            }
+            if !segment.is_empty() {
+                cursor_x += self.draw_line_with_fallback(
+                    segment, primary_face, fallback_faces, scale, cursor_x, pen_y, rgba, features, glyph_cache,
+                    clip_rect,
+                );
+            }
+        }
+        cursor_x - pen_x
+    }
+
+    /// 按 `TextAlign::Justify` 绘制一行：按空格拆成单词，把 `target_width_pixels` 减去
+    /// 单词总宽度后剩下的空间平均分摊到词间空隙；只有一个词（分不出空隙）时退化成普通
+    /// 左对齐绘制
+    fn draw_line_justified(
+        &mut self,
+        line: &str,
+        primary_face: &mut FontFace,
+        fallback_faces: &mut [FontFace],
+        scale: Scale,
+        pen_x: f32,
+        pen_y: f32,
+        rgba: Rgba<u8>,
+        features: &[(String, u32)],
+        glyph_cache: &mut crate::render::glyph_cache::GlyphRasterCache,
+        clip_rect: Option<Rect>,
+        target_width_pixels: f32,
+    ) -> f32 {
+        let words: Vec<&str> = line.split(' ').filter(|w| !w.is_empty()).collect();
+        if words.len() < 2 {
+            return self.draw_line_with_fallback(line, primary_face, fallback_faces, scale, pen_x, pen_y, rgba, features, glyph_cache, clip_rect);
+        }
+
+        let words_total_width: f32 = words
+            .iter()
+            .map(|w| self.shaped_text_width_pixels(w, primary_face.data, &primary_face.font, scale, features))
+            .sum();
+        let gap_count = words.len() - 1;
+        let extra = (target_width_pixels - words_total_width).max(0.0);
+        let gap_width = extra / gap_count as f32;
+
+        let mut cursor_x = pen_x;
+        for (i, word) in words.iter().enumerate() {
+            cursor_x += self.draw_line_with_fallback(word, primary_face, fallback_faces, scale, cursor_x, pen_y, rgba, features, glyph_cache, clip_rect);
+            if i < gap_count {
+                cursor_x += gap_width;
+            }
+        }
+        cursor_x - pen_x
+    }
+
+    /// 从 `line` 末尾逐字符回退，直到加上省略号 `…` 的宽度不超过 `max_width_pixels`；
+    /// 回退到空字符串都放不下时，直接返回单独的省略号（总得显示点什么）
+    fn truncate_with_ellipsis(
+        &self,
+        line: &str,
+        font_data: &[u8],
+        font: &Font,
+        scale: Scale,
+        features: &[(String, u32)],
+        max_width_pixels: f32,
+    ) -> String {
+        const ELLIPSIS: &str = "…";
+        let ellipsis_width = self.shaped_text_width_pixels(ELLIPSIS, font_data, font, scale, features);
+
+        let mut chars: Vec<char> = line.chars().collect();
+        loop {
+            let candidate: String = chars.iter().collect();
+            let candidate_width = self.shaped_text_width_pixels(&candidate, font_data, font, scale, features);
+            if candidate_width + ellipsis_width <= max_width_pixels || chars.is_empty() {
+                return format!("{}{}", candidate, ELLIPSIS);
+            }
+            chars.pop();
+        }
+    }
+
+    /// 绘制带对齐的文本：按 `bounds.width` 自动换行，按 `options` 纵向对齐，
+    /// 必要时按 `options.resize` 搜索一个更合适的字号，最后返回实际绘制用到的尺寸
+    /// （逻辑单位），供布局层后续复用。
     pub fn draw_text_aligned(
         &mut self,
         text: &str,
@@ -212,132 +1086,420 @@ impl Canvas {
         font_size: f32,
         color: Color,
         text_align: TextAlign,
-    ) -> Result<()> {
+        options: TextLayoutOptions,
+        fallback_fonts: &[Vec<u8>],
+        features: &[(String, u32)],
+        glyph_cache: &mut crate::render::glyph_cache::GlyphRasterCache,
+    ) -> Result<Size> {
         let font = Font::try_from_bytes(font_data)
             .ok_or_else(|| FlexRenderError::render_error("字体加载失败".to_string()))?;
 
+        let max_width_pixels = self.to_pixel_length(bounds.width);
+        let max_height_pixels = self.to_pixel_length(bounds.height);
+
+        let (resolved_font_size, mut lines) = self.resolve_font_size_and_lines(
+            text,
+            font_data,
+            &font,
+            font_size,
+            &options,
+            max_width_pixels,
+            max_height_pixels,
+        );
+
         let pixel_font_size = if self.dpi <= 1.0 {
-            font_size
+            resolved_font_size
         } else {
-            font_size * self.dpi / 72.0
+            resolved_font_size * self.dpi / 72.0
         };
         let scale = Scale::uniform(pixel_font_size);
+        let ascent = font.v_metrics(scale).ascent;
+
+        // `max_lines` 先把多出来的行砍掉，非 Visible 模式下给保留的最后一行加省略号；
+        // 再对 Ellipsis 模式下每一行做一次宽度兜底检查（正常换行不会超宽，但单字符
+        // 硬断行等边界情况可能超出一点点，用同样的回退逻辑截断）
+        if let Some(max_lines) = options.max_lines {
+            let max_lines = max_lines as usize;
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+                if max_lines > 0 && options.overflow != TextOverflow::Visible {
+                    if let Some(last) = lines.last_mut() {
+                        *last = self.truncate_with_ellipsis(last, font_data, &font, scale, features, max_width_pixels);
+                    }
+                }
+            }
+        }
+        if options.overflow == TextOverflow::Ellipsis {
+            for line in lines.iter_mut() {
+                let width = self.shaped_text_width_pixels(line, font_data, &font, scale, features);
+                if width > max_width_pixels {
+                    *line = self.truncate_with_ellipsis(line, font_data, &font, scale, features, max_width_pixels);
+                }
+            }
+        }
 
-        // 使用更准确的方法计算文本宽度
-        let glyphs: Vec<_> = font
-            .layout(text, scale, rusttype::point(0.0, 0.0))
-            .collect();
+        let line_height_pixels = self.natural_line_height_pixels(&font, scale) * options.line_spacing;
+        let total_height_pixels = line_height_pixels * lines.len() as f32;
 
-        // 计算文本的实际边界框
-        let text_width_pixels = if glyphs.is_empty() {
-            0.0
+        let block_y_pixels = match options.vertical_align {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => (max_height_pixels - total_height_pixels) / 2.0,
+            VerticalAlign::Bottom => max_height_pixels - total_height_pixels,
+        }
+        .max(0.0);
+
+        let (origin_x_pixels, origin_y_pixels) = self.to_pixel_coords(Point::new(bounds.x, bounds.y));
+        let rgba = Rgba([color.r, color.g, color.b, color.a]);
+
+        // `Clip` 模式下把边界框转成像素裁剪矩形，交给 `draw_shaped_text` 逐字形求交后再绘制
+        // （裁剪范围见那里的说明）；其余模式不设裁剪，维持现有的允许溢出行为
+        let clip_rect = if options.overflow == TextOverflow::Clip {
+            Some(Rect::new(
+                origin_x_pixels as f32,
+                origin_y_pixels as f32,
+                max_width_pixels,
+                max_height_pixels,
+            ))
         } else {
-            // 找到最右边的字符位置
-            let last_glyph = glyphs.last().unwrap();
-            let last_x = last_glyph.position().x;
-            let last_advance = last_glyph.unpositioned().h_metrics().advance_width;
-            last_x + last_advance
+            None
         };
 
-        // 将像素宽度转换为逻辑单位
-        let text_width_logical = if self.dpi <= 1.0 {
-            text_width_pixels
+        // 主字体和每个回退字体各解析一次、各自带一份覆盖缓存，在下面所有行之间共享，
+        // 避免像之前那样每画一行都重新 `Font::try_from_bytes` 一遍所有回退字体
+        let mut primary_face = FontFace::new(
+            font_data,
+            Font::try_from_bytes(font_data)
+                .ok_or_else(|| FlexRenderError::render_error("字体加载失败".to_string()))?,
+        );
+        let mut fallback_faces: Vec<FontFace> = fallback_fonts
+            .iter()
+            .filter_map(|data| Font::try_from_bytes(data).map(|font| FontFace::new(data.as_slice(), font)))
+            .collect();
+
+        let tab_width_pixels = self.to_pixel_length(options.tab_width).max(1.0);
+
+        let mut max_line_width_pixels: f32 = 0.0;
+        for (i, line) in lines.iter().enumerate() {
+            let has_tabs = line.contains('\t');
+            let line_width_pixels = if has_tabs {
+                self.line_width_with_tabs(line, font_data, &font, scale, features, tab_width_pixels)
+            } else {
+                self.shaped_text_width_pixels(line, font_data, &font, scale, features)
+            };
+            max_line_width_pixels = max_line_width_pixels.max(line_width_pixels);
+
+            let line_x_pixels = match text_align {
+                TextAlign::Left | TextAlign::Justify => 0.0,
+                TextAlign::Center => (max_width_pixels - line_width_pixels) / 2.0,
+                TextAlign::Right => max_width_pixels - line_width_pixels,
+            }
+            .max(0.0);
+
+            // 这一行的视觉框顶部，基线 = 顶部 + ascent
+            let line_top_y = origin_y_pixels as f32 + block_y_pixels + line_height_pixels * i as f32;
+            let pen_x = origin_x_pixels as f32 + line_x_pixels;
+            let pen_y = line_top_y + ascent;
+
+            // 制表符跳格和两端对齐分别需要按段/按词绘制，互斥处理；都不需要的普通行
+            // 走原来整行一次性绘制的快速路径
+            if has_tabs {
+                self.draw_line_with_tabs(
+                    line, &mut primary_face, &mut fallback_faces, scale, pen_x, pen_y, rgba, features,
+                    glyph_cache, clip_rect, tab_width_pixels,
+                );
+            } else if text_align == TextAlign::Justify {
+                self.draw_line_justified(
+                    line, &mut primary_face, &mut fallback_faces, scale, pen_x, pen_y, rgba, features,
+                    glyph_cache, clip_rect, max_width_pixels,
+                );
+            } else {
+                self.draw_line_with_fallback(
+                    line, &mut primary_face, &mut fallback_faces, scale, pen_x, pen_y, rgba, features,
+                    glyph_cache, clip_rect,
+                );
+            }
+        }
+
+        let laid_out_width_pixels = max_line_width_pixels.min(max_width_pixels);
+        Ok(Size::new(
+            self.to_logical_length(laid_out_width_pixels),
+            self.to_logical_length(total_height_pixels),
+        ))
+    }
+
+    /// 根据 `options.resize` 选出最终使用的字号并完成换行，`None` 模式下直接用请求字号换行，
+    /// `NoLarger`/`Max` 模式下在 [min, max] 区间二分搜索能放进 `bounds` 的最大字号
+    fn resolve_font_size_and_lines(
+        &self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        font_size: f32,
+        options: &TextLayoutOptions,
+        max_width_pixels: f32,
+        max_height_pixels: f32,
+    ) -> (f32, Vec<String>) {
+        match options.resize {
+            TextResize::None => {
+                let wrapped = self.layout_lines_at_size(
+                    text,
+                    font_data,
+                    font,
+                    font_size,
+                    options.line_spacing,
+                    max_width_pixels,
+                );
+                (font_size, wrapped.lines)
+            }
+            TextResize::NoLarger => {
+                let (fits, lines) = self.fits_at_size(
+                    text,
+                    font_data,
+                    font,
+                    options,
+                    font_size,
+                    max_width_pixels,
+                    max_height_pixels,
+                );
+                if fits {
+                    (font_size, lines)
+                } else {
+                    self.binary_search_font_size(
+                        text,
+                        font_data,
+                        font,
+                        options,
+                        options.min_font_size.min(font_size),
+                        font_size,
+                        max_width_pixels,
+                        max_height_pixels,
+                    )
+                }
+            }
+            TextResize::Max => self.binary_search_font_size(
+                text,
+                font_data,
+                font,
+                options,
+                options.min_font_size.min(font_size),
+                options.max_font_size.max(font_size),
+                max_width_pixels,
+                max_height_pixels,
+            ),
+        }
+    }
+
+    /// 在 `[lo, hi]` 区间二分搜索能放进边界框的最大字号；`lo` 本身都放不下时就用 `lo` 兜底
+    fn binary_search_font_size(
+        &self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        options: &TextLayoutOptions,
+        lo: f32,
+        hi: f32,
+        max_width_pixels: f32,
+        max_height_pixels: f32,
+    ) -> (f32, Vec<String>) {
+        let (lo_fits, lo_lines) =
+            self.fits_at_size(text, font_data, font, options, lo, max_width_pixels, max_height_pixels);
+        if !lo_fits || hi <= lo {
+            return (lo, lo_lines);
+        }
+
+        let mut best_size = lo;
+        let mut best_lines = lo_lines;
+        let mut low = lo;
+        let mut high = hi;
+
+        for _ in 0..12 {
+            if high - low < 0.25 {
+                break;
+            }
+            let mid = (low + high) / 2.0;
+            let (fits, lines) =
+                self.fits_at_size(text, font_data, font, options, mid, max_width_pixels, max_height_pixels);
+            if fits {
+                best_size = mid;
+                best_lines = lines;
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        (best_size, best_lines)
+    }
+
+    /// 在给定字号下换行，并判断换行结果是否能放进边界框（总高度不超、且没有任何一行超宽）
+    fn fits_at_size(
+        &self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        options: &TextLayoutOptions,
+        font_size: f32,
+        max_width_pixels: f32,
+        max_height_pixels: f32,
+    ) -> (bool, Vec<String>) {
+        let wrapped =
+            self.layout_lines_at_size(text, font_data, font, font_size, options.line_spacing, max_width_pixels);
+
+        let total_height = wrapped.line_height_pixels * wrapped.lines.len() as f32;
+        let lines_fit_width = wrapped
+            .lines
+            .iter()
+            .all(|line| self.shaped_text_width_pixels(line, font_data, font, wrapped.scale, &[]) <= max_width_pixels + 0.5);
+
+        (total_height <= max_height_pixels && lines_fit_width, wrapped.lines)
+    }
+
+    /// 在给定字号下把文本换行，返回换行结果及相关的像素度量
+    fn layout_lines_at_size(
+        &self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        font_size: f32,
+        line_spacing: f32,
+        max_width_pixels: f32,
+    ) -> WrappedText {
+        let pixel_font_size = if self.dpi <= 1.0 {
+            font_size
         } else {
-            text_width_pixels * 72.0 / self.dpi
+            font_size * self.dpi / 72.0
         };
+        let scale = Scale::uniform(pixel_font_size);
+        let lines = self.wrap_lines(text, font_data, font, scale, max_width_pixels);
 
-        // 根据对齐方式计算x坐标
-        let x = match text_align {
-            TextAlign::Left => bounds.x,
-            TextAlign::Center => bounds.x + (bounds.width - text_width_logical) / 2.0,
-            TextAlign::Right => bounds.x + bounds.width - text_width_logical,
-            TextAlign::Justify => bounds.x, // Justify按左对齐处理
-        };
+        WrappedText {
+            lines,
+            scale,
+            line_height_pixels: self.natural_line_height_pixels(font, scale) * line_spacing,
+        }
+    }
 
-        // 基线位置 - 简单的垂直居中，确保在边界框内，暂不实现
-        let y = bounds.y;
+    /// 贪心按单词换行：逐词累加，量出候选行的 shaped 宽度，一旦超出 `max_width_pixels`
+    /// 就另起一行；单个词本身已经超宽时按字符硬断行。只按普通空格拆词（而不是
+    /// `split_whitespace` 那样把所有空白都当成分隔符并丢弃），这样制表符会保留在词内或
+    /// 单独成词，交给绘制阶段的 `line_width_with_tabs`/`draw_line_with_tabs` 按制表位展开，
+    /// 这里量出来的宽度只是换行决策用的近似值
+    fn wrap_lines(
+        &self,
+        text: &str,
+        font_data: &[u8],
+        font: &Font,
+        scale: Scale,
+        max_width_pixels: f32,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for paragraph in text.split('\n') {
+            let mut current = String::new();
+
+            for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+                let word_width = self.shaped_text_width_pixels(word, font_data, font, scale, &[]);
+                if word_width > max_width_pixels {
+                    if !current.is_empty() {
+                        lines.push(std::mem::take(&mut current));
+                    }
+                    lines.extend(self.hard_break_word(word, font_data, font, scale, max_width_pixels));
+                    continue;
+                }
 
-        let (pixel_x, pixel_y) = self.to_pixel_coords(Point::new(x, y));
+                let candidate = if current.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", current, word)
+                };
+                let candidate_width = self.shaped_text_width_pixels(&candidate, font_data, font, scale, &[]);
 
-        let rgba = Rgba([color.r, color.g, color.b, color.a]);
+                if current.is_empty() || candidate_width <= max_width_pixels {
+                    current = candidate;
+                } else {
+                    lines.push(std::mem::take(&mut current));
+                    current = word.to_string();
+                }
+            }
 
-        println!("[DEBUG] Canvas绘制文本:");
-        println!("  文本: '{}'", text);
-        println!(
-            "  边界: x={}, y={}, width={}, height={}",
-            bounds.x, bounds.y, bounds.width, bounds.height
-        );
-        println!(
-            "  计算出的文本宽度: {} (像素: {})",
-            text_width_logical, text_width_pixels
-        );
-        println!("  对齐方式: {:?}", text_align);
-        println!("  最终绘制位置: x={}, y={}", x, y);
-        println!("  像素坐标: x={}, y={}", pixel_x, pixel_y);
-        println!(
-            "  字体大小: {} -> 像素字体大小: {}",
-            font_size, pixel_font_size
-        );
-        println!("  DPI: {}", self.dpi);
+            if !current.is_empty() || paragraph.is_empty() {
+                lines.push(current);
+            }
+        }
 
-        // 使用 rusttype 和 imageproc 绘制文本
-        imageproc::drawing::draw_text_mut(
-            &mut self.image,
-            rgba,
-            pixel_x as i32,
-            pixel_y as i32,
-            scale,
-            &font,
-            text,
-        );
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
 
-        // 绘制文本区域调试边界（红色实心边框）- 放在最后确保可见
-        let border_color = Color::new(255, 0, 0, 255); // 不透明红色
-        let border_width = 2.0;
-        // 上边框
-        self.fill_rect(
-            Rect::new(bounds.x, bounds.y, bounds.width, border_width),
-            border_color,
-        );
-        // 下边框
-        self.fill_rect(
-            Rect::new(
-                bounds.x,
-                bounds.y + bounds.height - border_width,
-                bounds.width,
-                border_width,
-            ),
-            border_color,
-        );
-        // 左边框
-        self.fill_rect(
-            Rect::new(bounds.x, bounds.y, border_width, bounds.height),
-            border_color,
-        );
-        // 右边框
-        self.fill_rect(
-            Rect::new(
-                bounds.x + bounds.width - border_width,
-                bounds.y,
-                border_width,
-                bounds.height,
-            ),
-            border_color,
-        );
+        lines
+    }
 
-        Ok(())
+    /// 按字符硬断行，用于单个词本身就超出 `max_width_pixels` 的情况
+    fn hard_break_word(
+        &self,
+        word: &str,
+        font_data: &[u8],
+        font: &Font,
+        scale: Scale,
+        max_width_pixels: f32,
+    ) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut current = String::new();
+
+        for ch in word.chars() {
+            let mut candidate = current.clone();
+            candidate.push(ch);
+            let candidate_width = self.shaped_text_width_pixels(&candidate, font_data, font, scale, &[]);
+
+            if current.is_empty() || candidate_width <= max_width_pixels {
+                current = candidate;
+            } else {
+                result.push(std::mem::take(&mut current));
+                current.push(ch);
+            }
+        }
+
+        if !current.is_empty() {
+            result.push(current);
+        }
+
+        result
+    }
+
+    /// 逻辑长度转像素长度（沿用 `to_pixel_coords`/`to_pixel_size` 的 DPI 缩放规则）
+    fn to_pixel_length(&self, value: f32) -> f32 {
+        if self.dpi <= 1.0 {
+            value
+        } else {
+            value * self.dpi / 72.0
+        }
+    }
+
+    /// 像素长度转逻辑长度，是 `to_pixel_length` 的反变换
+    fn to_logical_length(&self, value: f32) -> f32 {
+        if self.dpi <= 1.0 {
+            value
+        } else {
+            value * 72.0 / self.dpi
+        }
     }
 
     /// 绘制图片
+    ///
+    /// `image_src` 既可以是文件路径，也可以是 `data:` 内联图片或 `http(s)://` 远程 URL ——
+    /// 三者都交给全局 [`crate::resource::image_cache::ImageCache`] 按内容哈希去重加载
     pub fn draw_image(
         &mut self,
-        image_path: &str,
+        image_src: &str,
         dest_rect: Rect,
         object_fit: ObjectFit,
     ) -> Result<()> {
-        // 加载图片
-        let source_image = image::open(image_path)
-            .map_err(|e| FlexRenderError::render_error(format!("图片加载失败: {:?}", e)))?;
+        // 加载图片（按内容哈希缓存，文件路径/data URI/远程 URL 统一走这一条路径）
+        let source_image = {
+            let cache = crate::resource::image_cache::get_image_cache();
+            let cache = cache.lock().unwrap();
+            cache.load_any(image_src)?
+        };
 
         let source_rgba = source_image.to_rgba8();
         let (src_width, src_height) = source_rgba.dimensions();
@@ -499,6 +1661,35 @@ impl Canvas {
     }
 }
 
+impl crate::render::backend::DrawBackend for Canvas {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        Canvas::fill_rect(self, rect, color);
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, color: Color, width: f32) {
+        Canvas::stroke_rect(self, rect, color, width);
+    }
+
+    fn draw_text(
+        &mut self,
+        text: &str,
+        position: Point,
+        font_data: &[u8],
+        font_size: f32,
+        color: Color,
+    ) -> Result<()> {
+        Canvas::draw_text(self, text, position, font_data, font_size, color)
+    }
+
+    fn draw_image(&mut self, image_src: &str, dest_rect: Rect, object_fit: ObjectFit) -> Result<()> {
+        Canvas::draw_image(self, image_src, dest_rect, object_fit)
+    }
+
+    fn blend_image(&mut self, source: &RgbaImage, position: Point) {
+        Canvas::blend_image(self, source, position)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,4 +1728,44 @@ mod tests {
         assert_eq!(pixel[1], 0); // 绿色
         assert_eq!(pixel[2], 0); // 蓝色
     }
+
+    #[test]
+    fn test_text_layout_options_default() {
+        let options = TextLayoutOptions::default();
+        assert_eq!(options.line_spacing, 1.2);
+        assert_eq!(options.vertical_align, VerticalAlign::Top);
+        assert_eq!(options.resize, TextResize::None);
+    }
+
+    #[test]
+    fn test_fill_round_rect_center_is_opaque() {
+        let mut canvas = Canvas::new(Size::new(100.0, 100.0), Color::white(), 72.0);
+
+        canvas.fill_round_rect(Rect::new(10.0, 10.0, 50.0, 50.0), 12.0, Color::red());
+
+        let pixel = canvas.image.get_pixel(35, 35);
+        assert_eq!(pixel[0], 255);
+        assert_eq!(pixel[1], 0);
+        assert_eq!(pixel[2], 0);
+    }
+
+    #[test]
+    fn test_fill_round_rect_outer_corner_stays_background() {
+        let mut canvas = Canvas::new(Size::new(100.0, 100.0), Color::white(), 72.0);
+
+        canvas.fill_round_rect(Rect::new(10.0, 10.0, 50.0, 50.0), 12.0, Color::red());
+
+        // 圆角方块外侧最靠角的像素应该保留背景色，没有被方角矩形的覆盖率测试误盖住
+        let pixel = canvas.image.get_pixel(10, 10);
+        assert_eq!(pixel[0], 255);
+        assert_eq!(pixel[1], 255);
+        assert_eq!(pixel[2], 255);
+    }
+
+    #[test]
+    fn test_rounded_rect_sdf_zero_radius_matches_plain_rect() {
+        // 半径为 0 时退化成普通矩形的有符号距离场：边界上距离应为 0
+        let dist = Canvas::rounded_rect_sdf(0.0, 50.0, 100.0, 100.0, 0.0);
+        assert!((dist - 0.0).abs() < 0.5);
+    }
 }
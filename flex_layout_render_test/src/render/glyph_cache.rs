@@ -0,0 +1,246 @@
+//! 字形栅格化缓存
+//!
+//! 文字量大的模板里，同一个字形（同一个字体、字号）在不同帧甚至同一帧内会被反复
+//! 栅格化，这是 CPU 画布路径上文字渲染的主要开销。`GlyphRasterCache` 按
+//! `(字体, glyph id, 量化后的像素字号, 子像素相位)` 缓存栅格化出来的 8-bit alpha
+//! 覆盖率位图，命中时直接按画笔位置把缓存的蒙版和目标颜色混合，省掉重新栅格化的开销；
+//! 这和 GPU 文字渲染里常见的字形图集缓存思路一致，只是这里目标是 CPU `Canvas`。
+//!
+//! 字形的外形在 x 方向的小数位置上其实是会变的（`rusttype` 把笔位置的小数部分直接烘焙进
+//! 轮廓再栅格化，为的是保留水平 hinting），所以缓存 key 里按 [`SUBPIXEL_PHASES`] 档量化
+//! x 方向的小数部分，在不让缓存条目数爆炸的前提下尽量保留清晰度；y 方向没有这个量化，
+//! 统一按四舍五入取整处理。
+
+use std::collections::HashMap;
+
+/// x 方向小数位置量化的档位数：0、0.25、0.5、0.75 四档
+const SUBPIXEL_PHASES: u8 = 4;
+
+/// 缓存的默认字节预算：每个条目按 alpha 位图字节数加上 `RasterizedGlyph` 结构体本身的大小计，
+/// 不含 key 和 HashMap 的开销
+const DEFAULT_BYTE_BUDGET: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    /// 用字体数据的起始地址当作身份标识：同一份字体数据在一次渲染过程中地址不变，
+    /// 避免每次都去哈希整份字体文件的字节内容
+    font_data_ptr: usize,
+    glyph_id: u16,
+    /// 像素字号 * 64 四舍五入取整，避免浮点字号做 key 时的精度抖动
+    size_quantized: u32,
+    subpixel_phase: u8,
+}
+
+/// 一个字形栅格化出来的覆盖率位图，`left`/`top` 是相对画笔位置取整后的偏移
+#[derive(Debug, Clone)]
+pub struct RasterizedGlyph {
+    pub width: u32,
+    pub height: u32,
+    pub left: i32,
+    pub top: i32,
+    pub alpha: Vec<u8>,
+}
+
+impl RasterizedGlyph {
+    fn byte_size(&self) -> usize {
+        self.alpha.len() + std::mem::size_of::<Self>()
+    }
+}
+
+struct CacheEntry {
+    glyph: RasterizedGlyph,
+    last_access: u64,
+}
+
+/// 命中/字节占用统计，供 [`crate::render::renderer::Renderer::glyph_cache_stats`] 暴露给调用方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlyphCacheStats {
+    pub entries: usize,
+    pub bytes_used: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// 按字节预算做 LRU 淘汰的字形栅格化缓存
+pub struct GlyphRasterCache {
+    entries: HashMap<GlyphKey, CacheEntry>,
+    access_counter: u64,
+    byte_budget: usize,
+    bytes_used: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl GlyphRasterCache {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            access_counter: 0,
+            byte_budget: DEFAULT_BYTE_BUDGET,
+            bytes_used: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// 清空所有缓存条目，同时重置命中率统计
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes_used = 0;
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub fn stats(&self) -> GlyphCacheStats {
+        GlyphCacheStats {
+            entries: self.entries.len(),
+            bytes_used: self.bytes_used,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+
+    /// 把像素字号量化成缓存 key：乘 64 取整，和字体行业里常见的 26.6 定点字号精度一致
+    fn quantize_size(pixel_size: f32) -> u32 {
+        (pixel_size * 64.0).round().max(0.0) as u32
+    }
+
+    /// 把画笔 x 坐标的小数部分量化成 [`SUBPIXEL_PHASES`] 档之一，返回该档位代表的小数值
+    fn quantize_subpixel(pen_x: f32) -> (u8, f32) {
+        let fraction = pen_x - pen_x.floor();
+        let phase = ((fraction * SUBPIXEL_PHASES as f32).floor() as u8).min(SUBPIXEL_PHASES - 1);
+        (phase, phase as f32 / SUBPIXEL_PHASES as f32)
+    }
+
+    /// 取字形 `glyph_id` 在 `(font_data, pixel_size)` 下、画笔位置 `(pen_x, pen_y)` 处的栅格化
+    /// 位图；命中直接返回缓存条目，未命中调用 `rasterize` 生成后插入缓存再返回。
+    ///
+    /// `rasterize` 接收量化后的画笔位置（整数部分 + 量化后的子像素相位，y 取整），
+    /// 必须按这个位置栅格化，这样同一个 key 下不同调用产生的位图才是等价的。
+    pub fn get_or_rasterize(
+        &mut self,
+        font_data: &[u8],
+        glyph_id: u16,
+        pixel_size: f32,
+        pen_x: f32,
+        pen_y: f32,
+        rasterize: impl FnOnce(f32, f32) -> Option<RasterizedGlyph>,
+    ) -> Option<(i32, i32, &RasterizedGlyph)> {
+        let (phase, phase_fraction) = Self::quantize_subpixel(pen_x);
+        let floor_x = pen_x.floor();
+        let round_y = pen_y.round();
+
+        let key = GlyphKey {
+            font_data_ptr: font_data.as_ptr() as usize,
+            glyph_id,
+            size_quantized: Self::quantize_size(pixel_size),
+            subpixel_phase: phase,
+        };
+
+        self.access_counter += 1;
+        let access = self.access_counter;
+
+        if self.entries.contains_key(&key) {
+            self.hits += 1;
+            let entry = self.entries.get_mut(&key).unwrap();
+            entry.last_access = access;
+            return Some((floor_x as i32, round_y as i32, &entry.glyph));
+        }
+
+        self.misses += 1;
+        let glyph = rasterize(floor_x + phase_fraction, round_y)?;
+        self.insert(key, glyph, access);
+        self.entries.get(&key).map(|entry| (floor_x as i32, round_y as i32, &entry.glyph))
+    }
+
+    fn insert(&mut self, key: GlyphKey, glyph: RasterizedGlyph, access: u64) {
+        let incoming_size = glyph.byte_size();
+        while self.bytes_used + incoming_size > self.byte_budget && !self.entries.is_empty() {
+            self.evict_least_recently_used();
+        }
+        self.bytes_used += incoming_size;
+        self.entries.insert(key, CacheEntry { glyph, last_access: access });
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let Some(victim) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_access)
+            .map(|(key, _)| *key)
+        else {
+            return;
+        };
+        if let Some(entry) = self.entries.remove(&victim) {
+            self.bytes_used = self.bytes_used.saturating_sub(entry.glyph.byte_size());
+        }
+    }
+}
+
+impl Default for GlyphRasterCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy_glyph(width: u32, height: u32) -> RasterizedGlyph {
+        RasterizedGlyph {
+            width,
+            height,
+            left: 0,
+            top: 0,
+            alpha: vec![255; (width * height) as usize],
+        }
+    }
+
+    #[test]
+    fn caches_hit_on_same_key() {
+        let mut cache = GlyphRasterCache::new();
+        let font_data = [0u8; 4];
+
+        let calls = std::cell::Cell::new(0);
+        for _ in 0..3 {
+            cache.get_or_rasterize(&font_data, 1, 16.0, 10.2, 5.0, |_, _| {
+                calls.set(calls.get() + 1);
+                Some(dummy_glyph(4, 4))
+            });
+        }
+
+        assert_eq!(calls.get(), 1);
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn evicts_when_over_budget() {
+        let mut cache = GlyphRasterCache::new();
+        cache.byte_budget = 64;
+        let font_data = [0u8; 4];
+
+        for glyph_id in 0..10u16 {
+            cache.get_or_rasterize(&font_data, glyph_id, 16.0, 0.0, 0.0, |_, _| Some(dummy_glyph(4, 4)));
+        }
+
+        assert!(cache.stats().bytes_used <= 64);
+        assert!(cache.stats().entries < 10);
+    }
+
+    #[test]
+    fn clear_resets_stats() {
+        let mut cache = GlyphRasterCache::new();
+        let font_data = [0u8; 4];
+        cache.get_or_rasterize(&font_data, 1, 16.0, 0.0, 0.0, |_, _| Some(dummy_glyph(2, 2)));
+        cache.clear();
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.bytes_used, 0);
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.misses, 0);
+    }
+}
@@ -4,7 +4,15 @@
 
 pub mod canvas;
 pub mod renderer;
+pub mod color_glyph;
+pub mod glyph_cache;
+pub mod backend;
+pub mod svg_backend;
 
 // 重新导出主要类型
 pub use canvas::*;
-pub use renderer::*;
\ No newline at end of file
+pub use renderer::*;
+pub use color_glyph::*;
+pub use glyph_cache::*;
+pub use backend::*;
+pub use svg_backend::*;
\ No newline at end of file
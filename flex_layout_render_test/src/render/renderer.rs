@@ -14,6 +14,13 @@ pub struct Renderer {
     font_cache: HashMap<String, Vec<u8>>,
     /// 默认字体数据
     default_font: Vec<u8>,
+    /// 渲染器级别的默认回退字体家族：没有在 `TextStyle::font_fallbacks` 里单独声明
+    /// 回退字体的文本节点也会用它们兜底（比如项目统一要求的 CJK/emoji 后备字体）
+    fallback_fonts: Vec<String>,
+    /// 是否在渲染每个节点时打印调试信息；默认关闭，排查布局/样式问题时用 [`Renderer::set_debug`] 打开
+    debug: bool,
+    /// 字形栅格化缓存，跨多次 `render` 调用复用，避免文本量大的模板每帧都重新栅格化同一个字形
+    glyph_cache: crate::render::glyph_cache::GlyphRasterCache,
 }
 
 impl Renderer {
@@ -21,12 +28,25 @@ impl Renderer {
     pub fn new() -> Result<Self> {
         // 加载默认字体（这里使用一个简单的实现）
         let default_font = Self::load_default_font()?;
-        
+
         Ok(Self {
             font_cache: HashMap::new(),
             default_font,
+            fallback_fonts: Vec::new(),
+            debug: false,
+            glyph_cache: crate::render::glyph_cache::GlyphRasterCache::new(),
         })
     }
+
+    /// 设置渲染器级别的默认回退字体家族，会追加在每个文本节点自己的 `font_fallbacks` 之后
+    pub fn set_fallback_fonts(&mut self, fallbacks: &[String]) {
+        self.fallback_fonts = fallbacks.to_vec();
+    }
+
+    /// 打开/关闭每个节点渲染时的 `[DEBUG]` 信息打印，默认关闭
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
     
     /// 渲染布局结果到画布
     pub fn render(
@@ -34,33 +54,65 @@ impl Renderer {
         layout_result: &LayoutResult,
         canvas: &mut Canvas,
     ) -> Result<()> {
-        self.render_node(layout_result, canvas, Point::new(0.0, 0.0))
+        self.render_node(layout_result, canvas, Point::new(0.0, 0.0), None)
     }
-    
-    /// 递归渲染节点
+
+    /// 计算容器的裁剪矩形：在容器解析后的边框盒基础上扣掉 `border_width`，
+    /// 只在声明了裁剪的轴上收紧范围，另一轴保持不限制。两个轴都是 `Visible` 时返回 `None`
+    fn container_clip_rect(bounds: Rect, style: &crate::layout::ContainerStyle) -> Option<Rect> {
+        if !style.overflow_x.clips() && !style.overflow_y.clips() {
+            return None;
+        }
+
+        let inset = style.border_width.max(0.0);
+        let (x, width) = if style.overflow_x.clips() {
+            (bounds.x + inset, (bounds.width - 2.0 * inset).max(0.0))
+        } else {
+            (f32::MIN / 2.0, f32::MAX)
+        };
+        let (y, height) = if style.overflow_y.clips() {
+            (bounds.y + inset, (bounds.height - 2.0 * inset).max(0.0))
+        } else {
+            (f32::MIN / 2.0, f32::MAX)
+        };
+
+        Some(Rect::new(x, y, width, height))
+    }
+
+    /// 递归渲染节点。`clip` 是从祖先容器累积下来的裁剪矩形：子树完全落在裁剪区域外时
+    /// 整个跳过不画，这是包围盒层面的裁剪——受限于 `Canvas` 没有逐像素的裁剪/蒙版原语，
+    /// 部分与裁剪区域重叠的文本/图片节点目前仍按完整尺寸绘制
     fn render_node(
         &mut self,
         layout_result: &LayoutResult,
         canvas: &mut Canvas,
         parent_offset: Point,
+        clip: Option<Rect>,
     ) -> Result<()> {
         let absolute_position = Point::new(
             parent_offset.x + layout_result.layout.location.x,
             parent_offset.y + layout_result.layout.location.y,
         );
-        
+
         let size = Size::new(
             layout_result.layout.size.width,
             layout_result.layout.size.height,
         );
-        
+
         let bounds = Rect::new(
             absolute_position.x,
             absolute_position.y,
             size.width,
             size.height,
         );
-        
+
+        // 祖先的裁剪区域已经完全遮住了这个节点，整个子树都不需要绘制
+        if let Some(clip_rect) = clip {
+            if bounds.intersection(&clip_rect).is_none() {
+                return Ok(());
+            }
+        }
+
         // 根据节点类型进行渲染
         match &layout_result.node {
             LayoutNode::Container { style, .. } => {
@@ -72,13 +124,32 @@ impl Renderer {
             LayoutNode::Image { src, style } => {
                 self.render_image(canvas, bounds, src, style)?;
             },
+            // Spacer 不渲染任何内容，只在布局阶段占据空间
+            LayoutNode::Spacer { .. } => {},
         }
-        
+
+        // 容器声明了裁剪的话，子节点要同时受它自身的裁剪矩形和祖先裁剪矩形约束
+        let child_clip = match &layout_result.node {
+            LayoutNode::Container { style, .. } => {
+                match (clip, Self::container_clip_rect(bounds, style)) {
+                    // 两个裁剪矩形不相交时退化成一个零面积矩形，而不是 `None`（不限制），
+                    // 否则子节点会在既不属于祖先裁剪区域、也不属于本容器内容区域时仍被画出来
+                    (Some(ancestor), Some(own)) => {
+                        Some(ancestor.intersection(&own).unwrap_or(Rect::new(own.x, own.y, 0.0, 0.0)))
+                    }
+                    (Some(ancestor), None) => Some(ancestor),
+                    (None, Some(own)) => Some(own),
+                    (None, None) => None,
+                }
+            }
+            _ => clip,
+        };
+
         // 递归渲染子节点
         for child in &layout_result.children {
-            self.render_node(child, canvas, absolute_position)?;
+            self.render_node(child, canvas, absolute_position, child_clip)?;
         }
-        
+
         Ok(())
     }
     
@@ -89,41 +160,54 @@ impl Renderer {
         bounds: Rect,
         style: &crate::layout::ContainerStyle,
     ) -> Result<()> {
-        // 输出容器调试信息
-        println!("[DEBUG] 容器渲染调试信息:");
-        println!("  边界: x={}, y={}, width={}, height={}", bounds.x, bounds.y, bounds.width, bounds.height);
-        println!("  背景色: {:?}", style.background);
-        println!("  边框颜色: {:?}", style.border_color);
-        println!("  边框宽度: {}", style.border_width);
-        println!("  边框圆角: {}", style.border_radius);
-        
-        // 绘制背景
-        if let Some(background) = style.background {
-            canvas.fill_rect(bounds, background);
+        if self.debug {
+            println!("[DEBUG] 容器渲染调试信息:");
+            println!("  边界: x={}, y={}, width={}, height={}", bounds.x, bounds.y, bounds.width, bounds.height);
+            println!("  背景色: {:?}", style.background);
+            println!("  边框颜色: {:?}", style.border_color);
+            println!("  边框宽度: {}", style.border_width);
+            println!("  边框圆角: {}", style.border_radius);
         }
-        
+
+        // 投影阴影画在容器本体之下
+        if style.shadow_blur > 0.0 || style.shadow_spread != 0.0
+            || style.shadow_offset.x != 0.0 || style.shadow_offset.y != 0.0
+        {
+            canvas.draw_shadow(
+                bounds,
+                style.border_radius,
+                style.shadow_color,
+                style.shadow_blur,
+                style.shadow_spread,
+                style.shadow_offset,
+            );
+        }
+
+        // 绘制背景：纯色走原来的 fill_rect/fill_round_rect，渐变走 fill_gradient
+        if let Some(background) = &style.background {
+            match background {
+                crate::types::Background::Color(color) => {
+                    if style.border_radius > 0.0 {
+                        canvas.fill_round_rect(bounds, style.border_radius, *color);
+                    } else {
+                        canvas.fill_rect(bounds, *color);
+                    }
+                }
+                crate::types::Background::Gradient(gradient) => {
+                    canvas.fill_gradient(bounds, style.border_radius, gradient);
+                }
+            }
+        }
+
         // 绘制边框
         if style.border_width > 0.0 {
-            canvas.stroke_rect(bounds, style.border_color, style.border_width);
-        }
-        
-        // 绘制容器调试边界（蓝色实心边框）- 放在最后确保可见
-        let container_border_color = crate::types::Color::new(0, 0, 255, 255); // 不透明蓝色
-        let border_width = 2.0;
-        // 上边框
-        canvas.fill_rect(crate::types::Rect::new(bounds.x, bounds.y, bounds.width, border_width), container_border_color);
-        // 下边框
-        canvas.fill_rect(crate::types::Rect::new(bounds.x, bounds.y + bounds.height - border_width, bounds.width, border_width), container_border_color);
-        // 左边框
-        canvas.fill_rect(crate::types::Rect::new(bounds.x, bounds.y, border_width, bounds.height), container_border_color);
-        // 右边框
-        canvas.fill_rect(crate::types::Rect::new(bounds.x + bounds.width - border_width, bounds.y, border_width, bounds.height), container_border_color);
-        
-        // TODO: 实现圆角边框
-        if style.border_radius > 0.0 {
-            // 圆角边框的实现比较复杂，这里先跳过
+            if style.border_radius > 0.0 {
+                canvas.stroke_round_rect(bounds, style.border_radius, style.border_color, style.border_width);
+            } else {
+                canvas.stroke_rect(bounds, style.border_color, style.border_width);
+            }
         }
-        
+
         Ok(())
     }
     
@@ -135,28 +219,53 @@ impl Renderer {
         content: &str,
         style: &crate::layout::TextStyle,
     ) -> Result<()> {
-        // 输出调试信息
-        println!("[DEBUG] 文本渲染调试信息:");
-        println!("  内容: '{}'", content);
-        println!("  边界: x={}, y={}, width={}, height={}", bounds.x, bounds.y, bounds.width, bounds.height);
-        println!("  字体大小: {}", style.font_size);
-        println!("  文本对齐: {:?}", style.text_align);
-        println!("  字体家族: {}", style.font_family);
-        println!("  颜色: {:?}", style.color);
-        
+        if self.debug {
+            println!("[DEBUG] 文本渲染调试信息:");
+            println!("  内容: '{}'", content);
+            println!("  边界: x={}, y={}, width={}, height={}", bounds.x, bounds.y, bounds.width, bounds.height);
+            println!("  字体大小: {}", style.font_size);
+            println!("  文本对齐: {:?}", style.text_align);
+            println!("  字体家族: {}", style.font_family);
+            println!("  颜色: {:?}", style.color);
+        }
+
         // 获取字体数据
         let font_data = self.get_font_data(&style.font_family)?.to_vec();
-        
-        // 使用新的对齐绘制方法
-        canvas.draw_text_aligned(
+        let fallback_families = self.resolve_fallback_families(style);
+        let fallback_fonts = self.get_fallback_font_data(&fallback_families);
+
+        let layout_options = crate::render::TextLayoutOptions {
+            line_spacing: style.line_height,
+            vertical_align: style.vertical_align,
+            resize: style.resize,
+            min_font_size: style.min_font_size,
+            max_font_size: style.max_font_size,
+            tab_width: style.tab_width,
+            overflow: style.overflow,
+            max_lines: style.max_lines,
+        };
+
+        // 使用新的对齐绘制方法；返回的实际排版尺寸目前仅供调试参考，
+        // 后续可以反馈给布局层做二次测量
+        let laid_out_size = canvas.draw_text_aligned(
             content,
             bounds,
             &font_data,
             style.font_size,
             style.color,
             style.text_align,
+            layout_options,
+            &fallback_fonts,
+            &style.font_features,
+            &mut self.glyph_cache,
         )?;
-        
+        if self.debug {
+            println!(
+                "  实际排版尺寸: width={}, height={}",
+                laid_out_size.width, laid_out_size.height
+            );
+        }
+
         Ok(())
     }
     
@@ -196,7 +305,29 @@ impl Renderer {
         
         Ok(self.font_cache.get(font_family).unwrap_or(&self.default_font))
     }
-    
+
+    /// 把文本节点自己声明的 `font_fallbacks` 和渲染器级别的默认回退字体（[`Renderer::set_fallback_fonts`]）
+    /// 按顺序拼成一条完整的回退链，跳过和主字体或前面已经出现过的家族重复的条目
+    fn resolve_fallback_families(&self, style: &crate::layout::TextStyle) -> Vec<String> {
+        let mut families = Vec::new();
+        for family in style.font_fallbacks.iter().chain(self.fallback_fonts.iter()) {
+            if family != &style.font_family && !families.contains(family) {
+                families.push(family.clone());
+            }
+        }
+        families
+    }
+
+    /// 解析 `font_fallbacks` 列表里每个备用字体家族的字节数据，跳过加载失败的家族。
+    /// 绘制时主字体缺字形（CJK 落到纯拉丁字体上这类情况）会按顺序在这些字体里找
+    /// 第一个能显示该字符的，实现 mixed-script 文本端到端渲染。
+    fn get_fallback_font_data(&mut self, font_fallbacks: &[String]) -> Vec<Vec<u8>> {
+        font_fallbacks
+            .iter()
+            .filter_map(|family| self.get_font_data(family).ok().map(|data| data.to_vec()))
+            .collect()
+    }
+
     /// 加载字体文件
     fn load_font(&self, font_family: &str) -> Result<Vec<u8>> {
         // 常见字体路径映射
@@ -262,6 +393,89 @@ impl Renderer {
     pub fn cached_font_count(&self) -> usize {
         self.font_cache.len()
     }
+
+    /// 获取字形栅格化缓存的命中率/字节占用统计
+    pub fn glyph_cache_stats(&self) -> crate::render::glyph_cache::GlyphCacheStats {
+        self.glyph_cache.stats()
+    }
+
+    /// 清空字形栅格化缓存
+    pub fn clear_glyph_cache(&mut self) {
+        self.glyph_cache.clear();
+    }
+
+    /// 渲染布局结果到任意 `DrawBackend`，同一份布局结果既能喂给 `Canvas` 生成位图，
+    /// 也能喂给 `SvgBackend` 生成矢量文档。受 trait 表达能力限制，文本在这条路径上
+    /// 只按单行绘制，不做 `draw_text_aligned` 那样的自动换行/自适应缩放——
+    /// 栅格路径仍然走 `render`，不受影响。
+    pub fn render_to_backend<B: crate::render::backend::DrawBackend>(
+        &mut self,
+        layout_result: &LayoutResult,
+        backend: &mut B,
+    ) -> Result<()> {
+        self.render_node_to_backend(layout_result, backend, Point::new(0.0, 0.0))
+    }
+
+    fn render_node_to_backend<B: crate::render::backend::DrawBackend>(
+        &mut self,
+        layout_result: &LayoutResult,
+        backend: &mut B,
+        parent_offset: Point,
+    ) -> Result<()> {
+        let absolute_position = Point::new(
+            parent_offset.x + layout_result.layout.location.x,
+            parent_offset.y + layout_result.layout.location.y,
+        );
+
+        let size = Size::new(
+            layout_result.layout.size.width,
+            layout_result.layout.size.height,
+        );
+
+        let bounds = Rect::new(
+            absolute_position.x,
+            absolute_position.y,
+            size.width,
+            size.height,
+        );
+
+        match &layout_result.node {
+            LayoutNode::Container { style, .. } => {
+                // `DrawBackend` trait 没有渐变填充原语，
+                // 渐变背景退化为取第一个停止点的纯色，保证矢量导出至少不丢背景
+                if let Some(background) = &style.background {
+                    let color = match background {
+                        crate::types::Background::Color(color) => *color,
+                        crate::types::Background::Gradient(gradient) => gradient.first_stop_color(),
+                    };
+                    backend.fill_rect(bounds, color);
+                }
+                if style.border_width > 0.0 {
+                    backend.stroke_rect(bounds, style.border_color, style.border_width);
+                }
+            }
+            LayoutNode::Text { content, style } => {
+                let font_data = self.get_font_data(&style.font_family)?.to_vec();
+                backend.draw_text(
+                    content,
+                    Point::new(bounds.x, bounds.y),
+                    &font_data,
+                    style.font_size,
+                    style.color,
+                )?;
+            }
+            LayoutNode::Image { src, style } => {
+                backend.draw_image(src, bounds, style.object_fit)?;
+            }
+            LayoutNode::Spacer { .. } => {}
+        }
+
+        for child in &layout_result.children {
+            self.render_node_to_backend(child, backend, absolute_position)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Renderer {
@@ -269,6 +483,9 @@ impl Default for Renderer {
         Self::new().unwrap_or_else(|_| Self {
             font_cache: HashMap::new(),
             default_font: vec![0; 1024], // 占位符
+            fallback_fonts: Vec::new(),
+            debug: false,
+            glyph_cache: crate::render::glyph_cache::GlyphRasterCache::new(),
         })
     }
 }
@@ -0,0 +1,168 @@
+//! SVG 矢量输出后端
+//!
+//! 把与 `Canvas` 完全相同的绘制原语落地成一份 SVG 文档，而不是位图，用于需要
+//! 分辨率无关、文字可选中输出的场景（比如前端直接内联展示，或者印刷场景的二次编辑）。
+
+use crate::error::Result;
+use crate::render::backend::DrawBackend;
+use crate::types::{Color, ObjectFit, Point, Rect, Size};
+use image::RgbaImage;
+use std::fmt::Write as _;
+
+/// 把每次绘制调用追加成一个 SVG 元素，最后通过 `into_svg` 拼出完整文档
+pub struct SvgBackend {
+    size: Size,
+    elements: String,
+}
+
+impl SvgBackend {
+    /// 创建一个指定画布尺寸（逻辑单位）的空白 SVG 后端
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            elements: String::new(),
+        }
+    }
+
+    /// 拼出完整的 SVG 文档
+    pub fn into_svg(self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n{}</svg>\n",
+            self.size.width, self.size.height, self.size.width, self.size.height, self.elements
+        )
+    }
+
+    fn color_to_attr(color: Color) -> String {
+        format!(
+            "rgba({},{},{},{})",
+            color.r,
+            color.g,
+            color.b,
+            color.a as f32 / 255.0
+        )
+    }
+}
+
+impl DrawBackend for SvgBackend {
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let _ = writeln!(
+            self.elements,
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" />",
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            Self::color_to_attr(color)
+        );
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, color: Color, width: f32) {
+        let _ = writeln!(
+            self.elements,
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" />",
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            Self::color_to_attr(color),
+            width
+        );
+    }
+
+    fn draw_text(
+        &mut self,
+        text: &str,
+        position: Point,
+        _font_data: &[u8],
+        font_size: f32,
+        color: Color,
+    ) -> Result<()> {
+        // SVG 原生支持文字渲染，不需要自己栅格化；position 是视觉框顶部，
+        // 用 0.8 * font_size 近似 ascent 把它换算成基线，和 Canvas 的约定保持一致
+        let baseline_y = position.y + font_size * 0.8;
+        let _ = writeln!(
+            self.elements,
+            "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\">{}</text>",
+            position.x,
+            baseline_y,
+            font_size,
+            Self::color_to_attr(color),
+            escape_xml(text)
+        );
+        Ok(())
+    }
+
+    fn draw_image(&mut self, image_src: &str, dest_rect: Rect, _object_fit: ObjectFit) -> Result<()> {
+        // object_fit 的精细拉伸/裁切逻辑留给光栅路径；SVG 这里直接引用原图并铺满目标区域。
+        // `href` 原生支持文件路径、data URI 和 http(s) URL，三者都可以直接透传
+        let _ = writeln!(
+            self.elements,
+            "  <image href=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" />",
+            escape_xml(image_src),
+            dest_rect.x,
+            dest_rect.y,
+            dest_rect.width,
+            dest_rect.height
+        );
+        Ok(())
+    }
+
+    fn blend_image(&mut self, source: &RgbaImage, position: Point) {
+        // 彩色字形位图等场景：编码成 PNG data URI 内嵌进文档
+        let mut png_bytes: Vec<u8> = Vec::new();
+        let dynamic = image::DynamicImage::ImageRgba8(source.clone());
+        if dynamic
+            .write_to(&mut std::io::Cursor::new(&mut png_bytes), image::ImageOutputFormat::Png)
+            .is_ok()
+        {
+            let encoded = base64::encode(&png_bytes);
+            let _ = writeln!(
+                self.elements,
+                "  <image href=\"data:image/png;base64,{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" />",
+                encoded,
+                position.x,
+                position.y,
+                source.width(),
+                source.height()
+            );
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_backend_emits_rect() {
+        let mut backend = SvgBackend::new(Size::new(100.0, 100.0));
+        backend.fill_rect(Rect::new(0.0, 0.0, 50.0, 50.0), Color::red());
+
+        let svg = backend.into_svg();
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("<rect"));
+        assert!(svg.contains("width=\"50\""));
+    }
+
+    #[test]
+    fn test_svg_backend_escapes_text() {
+        let mut backend = SvgBackend::new(Size::new(100.0, 100.0));
+        backend
+            .draw_text("<a & b>", Point::new(0.0, 0.0), &[], 16.0, Color::black())
+            .unwrap();
+
+        let svg = backend.into_svg();
+        assert!(svg.contains("&lt;a &amp; b&gt;"));
+    }
+}
@@ -7,6 +7,14 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+/// 字体缓存条目的唯一标识，目前就是 [`FontManager::select_font`]/[`FontManager::load_font_info`]
+/// 内部使用的缓存 key（家族名，或者按粗细/样式精确匹配时的 "family-weight-style" key）。
+/// 调用方不需要关心内部格式，只需要把它原样传回 [`FontManager::glyph_or_fallback`] 之类的方法。
+pub type FontId = String;
+
+/// rusttype 的字形索引，`0` 代表 `.notdef`（字体里没有这个字符的字形）
+pub type GlyphId = u16;
+
 /// 字体信息
 #[derive(Debug, Clone)]
 pub struct FontInfo {
@@ -20,6 +28,72 @@ pub struct FontInfo {
     pub size: usize,
 }
 
+/// 字体样式（对应 CSS `font-style`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl Default for FontStyle {
+    fn default() -> Self {
+        FontStyle::Normal
+    }
+}
+
+/// 查询系统字体时使用的粗细、样式与拉伸属性
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontProperties {
+    /// 粗细 (100-900)，对应 CSS `font-weight`
+    pub weight: u16,
+    /// 样式：正常、斜体或倾斜体
+    pub style: FontStyle,
+    /// 拉伸比例，1.0 为标准宽度
+    pub stretch: f32,
+}
+
+impl Default for FontProperties {
+    fn default() -> Self {
+        Self {
+            weight: 400,
+            style: FontStyle::Normal,
+            stretch: 1.0,
+        }
+    }
+}
+
+/// 字体组：按优先级持有主字体、回退字体和默认字体，支持逐字符选择合适的字体
+#[derive(Debug, Clone)]
+pub struct FontGroup {
+    /// 按优先级排列的候选字体
+    pub fonts: Vec<FontInfo>,
+}
+
+impl FontGroup {
+    /// 创建字体组
+    pub fn new(fonts: Vec<FontInfo>) -> Self {
+        Self { fonts }
+    }
+
+    /// 查找能够显示给定字符的第一个字体
+    ///
+    /// 通过检查字体 cmap 中该码点是否解析为非 `.notdef`（glyph id 为 0）的字形来判断覆盖范围，
+    /// 找不到覆盖该字符的字体时返回 `None`，由调用方决定是否退回主字体。
+    pub fn font_for_char(&self, c: char) -> Option<&FontInfo> {
+        self.fonts.iter().find(|info| {
+            rusttype::Font::try_from_bytes(&info.data)
+                .map(|font| font.glyph(c).id().0 != 0)
+                .unwrap_or(false)
+        })
+    }
+
+    /// 字体组中的主字体（第一个候选字体）
+    pub fn primary(&self) -> Option<&FontInfo> {
+        self.fonts.first()
+    }
+}
+
 /// 字体管理器
 pub struct FontManager {
     /// 字体缓存
@@ -86,11 +160,16 @@ impl FontManager {
 
     /// 加载字体
     pub fn load_font(&mut self, family: &str) -> Result<Arc<Vec<u8>>> {
+        self.load_font_info(family).map(|info| info.data)
+    }
+
+    /// 加载字体并返回完整的 `FontInfo`（供字体组解析使用）
+    fn load_font_info(&mut self, family: &str) -> Result<FontInfo> {
         // 检查缓存
         {
             let fonts = self.fonts.lock().unwrap();
             if let Some(font_info) = fonts.get(family) {
-                return Ok(font_info.data.clone());
+                return Ok(font_info.clone());
             }
         }
 
@@ -117,7 +196,176 @@ impl FontManager {
             fonts.insert(family.to_string(), font_info.clone());
         }
 
-        Ok(font_info.data)
+        Ok(font_info)
+    }
+
+    /// 按家族名、粗细和样式精确查询一个字体
+    ///
+    /// 优先通过 `font-kit` 枚举系统已安装的字体，按标准的最近粗细规则匹配请求的
+    /// family/weight/style；当 `font-kit` 不可用或没有命中时，退回到按文件名
+    /// 子串匹配的扫描逻辑（即 [`FontManager::load_font_info`]），不让查询直接失败。
+    pub fn select_font(&mut self, family: &str, props: FontProperties) -> Result<FontInfo> {
+        #[cfg(feature = "system-fonts")]
+        {
+            if let Ok(info) = self.select_font_via_system_source(family, props) {
+                return Ok(info);
+            }
+        }
+
+        let _ = props; // 文件名扫描模式下无法区分粗细/样式，仅按家族名查找
+        self.load_font_info(family)
+    }
+
+    /// 通过 `font-kit` 的 `SystemSource` 枚举并匹配已安装字体
+    #[cfg(feature = "system-fonts")]
+    fn select_font_via_system_source(&mut self, family: &str, props: FontProperties) -> Result<FontInfo> {
+        use font_kit::family_name::FamilyName;
+        use font_kit::properties::{Properties as FkProperties, Stretch as FkStretch, Style as FkStyle, Weight as FkWeight};
+        use font_kit::source::SystemSource;
+
+        let style = match props.style {
+            FontStyle::Normal => FkStyle::Normal,
+            FontStyle::Italic => FkStyle::Italic,
+            FontStyle::Oblique => FkStyle::Oblique,
+        };
+
+        let query = FkProperties {
+            style,
+            weight: FkWeight(props.weight as f32),
+            stretch: FkStretch(props.stretch),
+        };
+
+        let handle = SystemSource::new()
+            .select_best_match(&[FamilyName::Title(family.to_string())], &query)
+            .map_err(|e| FlexRenderError::render_error(format!("系统字体查询失败: {:?}", e)))?;
+
+        let path = match &handle {
+            font_kit::handle::Handle::Path { path, .. } => path.clone(),
+            font_kit::handle::Handle::Memory { .. } => PathBuf::from(family),
+        };
+
+        let font = handle
+            .load()
+            .map_err(|e| FlexRenderError::render_error(format!("加载系统字体失败: {:?}", e)))?;
+        let font_data = font
+            .copy_font_data()
+            .ok_or_else(|| FlexRenderError::render_error("无法获取字体数据".to_string()))?;
+
+        // 按 family+weight+style 缓存，这样同一家族的不同字重/样式不会互相覆盖
+        let cache_key = format!("{}-{}-{:?}", family, props.weight, props.style);
+        let font_info = FontInfo {
+            family: cache_key.clone(),
+            path,
+            data: font_data.clone(),
+            size: font_data.len(),
+        };
+
+        let mut fonts = self.fonts.lock().unwrap();
+        fonts.insert(cache_key, font_info.clone());
+
+        Ok(font_info)
+    }
+
+    /// 按家族名 + 粗细 + 样式解析一个字体，返回可以喂给 [`FontManager::glyph_or_fallback`] 的
+    /// `FontId`。内部直接复用 [`FontManager::select_font`] 的查询/缓存逻辑，这里只是把结果
+    /// 收敛成一个轻量标识，避免调用方长期持有完整的 `FontInfo`（尤其是里面的字体字节数据）。
+    /// 查不到这个家族时退回默认字体，并把它登记进缓存，让返回的 `FontId` 能被
+    /// `glyph_or_fallback` 查到。
+    pub fn load_family(
+        &mut self,
+        name: &str,
+        weight: crate::types::FontWeight,
+        style: FontStyle,
+    ) -> Result<FontId> {
+        let props = FontProperties {
+            weight: weight.to_number(),
+            style,
+            stretch: 1.0,
+        };
+        let info = match self.select_font(name, props) {
+            Ok(info) => info,
+            Err(err) => {
+                let default_font = self.default_font.clone().ok_or(err)?;
+                self.fonts
+                    .lock()
+                    .unwrap()
+                    .entry(default_font.family.clone())
+                    .or_insert_with(|| default_font.clone());
+                default_font
+            }
+        };
+        Ok(info.family)
+    }
+
+    /// 在 `primary` 和 `fallbacks` 组成的回退链中，找到第一个能显示字符 `c` 的字体
+    ///
+    /// 逐个检查字体的 cmap：`primary` 本身如果把 `c` 解析成 `.notdef`（glyph id 为 0），
+    /// 就依次尝试 `fallbacks`；都没有命中时回退到 `primary` 本身（即便它画不出这个字符，
+    /// 也好过直接失败；[`FontGroup::font_for_char`] 在同样情况下返回 `None`，由调用方自己兜底）。
+    pub fn glyph_or_fallback(
+        &self,
+        primary: &FontId,
+        fallbacks: &[FontId],
+        c: char,
+    ) -> (FontId, GlyphId) {
+        let fonts = self.fonts.lock().unwrap();
+
+        for font_id in std::iter::once(primary).chain(fallbacks.iter()) {
+            if let Some(info) = fonts.get(font_id) {
+                if let Some(font) = rusttype::Font::try_from_bytes(&info.data) {
+                    let glyph_id = font.glyph(c).id().0;
+                    if glyph_id != 0 {
+                        return (font_id.clone(), glyph_id);
+                    }
+                }
+            }
+        }
+
+        (primary.clone(), 0)
+    }
+
+    /// 创建字体组：主字体 + 配置的回退字体 + 默认字体
+    ///
+    /// 模仿 Servo `FontGroup` 的做法：按顺序持有一组候选字体，
+    /// `measure_text` 再通过 [`FontGroup::font_for_char`] 按码点选择第一个能显示该字符的字体，
+    /// 从而让混合了 CJK、emoji 等字符的文本也能测得正确的宽度。
+    pub fn create_font_group(&mut self, primary: &str, fallbacks: &[&str]) -> Result<FontGroup> {
+        self.create_font_group_with_properties(primary, fallbacks, FontProperties::default())
+    }
+
+    /// 创建字体组，同时按给定的粗细/样式精确选择主字体
+    pub fn create_font_group_with_properties(
+        &mut self,
+        primary: &str,
+        fallbacks: &[&str],
+        props: FontProperties,
+    ) -> Result<FontGroup> {
+        let mut fonts = Vec::new();
+
+        if let Ok(info) = self.select_font(primary, props) {
+            fonts.push(info);
+        }
+
+        for fallback in fallbacks {
+            if let Ok(info) = self.load_font_info(fallback) {
+                fonts.push(info);
+            }
+        }
+
+        if let Some(ref default_font) = self.default_font {
+            if !fonts.iter().any(|f| f.family == default_font.family) {
+                fonts.push(default_font.clone());
+            }
+        }
+
+        if fonts.is_empty() {
+            return Err(FlexRenderError::render_error(format!(
+                "无法为字体组加载任何字体: {}",
+                primary
+            )));
+        }
+
+        Ok(FontGroup::new(fonts))
     }
 
     /// 查找字体文件
@@ -311,6 +559,31 @@ mod tests {
         assert_eq!(count, 0);
     }
 
+    #[test]
+    fn test_font_properties_default() {
+        let props = FontProperties::default();
+        assert_eq!(props.weight, 400);
+        assert_eq!(props.style, FontStyle::Normal);
+        assert_eq!(props.stretch, 1.0);
+    }
+
+    #[test]
+    fn test_select_font_falls_back_to_scan() {
+        let mut manager = FontManager::new();
+        // 未启用 `system-fonts` 特性时应退回到按文件名扫描的旧逻辑，而不是直接报错
+        let _ = manager.select_font("NonExistentFamily", FontProperties::default());
+    }
+
+    #[test]
+    fn test_create_font_group() {
+        let mut manager = FontManager::new();
+        let group = manager.create_font_group("NonExistentFont", &["AlsoMissing"]);
+
+        // 至少应该能回退到默认字体（占位符字体）
+        assert!(group.is_ok());
+        assert!(!group.unwrap().fonts.is_empty());
+    }
+
     #[test]
     fn test_default_font() {
         let manager = FontManager::new();
@@ -320,4 +593,24 @@ mod tests {
         let font_data = default_font.unwrap();
         assert!(!font_data.is_empty());
     }
+
+    #[test]
+    fn test_load_family_falls_back_to_default() {
+        let mut manager = FontManager::new();
+        // 家族名找不到时应该还是能拿到一个可用的 FontId（兜底占位字体），而不是报错
+        let font_id = manager.load_family(
+            "NonExistentFamily",
+            crate::types::FontWeight::Normal,
+            FontStyle::Normal,
+        );
+        assert!(font_id.is_ok());
+    }
+
+    #[test]
+    fn test_glyph_or_fallback_defaults_to_primary_when_unloaded() {
+        let manager = FontManager::new();
+        let (font_id, glyph_id) = manager.glyph_or_fallback(&"Unloaded".to_string(), &[], 'a');
+        assert_eq!(font_id, "Unloaded");
+        assert_eq!(glyph_id, 0);
+    }
 }
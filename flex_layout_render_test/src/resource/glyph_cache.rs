@@ -0,0 +1,174 @@
+//! 字形缓存
+//!
+//! `measure_text` 和渲染阶段都需要单个字形的前进宽度与轮廓，如果每次都重新走
+//! rusttype 的布局/轮廓提取流程会很浪费，而且这个库目前也没有任何途径拿到矢量
+//! 轮廓用于高质量或 SVG/PDF 输出。本模块提供一个以 `(字体, 字形)` 为键
+//! 的共享缓存：advance 和轮廓都归一化到 em 单位存储，measure_text 与渲染器共用
+//! 同一份数据，换算到具体像素大小时只需乘以 `pixel_size`。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 字体标识符：用字体族名（含粗细/样式后缀）唯一标识一个已加载的字体面
+pub type FontId = String;
+
+/// 字形标识符（字体内部的 glyph index）
+pub type GlyphId = u16;
+
+/// 归一化到 em 单位（0.0-1.0）的轮廓线段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlineSegment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+}
+
+/// 缓存的字形：em 单位下的前进宽度 + 轮廓路径
+#[derive(Debug, Clone, Default)]
+pub struct CachedGlyph {
+    /// 前进宽度（em 单位，乘以像素字号即为像素宽度）
+    pub advance: f32,
+    /// 轮廓路径，坐标归一化到 em 单位
+    pub outline: Vec<OutlineSegment>,
+}
+
+/// 将 rusttype 的轮廓回调收集为归一化的 `OutlineSegment` 列表
+struct OutlineCollector {
+    units_per_em: f32,
+    segments: Vec<OutlineSegment>,
+}
+
+impl rusttype::OutlineBuilder for OutlineCollector {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.segments
+            .push(OutlineSegment::MoveTo(x / self.units_per_em, y / self.units_per_em));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.segments
+            .push(OutlineSegment::LineTo(x / self.units_per_em, y / self.units_per_em));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.segments.push(OutlineSegment::QuadTo(
+            x1 / self.units_per_em,
+            y1 / self.units_per_em,
+            x / self.units_per_em,
+            y / self.units_per_em,
+        ));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.segments.push(OutlineSegment::CurveTo(
+            x1 / self.units_per_em,
+            y1 / self.units_per_em,
+            x2 / self.units_per_em,
+            y2 / self.units_per_em,
+            x / self.units_per_em,
+            y / self.units_per_em,
+        ));
+    }
+
+    fn close(&mut self) {}
+}
+
+/// 按 (字体, 字形) 缓存 advance 与轮廓的共享缓存
+pub struct GlyphCache {
+    units_per_em: Mutex<HashMap<FontId, f32>>,
+    glyphs: Mutex<HashMap<(FontId, GlyphId), CachedGlyph>>,
+}
+
+impl GlyphCache {
+    pub fn new() -> Self {
+        Self {
+            units_per_em: Mutex::new(HashMap::new()),
+            glyphs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取一个字形的缓存数据，首次访问时才会计算并填充缓存（内部可变，沿用
+    /// 本模块一贯的 `Arc<Mutex<..>>` 模式）
+    pub fn glyph(&self, font_id: &FontId, font: &rusttype::Font, glyph_id: GlyphId) -> CachedGlyph {
+        let key = (font_id.clone(), glyph_id);
+        {
+            let glyphs = self.glyphs.lock().unwrap();
+            if let Some(cached) = glyphs.get(&key) {
+                return cached.clone();
+            }
+        }
+
+        let units_per_em = font.units_per_em() as f32;
+        {
+            let mut table = self.units_per_em.lock().unwrap();
+            table.insert(font_id.clone(), units_per_em);
+        }
+
+        let glyph = font
+            .glyph(rusttype::GlyphId(glyph_id))
+            .scaled(rusttype::Scale::uniform(units_per_em));
+        let advance = glyph.h_metrics().advance_width / units_per_em;
+
+        let mut collector = OutlineCollector {
+            units_per_em,
+            segments: Vec::new(),
+        };
+        glyph.build_outline(&mut collector);
+
+        let cached = CachedGlyph {
+            advance,
+            outline: collector.segments,
+        };
+
+        let mut glyphs = self.glyphs.lock().unwrap();
+        glyphs.insert(key, cached.clone());
+        cached
+    }
+
+    /// 已记录的字体单位数（em 大小），主要用于调试/诊断
+    pub fn units_per_em(&self, font_id: &FontId) -> Option<f32> {
+        self.units_per_em.lock().unwrap().get(font_id).copied()
+    }
+
+    /// 清空缓存
+    pub fn clear(&self) {
+        self.glyphs.lock().unwrap().clear();
+        self.units_per_em.lock().unwrap().clear();
+    }
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref GLOBAL_GLYPH_CACHE: Arc<GlyphCache> = Arc::new(GlyphCache::new());
+}
+
+/// 获取全局字形缓存
+pub fn get_glyph_cache() -> Arc<GlyphCache> {
+    GLOBAL_GLYPH_CACHE.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyph_cache_populates_and_reuses() {
+        let data = crate::resource::font_manager::FontManager::new()
+            .get_default_font()
+            .unwrap();
+        let font = rusttype::Font::try_from_bytes(&data);
+
+        // 占位字体数据可能不是合法字体，这里只验证缓存逻辑本身不会 panic
+        if let Some(font) = font {
+            let cache = GlyphCache::new();
+            let first = cache.glyph(&"Default".to_string(), &font, 0);
+            let second = cache.glyph(&"Default".to_string(), &font, 0);
+            assert_eq!(first.advance, second.advance);
+        }
+    }
+}
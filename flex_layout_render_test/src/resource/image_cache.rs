@@ -5,10 +5,28 @@
 use crate::error::*;
 use crate::types::*;
 use image::{DynamicImage, ImageFormat, GenericImageView};
+pub use image::imageops::FilterType;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::fs;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 同一缓存键的进行中解码：第一个请求者（leader）负责解码并广播结果，
+/// 后续请求者（follower）等在 `condvar` 上，不会重复触发解码（single-flight）
+struct InFlightSlot {
+    state: Mutex<InFlightState>,
+    condvar: Condvar,
+}
+
+enum InFlightState {
+    Pending,
+    /// 用 `String` 而非 `FlexRenderError` 存放失败结果，因为后者内部包裹的
+    /// `image::ImageError` 等不是 `Clone`，无法广播给多个等待者
+    Done(std::result::Result<Arc<DynamicImage>, String>),
+}
 
 /// 缓存的图片信息
 #[derive(Debug, Clone)]
@@ -40,6 +58,12 @@ pub struct ImageCacheConfig {
     pub expire_duration: Duration,
     /// 是否启用 LRU 清理
     pub enable_lru: bool,
+    /// 缩放变体的磁盘缓存目录；为 `None` 时 [`ImageCache::load_scaled`] 只使用内存缓存
+    pub disk_cache_dir: Option<PathBuf>,
+    /// 磁盘缓存目录允许占用的最大字节数，超出时按文件 mtime 由旧到新清理
+    pub max_disk_bytes: usize,
+    /// [`ImageCache::load_tiled`] 切分瓦片的边长（像素），最后一行/列瓦片会裁剪为剩余部分
+    pub tile_size: u32,
 }
 
 impl Default for ImageCacheConfig {
@@ -49,6 +73,67 @@ impl Default for ImageCacheConfig {
             max_size_bytes: 100 * 1024 * 1024, // 100MB
             expire_duration: Duration::from_secs(3600), // 1小时
             enable_lru: true,
+            disk_cache_dir: dirs::cache_dir().map(|dir| dir.join("flex_layout_render").join("images")),
+            max_disk_bytes: 500 * 1024 * 1024, // 500MB
+            tile_size: 512,
+        }
+    }
+}
+
+/// [`ImageCache::load_tiled`] 返回的超大图句柄：只记录解码所需的元数据（路径、内容哈希、
+/// 整图尺寸、瓦片边长），真正的像素数据要到 [`ImageCache::load_tiles_in_rect`] 按需裁剪
+#[derive(Debug, Clone)]
+pub struct TiledImage {
+    /// 源文件路径
+    pub path: PathBuf,
+    /// 源文件内容的 SHA-256 摘要，用作瓦片缓存键的前缀
+    pub content_hash: String,
+    /// 整图的像素尺寸 `(width, height)`
+    pub image_dims: (u32, u32),
+    /// 瓦片边长（像素）
+    pub tile_size: u32,
+}
+
+impl TiledImage {
+    /// 瓦片网格的列数、行数（最后一行/列可能是边长小于 `tile_size` 的剩余部分）
+    pub fn tile_grid(&self) -> (u32, u32) {
+        let (width, height) = self.image_dims;
+        (
+            (width + self.tile_size - 1) / self.tile_size,
+            (height + self.tile_size - 1) / self.tile_size,
+        )
+    }
+}
+
+/// [`ImageCache::memory_report`] 返回的详细内存/命中率报表
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReport {
+    /// 缓存条目数（原图 + 缩放变体 + 瓦片，一视同仁）
+    pub entry_count: usize,
+    /// 内存中常驻的总字节数
+    pub resident_bytes: usize,
+    /// 按颜色格式（`Rgba8`、`Rgb8` 等）拆分的常驻字节数
+    pub resident_bytes_by_format: HashMap<&'static str, usize>,
+    /// 磁盘缓存目录当前占用的字节数；未启用磁盘缓存时为 0
+    pub disk_cache_bytes: usize,
+    /// 累计淘汰（过期 + LRU）的条目数
+    pub eviction_count: usize,
+    /// `load_image` 缓存探测命中的累计次数
+    pub hit_count: usize,
+    /// `load_image` 缓存探测未命中的累计次数
+    pub miss_count: usize,
+    /// 所有条目 `access_count` 之和，与旧版 `cache_stats` 的第三个字段口径一致
+    pub total_accesses: usize,
+}
+
+impl MemoryReport {
+    /// 命中率 = hit / (hit + miss)；还没有任何请求时返回 0.0 而不是 NaN
+    pub fn hit_ratio(&self) -> f32 {
+        let total = self.hit_count + self.miss_count;
+        if total == 0 {
+            0.0
+        } else {
+            self.hit_count as f32 / total as f32
         }
     }
 }
@@ -61,6 +146,14 @@ pub struct ImageCache {
     config: ImageCacheConfig,
     /// 当前缓存大小
     current_size: Arc<Mutex<usize>>,
+    /// 正在进行中的解码，键为缓存键；用于 `load_image`/`load_scaled` 的 single-flight 去重
+    in_flight: Arc<Mutex<HashMap<String, Arc<InFlightSlot>>>>,
+    /// `load_image` 缓存探测命中的累计次数
+    hit_count: Arc<Mutex<usize>>,
+    /// `load_image` 缓存探测未命中（触发了一次解码）的累计次数
+    miss_count: Arc<Mutex<usize>>,
+    /// `cleanup_cache` 累计淘汰（过期 + LRU）的条目数
+    eviction_count: Arc<Mutex<usize>>,
 }
 
 impl ImageCache {
@@ -70,35 +163,104 @@ impl ImageCache {
             cache: Arc::new(Mutex::new(HashMap::new())),
             config,
             current_size: Arc::new(Mutex::new(0)),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            hit_count: Arc::new(Mutex::new(0)),
+            miss_count: Arc::new(Mutex::new(0)),
+            eviction_count: Arc::new(Mutex::new(0)),
         }
     }
-    
-    /// 加载图片
+
+    /// 加载图片；命中缓存直接返回，否则解码并写入缓存。多个线程同时请求同一未缓存路径时，
+    /// 只有第一个调用者真正解码，其余调用者等待其结果（见 [`Self::load_with_single_flight`]）。
+    /// 缓存探测的命中/未命中计入 [`Self::memory_report`] 的 `hit_count`/`miss_count`
     pub fn load_image<P: AsRef<Path>>(&self, path: P) -> Result<Arc<DynamicImage>> {
         let path = path.as_ref();
         let path_str = path.to_string_lossy().to_string();
-        
-        // 检查缓存
-        {
-            let mut cache = self.cache.lock().unwrap();
-            if let Some(cached) = cache.get_mut(&path_str) {
-                // 更新访问信息
-                cached.last_accessed = Instant::now();
-                cached.access_count += 1;
-                return Ok(cached.image.clone());
+
+        if let Some(image) = self.try_get(&path_str) {
+            *self.hit_count.lock().unwrap() += 1;
+            return Ok(image);
+        }
+        *self.miss_count.lock().unwrap() += 1;
+
+        self.load_with_single_flight(path_str, || self.decode_and_cache(path))
+    }
+
+    /// 非阻塞查询内存缓存：命中则更新访问信息并返回，未命中（包括正在解码中）直接返回
+    /// `None`，不会触发解码也不会阻塞等待。供渲染路径在图片还没准备好时选择跳过
+    pub fn try_get<P: AsRef<Path>>(&self, path: P) -> Option<Arc<DynamicImage>> {
+        let cache_key = path.as_ref().to_string_lossy().to_string();
+        let mut cache = self.cache.lock().unwrap();
+        let cached = cache.get_mut(&cache_key)?;
+        cached.last_accessed = Instant::now();
+        cached.access_count += 1;
+        Some(cached.image.clone())
+    }
+
+    /// single-flight 执行 `decode`：同一 `cache_key` 只有一个调用者（leader）真正解码，
+    /// 其余调用者等在共享的 `Condvar` 上，解码完成后一起拿到同一份 `Arc<DynamicImage>`
+    fn load_with_single_flight(
+        &self,
+        cache_key: String,
+        decode: impl FnOnce() -> Result<Arc<DynamicImage>>,
+    ) -> Result<Arc<DynamicImage>> {
+        let (slot, is_leader) = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            if let Some(slot) = in_flight.get(&cache_key) {
+                (slot.clone(), false)
+            } else {
+                let slot = Arc::new(InFlightSlot {
+                    state: Mutex::new(InFlightState::Pending),
+                    condvar: Condvar::new(),
+                });
+                in_flight.insert(cache_key.clone(), slot.clone());
+                (slot, true)
+            }
+        };
+
+        if is_leader {
+            let result = decode();
+            let broadcast = match &result {
+                Ok(image) => Ok(image.clone()),
+                Err(e) => Err(e.to_string()),
+            };
+
+            *slot.state.lock().unwrap() = InFlightState::Done(broadcast);
+            slot.condvar.notify_all();
+
+            // 本键这一代的解码已经结束，移除登记以便之后（如缓存失效后）可以重新发起
+            self.in_flight.lock().unwrap().remove(&cache_key);
+
+            result
+        } else {
+            let mut state = slot.state.lock().unwrap();
+            while matches!(*state, InFlightState::Pending) {
+                state = slot.condvar.wait(state).unwrap();
+            }
+            match &*state {
+                InFlightState::Done(Ok(image)) => Ok(image.clone()),
+                InFlightState::Done(Err(message)) => {
+                    Err(FlexRenderError::render_error(message.clone()))
+                }
+                InFlightState::Pending => unreachable!("刚刚已经等待到 Done 状态"),
             }
         }
-        
+    }
+
+    /// 从文件解码图片并写入缓存，返回共享的 `Arc<DynamicImage>`
+    fn decode_and_cache(&self, path: &Path) -> Result<Arc<DynamicImage>> {
+        let path_str = path.to_string_lossy().to_string();
+
         // 加载图片
         let image = self.load_image_from_file(path)?;
         let original_size = Size::new(
             image.width() as f32,
             image.height() as f32,
         );
-        
+
         // 估算内存使用量
         let estimated_size = self.estimate_image_memory_size(&image);
-        
+
         let cached_image = CachedImage {
             image: Arc::new(image),
             original_size,
@@ -108,22 +270,381 @@ impl ImageCache {
             last_accessed: Instant::now(),
             access_count: 1,
         };
-        
+
         // 检查是否需要清理缓存
         self.cleanup_if_needed();
-        
+
         // 添加到缓存
         {
             let mut cache = self.cache.lock().unwrap();
             let mut current_size = self.current_size.lock().unwrap();
-            
+
             cache.insert(path_str, cached_image.clone());
             *current_size += estimated_size;
         }
-        
+
         Ok(cached_image.image)
     }
-    
+
+    /// 加载图片并缩放到目标尺寸，缓存预缩放后的结果，避免每次布局都重新 resize。
+    ///
+    /// 缓存键由文件内容的 SHA-256 摘要 + 目标宽高 + 滤波算法组成，因此同一文件换路径、
+    /// 或同一路径换内容都不会读到过期的缩放结果。先查内存缓存，再查 `disk_cache_dir`
+    /// 下以该键命名的 PNG 文件，两者都未命中时才真正解码原图并执行缩放
+    pub fn load_scaled<P: AsRef<Path>>(
+        &self,
+        path: P,
+        target: Size,
+        filter: FilterType,
+    ) -> Result<Arc<DynamicImage>> {
+        let path = path.as_ref();
+        let width = target.width.round().max(1.0) as u32;
+        let height = target.height.round().max(1.0) as u32;
+
+        let content_hash = self.content_hash(path)?;
+        let cache_key = Self::scaled_cache_key(&content_hash, width, height, filter);
+
+        // 1. 内存缓存
+        {
+            let mut cache = self.cache.lock().unwrap();
+            if let Some(cached) = cache.get_mut(&cache_key) {
+                cached.last_accessed = Instant::now();
+                cached.access_count += 1;
+                return Ok(cached.image.clone());
+            }
+        }
+
+        // 2. 磁盘缓存
+        if let Some(disk_path) = self.disk_cache_path(&cache_key) {
+            if disk_path.exists() {
+                if let Ok(image) = image::open(&disk_path) {
+                    return self.insert_variant_into_memory(cache_key, image, path);
+                }
+            }
+        }
+
+        // 3. 两级缓存都未命中：解码原图（复用 load_image 的原图缓存），缩放并落盘
+        let original = self.load_image(path)?;
+        let scaled = original.resize_exact(width, height, filter);
+
+        if let Some(disk_path) = self.disk_cache_path(&cache_key) {
+            if let Some(parent) = disk_path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if scaled.save_with_format(&disk_path, ImageFormat::Png).is_ok() {
+                self.cleanup_disk_cache_if_needed();
+            }
+        }
+
+        self.insert_variant_into_memory(cache_key, scaled, path)
+    }
+
+    /// 为超大图片建立瓦片句柄：只读取内容哈希和图片尺寸（`image::image_dimensions` 只解析
+    /// 文件头，不会解码像素），不会把整张图载入内存。之后通过 [`Self::load_tiles_in_rect`]
+    /// 按需裁剪并缓存实际用到的瓦片
+    pub fn load_tiled<P: AsRef<Path>>(&self, path: P) -> Result<TiledImage> {
+        let path = path.as_ref();
+        let content_hash = self.content_hash(path)?;
+        let image_dims = image::image_dimensions(path).map_err(|e| {
+            FlexRenderError::render_error(format!("读取图片尺寸失败: {:?} - {}", path, e))
+        })?;
+
+        Ok(TiledImage {
+            path: path.to_path_buf(),
+            content_hash,
+            image_dims,
+            tile_size: self.config.tile_size,
+        })
+    }
+
+    /// 解码（或从缓存取出）与 `visible_rect` 相交的所有瓦片，返回 `(瓦片坐标, 瓦片图像)` 列表。
+    /// 已缓存的瓦片直接复用；只要还有至少一个瓦片缺失，就解码一次完整原图用于裁剪 —— 这一批
+    /// 缺失的瓦片共享这一次解码，裁剪完成后完整原图即被丢弃，只有裁出的瓦片计入缓存和内存记账
+    pub fn load_tiles_in_rect(
+        &self,
+        tiled: &TiledImage,
+        visible_rect: Rect,
+    ) -> Result<Vec<((u32, u32), Arc<DynamicImage>)>> {
+        let (x_range, y_range) =
+            Self::compute_tile_range(tiled.image_dims, tiled.tile_size, visible_rect);
+
+        let mut source: Option<DynamicImage> = None;
+        let mut tiles = Vec::new();
+
+        for tile_y in y_range {
+            for tile_x in x_range.clone() {
+                let cache_key = Self::tile_cache_key(&tiled.content_hash, tile_x, tile_y);
+                if let Some(image) = self.try_get(&cache_key) {
+                    tiles.push(((tile_x, tile_y), image));
+                    continue;
+                }
+
+                if source.is_none() {
+                    source = Some(self.load_image_from_file(&tiled.path)?);
+                }
+                let full = source.as_ref().unwrap();
+
+                let (tile_w, tile_h) =
+                    Self::compute_tile_size(tiled.image_dims, (tile_x, tile_y), tiled.tile_size);
+                let origin_x = tile_x * tiled.tile_size;
+                let origin_y = tile_y * tiled.tile_size;
+                let cropped = full.crop_imm(origin_x, origin_y, tile_w, tile_h);
+
+                let image = self.insert_variant_into_memory(cache_key, cropped, &tiled.path)?;
+                tiles.push(((tile_x, tile_y), image));
+            }
+        }
+
+        Ok(tiles)
+    }
+
+    /// 组装瓦片的缓存键：内容哈希 + 瓦片坐标
+    fn tile_cache_key(content_hash: &str, tile_x: u32, tile_y: u32) -> String {
+        format!("{}_tile_{}_{}", content_hash, tile_x, tile_y)
+    }
+
+    /// 计算瓦片 `(tile_x, tile_y)` 的实际像素宽高；最后一行/列会被裁剪为剩余部分，
+    /// 不会超出 `image_dims`
+    fn compute_tile_size(
+        image_dims: (u32, u32),
+        tile_index: (u32, u32),
+        tile_size: u32,
+    ) -> (u32, u32) {
+        let (image_w, image_h) = image_dims;
+        let (tile_x, tile_y) = tile_index;
+
+        let width = tile_size.min(image_w.saturating_sub(tile_x * tile_size));
+        let height = tile_size.min(image_h.saturating_sub(tile_y * tile_size));
+
+        (width, height)
+    }
+
+    /// 计算与 `visible_rect` 相交的瓦片索引范围（闭区间），已经按整图的瓦片网格边界裁剪
+    fn compute_tile_range(
+        image_dims: (u32, u32),
+        tile_size: u32,
+        visible_rect: Rect,
+    ) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
+        let (image_w, image_h) = image_dims;
+        if image_w == 0 || image_h == 0 || tile_size == 0 {
+            return (0..=0, 0..=0);
+        }
+
+        let max_tile_x = (image_w - 1) / tile_size;
+        let max_tile_y = (image_h - 1) / tile_size;
+
+        let to_tile_index = |coordinate: f32, max_index: u32, max_pixel: u32| -> u32 {
+            let clamped = coordinate.max(0.0).min(max_pixel as f32) as u32;
+            (clamped / tile_size).min(max_index)
+        };
+
+        let left = visible_rect.left();
+        let top = visible_rect.top();
+        let right = (visible_rect.right() - 1.0).max(left);
+        let bottom = (visible_rect.bottom() - 1.0).max(top);
+
+        let x0 = to_tile_index(left, max_tile_x, image_w - 1);
+        let x1 = to_tile_index(right, max_tile_x, image_w - 1);
+        let y0 = to_tile_index(top, max_tile_y, image_h - 1);
+        let y1 = to_tile_index(bottom, max_tile_y, image_h - 1);
+
+        (x0..=x1, y0..=y1)
+    }
+
+    /// 把一个派生变体（缩放结果或瓦片裁剪结果）记入内存缓存，复用与原图相同的内存记账与 LRU 清理
+    fn insert_variant_into_memory(
+        &self,
+        cache_key: String,
+        image: DynamicImage,
+        source_path: &Path,
+    ) -> Result<Arc<DynamicImage>> {
+        let estimated_size = self.estimate_image_memory_size(&image);
+        let original_size = Size::new(image.width() as f32, image.height() as f32);
+
+        let cached_image = CachedImage {
+            image: Arc::new(image),
+            original_size,
+            path: source_path.to_path_buf(),
+            file_size: estimated_size,
+            loaded_at: Instant::now(),
+            last_accessed: Instant::now(),
+            access_count: 1,
+        };
+
+        self.cleanup_if_needed();
+
+        {
+            let mut cache = self.cache.lock().unwrap();
+            let mut current_size = self.current_size.lock().unwrap();
+
+            cache.insert(cache_key, cached_image.clone());
+            *current_size += estimated_size;
+        }
+
+        Ok(cached_image.image)
+    }
+
+    /// 对文件原始字节求 SHA-256，返回十六进制摘要作为内容寻址的缓存键前缀
+    fn content_hash<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        let bytes = fs::read(path.as_ref()).map_err(|e| {
+            FlexRenderError::render_error(format!("读取图片文件失败: {:?} - {}", path.as_ref(), e))
+        })?;
+        Ok(Self::hash_bytes(&bytes))
+    }
+
+    /// 对任意字节求 SHA-256，返回十六进制摘要；[`Self::load_from_bytes`] 用它做内容寻址缓存键,
+    /// 这样同一份字节无论来自文件、data URI 还是远程下载都落在同一个缓存条目上
+    fn hash_bytes(bytes: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// 从内存字节解码图片并缓存，不经过文件系统。缓存键是字节内容的 SHA-256 摘要，
+    /// 因此同一张图不论以文件路径、data URI 还是远程 URL 出现都只解码、计费一次
+    pub fn load_from_bytes(&self, bytes: &[u8]) -> Result<Arc<DynamicImage>> {
+        let cache_key = Self::hash_bytes(bytes);
+
+        if let Some(image) = self.try_get(&cache_key) {
+            return Ok(image);
+        }
+
+        let owned = bytes.to_vec();
+        let key_for_decode = cache_key.clone();
+        self.load_with_single_flight(cache_key, move || self.decode_bytes_and_cache(&key_for_decode, &owned))
+    }
+
+    /// 解析 `data:image/<mime>;base64,<payload>` 形式的内联图片，base64 解码后委托给
+    /// [`Self::load_from_bytes`] 做内容寻址缓存
+    pub fn load_from_data_uri(&self, data_uri: &str) -> Result<Arc<DynamicImage>> {
+        let rest = data_uri.strip_prefix("data:").ok_or_else(|| {
+            FlexRenderError::render_error(format!("不是合法的 data URI: {}", data_uri))
+        })?;
+
+        let (meta, payload) = rest.split_once(',').ok_or_else(|| {
+            FlexRenderError::render_error(format!("data URI 缺少逗号分隔的负载: {}", data_uri))
+        })?;
+
+        if !meta.contains("base64") {
+            return Err(FlexRenderError::render_error(
+                "仅支持 base64 编码的 data URI".to_string(),
+            ));
+        }
+
+        let bytes = base64::decode(payload).map_err(|e| {
+            FlexRenderError::render_error(format!("data URI base64 解码失败: {}", e))
+        })?;
+
+        self.load_from_bytes(&bytes)
+    }
+
+    /// 通过 HTTP(S) 拉取远程图片字节并委托给 [`Self::load_from_bytes`] 解码缓存；
+    /// 需要启用 `http-images` feature，否则返回错误而不是静默跳过
+    pub fn load_from_url(&self, url: &str) -> Result<Arc<DynamicImage>> {
+        #[cfg(feature = "http-images")]
+        {
+            let bytes = Self::fetch_url_bytes(url)?;
+            return self.load_from_bytes(&bytes);
+        }
+
+        #[cfg(not(feature = "http-images"))]
+        {
+            let _ = url;
+            Err(FlexRenderError::render_error(
+                "加载远程图片需要启用 `http-images` feature".to_string(),
+            ))
+        }
+    }
+
+    #[cfg(feature = "http-images")]
+    fn fetch_url_bytes(url: &str) -> Result<Vec<u8>> {
+        use std::io::Read;
+
+        let response = ureq::get(url)
+            .call()
+            .map_err(|e| FlexRenderError::render_error(format!("请求远程图片失败: {:?} - {}", url, e)))?;
+
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut bytes)
+            .map_err(|e| FlexRenderError::render_error(format!("读取远程图片响应失败: {:?} - {}", url, e)))?;
+
+        Ok(bytes)
+    }
+
+    /// 根据 `src` 的形态分发到对应加载器：`data:` 前缀走内联 data URI 解码，
+    /// `http://`/`https://` 前缀走远程下载，其余一律按文件路径处理
+    pub fn load_any(&self, src: &str) -> Result<Arc<DynamicImage>> {
+        if src.starts_with("data:") {
+            self.load_from_data_uri(src)
+        } else if src.starts_with("http://") || src.starts_with("https://") {
+            self.load_from_url(src)
+        } else {
+            self.load_image(src)
+        }
+    }
+
+    /// 解码内存字节并写入缓存；`cache_key` 是调用方已经算好的内容哈希，避免重复哈希一次
+    fn decode_bytes_and_cache(&self, cache_key: &str, bytes: &[u8]) -> Result<Arc<DynamicImage>> {
+        let image = image::load_from_memory(bytes).map_err(|e| {
+            FlexRenderError::render_error(format!("从内存解码图片失败: {}", e))
+        })?;
+
+        // 没有真实文件路径，用内容哈希拼一个虚拟路径占位，便于 `cached_images` 等接口展示
+        let virtual_path = PathBuf::from(format!("memory://{}", cache_key));
+        self.insert_variant_into_memory(cache_key.to_string(), image, &virtual_path)
+    }
+
+    /// 组装缩放变体的缓存键：内容哈希 + 目标宽高 + 滤波算法
+    fn scaled_cache_key(content_hash: &str, width: u32, height: u32, filter: FilterType) -> String {
+        format!("{}_{}x{}_{:?}", content_hash, width, height, filter)
+    }
+
+    /// 缩放变体在磁盘缓存目录下的存储路径；未配置 `disk_cache_dir` 时返回 `None`
+    fn disk_cache_path(&self, cache_key: &str) -> Option<PathBuf> {
+        self.config
+            .disk_cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.png", cache_key)))
+    }
+
+    /// 按文件 mtime 从旧到新清理磁盘缓存目录，直到总占用回落到 `max_disk_bytes` 以内
+    fn cleanup_disk_cache_if_needed(&self) {
+        let Some(dir) = &self.config.disk_cache_dir else {
+            return;
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        let mut files: Vec<(PathBuf, u64, SystemTime)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().ok()?;
+                Some((entry.path(), metadata.len(), modified))
+            })
+            .collect();
+
+        let total_size: u64 = files.iter().map(|(_, size, _)| size).sum();
+        if total_size <= self.config.max_disk_bytes as u64 {
+            return;
+        }
+
+        files.sort_by_key(|(_, _, modified)| *modified);
+
+        let mut remaining = total_size;
+        for (path, size, _) in files {
+            if remaining <= self.config.max_disk_bytes as u64 {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                remaining = remaining.saturating_sub(size);
+            }
+        }
+    }
+
     /// 从文件加载图片
     fn load_image_from_file<P: AsRef<Path>>(&self, path: P) -> Result<DynamicImage> {
         let path = path.as_ref();
@@ -177,7 +698,13 @@ impl ImageCache {
     /// 估算图片内存使用量
     fn estimate_image_memory_size(&self, image: &DynamicImage) -> usize {
         let (width, height) = image.dimensions();
-        let bytes_per_pixel = match image {
+        (width * height) as usize * Self::bytes_per_pixel(image)
+    }
+
+    /// 每像素字节数，按颜色格式区分；[`Self::estimate_image_memory_size`] 和
+    /// [`Self::memory_report`] 共用这张表，保证内存估算和按格式拆分的报表口径一致
+    fn bytes_per_pixel(image: &DynamicImage) -> usize {
+        match image {
             DynamicImage::ImageLuma8(_) => 1,
             DynamicImage::ImageLumaA8(_) => 2,
             DynamicImage::ImageRgb8(_) => 3,
@@ -189,9 +716,24 @@ impl ImageCache {
             DynamicImage::ImageRgb32F(_) => 12,
             DynamicImage::ImageRgba32F(_) => 16,
             _ => 4, // 默认假设 RGBA
-        };
-        
-        (width * height) as usize * bytes_per_pixel
+        }
+    }
+
+    /// 颜色格式的可读名字，用作 [`MemoryReport::resident_bytes_by_format`] 的键
+    fn format_label(image: &DynamicImage) -> &'static str {
+        match image {
+            DynamicImage::ImageLuma8(_) => "Luma8",
+            DynamicImage::ImageLumaA8(_) => "LumaA8",
+            DynamicImage::ImageRgb8(_) => "Rgb8",
+            DynamicImage::ImageRgba8(_) => "Rgba8",
+            DynamicImage::ImageLuma16(_) => "Luma16",
+            DynamicImage::ImageLumaA16(_) => "LumaA16",
+            DynamicImage::ImageRgb16(_) => "Rgb16",
+            DynamicImage::ImageRgba16(_) => "Rgba16",
+            DynamicImage::ImageRgb32F(_) => "Rgb32F",
+            DynamicImage::ImageRgba32F(_) => "Rgba32F",
+            _ => "Other",
+        }
     }
     
     /// 清理缓存（如果需要）
@@ -255,6 +797,7 @@ impl ImageCache {
         }
         
         // 执行移除
+        *self.eviction_count.lock().unwrap() += to_remove.len();
         for (key, size) in to_remove {
             cache.remove(&key);
             *current_size = current_size.saturating_sub(size);
@@ -268,16 +811,104 @@ impl ImageCache {
         }
         Ok(())
     }
-    
+
+    /// 并发预加载一批图片：用一个小型工作线程池（通过 `mpsc` 通道分发任务）分担解码，
+    /// 线程数取 `min(可用 CPU 核心数, 任务数)`。重复路径、以及与其它 `load_image` 调用撞车
+    /// 的路径由 single-flight 机制保证只解码一次。任一张图片失败都会汇总进返回的错误里，
+    /// 但不会中断其余图片的加载
+    pub fn preload_images_parallel<P: AsRef<Path>>(&self, paths: &[P]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let worker_count = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(paths.len());
+
+        let (task_tx, task_rx) = mpsc::channel::<PathBuf>();
+        for path in paths {
+            task_tx.send(path.as_ref().to_path_buf()).unwrap();
+        }
+        drop(task_tx);
+        let task_rx = Mutex::new(task_rx);
+
+        let errors: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        std::thread::scope(|scope| {
+            for _ in 0..worker_count {
+                scope.spawn(|| loop {
+                    let path = task_rx.lock().unwrap().recv();
+                    let Ok(path) = path else { break };
+                    if let Err(e) = self.load_image(&path) {
+                        errors.lock().unwrap().push(format!("{:?}: {}", path, e));
+                    }
+                });
+            }
+        });
+
+        let errors = errors.into_inner().unwrap();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(FlexRenderError::render_error(format!(
+                "并发预加载 {} 张图片失败: {}",
+                errors.len(),
+                errors.join("; ")
+            )))
+        }
+    }
+
     /// 获取缓存统计信息
     pub fn cache_stats(&self) -> (usize, usize, usize) {
+        let report = self.memory_report();
+        (report.entry_count, report.resident_bytes, report.total_accesses)
+    }
+
+    /// 详细的内存/命中率报表：按颜色格式拆分常驻字节、磁盘变体占用、累计淘汰数，
+    /// 以及 [`Self::load_image`] 的累计命中/未命中次数。比 [`Self::cache_stats`]
+    /// 的精简元组更适合诊断缓存颠簸、调整 [`ImageCacheConfig`] 的容量上限
+    pub fn memory_report(&self) -> MemoryReport {
         let cache = self.cache.lock().unwrap();
-        let current_size = *self.current_size.lock().unwrap();
-        let total_accesses = cache.values().map(|c| c.access_count).sum();
-        
-        (cache.len(), current_size, total_accesses)
+
+        let mut resident_bytes_by_format: HashMap<&'static str, usize> = HashMap::new();
+        let mut total_accesses = 0;
+        for cached in cache.values() {
+            *resident_bytes_by_format
+                .entry(Self::format_label(&cached.image))
+                .or_insert(0) += cached.file_size;
+            total_accesses += cached.access_count;
+        }
+
+        MemoryReport {
+            entry_count: cache.len(),
+            resident_bytes: *self.current_size.lock().unwrap(),
+            resident_bytes_by_format,
+            disk_cache_bytes: self.disk_cache_usage_bytes(),
+            eviction_count: *self.eviction_count.lock().unwrap(),
+            hit_count: *self.hit_count.lock().unwrap(),
+            miss_count: *self.miss_count.lock().unwrap(),
+            total_accesses,
+        }
     }
-    
+
+    /// 磁盘缓存目录当前实际占用的字节数；未启用磁盘缓存（`disk_cache_dir` 为 `None`）
+    /// 或目录尚不存在时返回 0
+    fn disk_cache_usage_bytes(&self) -> usize {
+        let Some(dir) = &self.config.disk_cache_dir else {
+            return 0;
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return 0;
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len() as usize)
+            .sum()
+    }
+
     /// 清空缓存
     pub fn clear(&self) {
         let mut cache = self.cache.lock().unwrap();
@@ -354,6 +985,7 @@ mod tests {
             max_size_bytes: 50 * 1024 * 1024,
             expire_duration: Duration::from_secs(1800),
             enable_lru: false,
+            ..Default::default()
         };
         
         let cache = ImageCache::new(config.clone());
@@ -392,4 +1024,372 @@ mod tests {
         assert_eq!(count, 0);
         assert_eq!(size, 0);
     }
+
+    fn write_test_image(path: &Path, width: u32, height: u32) {
+        let image = DynamicImage::ImageRgba8(image::RgbaImage::new(width, height));
+        image.save_with_format(path, ImageFormat::Png).unwrap();
+    }
+
+    #[test]
+    fn test_load_scaled_caches_in_memory_and_on_disk() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        write_test_image(&source_path, 20, 20);
+
+        let disk_cache_dir = dir.path().join("cache");
+        let cache = ImageCache::new(ImageCacheConfig {
+            disk_cache_dir: Some(disk_cache_dir.clone()),
+            ..Default::default()
+        });
+
+        let first = cache
+            .load_scaled(&source_path, Size::new(10.0, 10.0), FilterType::Triangle)
+            .unwrap();
+        assert_eq!(first.width(), 10);
+        assert_eq!(first.height(), 10);
+
+        // 磁盘上应该已经写入了对应的 PNG 变体
+        let disk_entries: Vec<_> = fs::read_dir(&disk_cache_dir).unwrap().collect();
+        assert_eq!(disk_entries.len(), 1);
+
+        // 第二次调用命中内存缓存，返回同一份 Arc
+        let second = cache
+            .load_scaled(&source_path, Size::new(10.0, 10.0), FilterType::Triangle)
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_load_scaled_reads_back_from_disk_cache() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        write_test_image(&source_path, 20, 20);
+
+        let disk_cache_dir = dir.path().join("cache");
+        let config = ImageCacheConfig {
+            disk_cache_dir: Some(disk_cache_dir),
+            ..Default::default()
+        };
+
+        // 第一个缓存实例写盘，第二个全新实例（内存缓存为空）应该能从磁盘命中
+        let writer = ImageCache::new(config.clone());
+        writer
+            .load_scaled(&source_path, Size::new(10.0, 10.0), FilterType::Triangle)
+            .unwrap();
+
+        let reader = ImageCache::new(config);
+        let (count_before, ..) = reader.cache_stats();
+        assert_eq!(count_before, 0);
+
+        let scaled = reader
+            .load_scaled(&source_path, Size::new(10.0, 10.0), FilterType::Triangle)
+            .unwrap();
+        assert_eq!(scaled.width(), 10);
+        assert_eq!(scaled.height(), 10);
+    }
+
+    #[test]
+    fn test_cleanup_disk_cache_evicts_oldest_file_by_mtime() {
+        let dir = tempdir().unwrap();
+        let disk_cache_dir = dir.path().join("cache");
+        fs::create_dir_all(&disk_cache_dir).unwrap();
+
+        let older = disk_cache_dir.join("older.png");
+        let newer = disk_cache_dir.join("newer.png");
+        fs::write(&older, vec![0u8; 1024]).unwrap();
+        // 确保两个文件的 mtime 有可观测的先后顺序
+        std::thread::sleep(Duration::from_millis(10));
+        fs::write(&newer, vec![0u8; 1024]).unwrap();
+
+        let cache = ImageCache::new(ImageCacheConfig {
+            disk_cache_dir: Some(disk_cache_dir.clone()),
+            max_disk_bytes: 1024, // 只够容纳一个文件
+            ..Default::default()
+        });
+
+        cache.cleanup_disk_cache_if_needed();
+
+        assert!(!older.exists(), "较旧的文件应当被清理");
+        assert!(newer.exists(), "较新的文件应当保留");
+    }
+
+    #[test]
+    fn test_try_get_does_not_trigger_decode() {
+        let cache = ImageCache::default();
+        assert!(cache.try_get("nonexistent.png").is_none());
+
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        write_test_image(&source_path, 4, 4);
+
+        assert!(cache.try_get(&source_path).is_none());
+        cache.load_image(&source_path).unwrap();
+        assert!(cache.try_get(&source_path).is_some());
+    }
+
+    #[test]
+    fn test_concurrent_load_image_decodes_once_and_shares_result() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        write_test_image(&source_path, 8, 8);
+
+        let cache = Arc::new(ImageCache::default());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                let source_path = source_path.clone();
+                std::thread::spawn(move || cache.load_image(&source_path).unwrap())
+            })
+            .collect();
+
+        let images: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        for image in &images[1..] {
+            assert!(Arc::ptr_eq(&images[0], image), "所有调用者应当拿到同一份解码结果");
+        }
+
+        let (count, ..) = cache.cache_stats();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_preload_images_parallel_loads_all_and_reports_missing() {
+        let dir = tempdir().unwrap();
+        let mut paths = Vec::new();
+        for i in 0..5 {
+            let path = dir.path().join(format!("img{}.png", i));
+            write_test_image(&path, 4, 4);
+            paths.push(path);
+        }
+
+        let cache = ImageCache::default();
+        cache.preload_images_parallel(&paths).unwrap();
+        for path in &paths {
+            assert!(cache.contains(path));
+        }
+
+        let missing = dir.path().join("missing.png");
+        let err = cache.preload_images_parallel(&[missing]).unwrap_err();
+        assert!(err.to_string().contains("并发预加载"));
+    }
+
+    #[test]
+    fn test_compute_tile_size_clamps_remainder_tiles() {
+        // 100x100 的图，瓦片边长 64：最后一行/列只剩 36 像素
+        assert_eq!(ImageCache::compute_tile_size((100, 100), (0, 0), 64), (64, 64));
+        assert_eq!(ImageCache::compute_tile_size((100, 100), (1, 0), 64), (36, 64));
+        assert_eq!(ImageCache::compute_tile_size((100, 100), (0, 1), 64), (64, 36));
+        assert_eq!(ImageCache::compute_tile_size((100, 100), (1, 1), 64), (36, 36));
+    }
+
+    #[test]
+    fn test_compute_tile_range_covers_visible_rect() {
+        let (x_range, y_range) =
+            ImageCache::compute_tile_range((1000, 1000), 256, Rect::new(300.0, 10.0, 50.0, 600.0));
+        assert_eq!(x_range, 1..=1);
+        assert_eq!(y_range, 0..=2);
+    }
+
+    #[test]
+    fn test_compute_tile_range_clamps_to_grid_bounds() {
+        // 可见区域远超出图片边界，结果应当被夹到最后一个瓦片
+        let (x_range, y_range) =
+            ImageCache::compute_tile_range((100, 100), 64, Rect::new(-500.0, -500.0, 5000.0, 5000.0));
+        assert_eq!(x_range, 0..=1);
+        assert_eq!(y_range, 0..=1);
+    }
+
+    #[test]
+    fn test_load_tiled_reports_dims_and_grid() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("huge.png");
+        write_test_image(&source_path, 100, 60);
+
+        let cache = ImageCache::default();
+        let tiled = cache.load_tiled(&source_path).unwrap();
+
+        assert_eq!(tiled.image_dims, (100, 60));
+        assert_eq!(tiled.tile_grid(), (1, 1)); // 默认瓦片边长 512，100x60 只需一块
+
+        let small_tile_cache = ImageCache::new(ImageCacheConfig {
+            tile_size: 32,
+            ..Default::default()
+        });
+        let tiled_small = small_tile_cache.load_tiled(&source_path).unwrap();
+        assert_eq!(tiled_small.tile_grid(), (4, 2));
+    }
+
+    #[test]
+    fn test_load_tiles_in_rect_decodes_only_intersecting_tiles() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("huge.png");
+        write_test_image(&source_path, 128, 128);
+
+        let cache = ImageCache::new(ImageCacheConfig {
+            tile_size: 32,
+            ..Default::default()
+        });
+        let tiled = cache.load_tiled(&source_path).unwrap();
+
+        // 请求的矩形只与左上角的一块瓦片相交
+        let tiles = cache
+            .load_tiles_in_rect(&tiled, Rect::new(0.0, 0.0, 10.0, 10.0))
+            .unwrap();
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].0, (0, 0));
+        assert_eq!(tiles[0].1.width(), 32);
+        assert_eq!(tiles[0].1.height(), 32);
+
+        // 整张图被视为 4x4 个瓦片，只有这一块应当进入缓存
+        let (count, ..) = cache.cache_stats();
+        assert_eq!(count, 1);
+
+        // 再次请求同一矩形应当命中缓存，而不是重新裁剪
+        let tiles_again = cache
+            .load_tiles_in_rect(&tiled, Rect::new(0.0, 0.0, 10.0, 10.0))
+            .unwrap();
+        assert!(Arc::ptr_eq(&tiles[0].1, &tiles_again[0].1));
+    }
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgba8(image::RgbaImage::new(width, height));
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_load_from_bytes_dedupes_by_content_hash() {
+        let cache = ImageCache::default();
+        let bytes = png_bytes(6, 6);
+
+        let first = cache.load_from_bytes(&bytes).unwrap();
+        let second = cache.load_from_bytes(&bytes).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let (count, ..) = cache.cache_stats();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_load_from_bytes_same_content_dedupes_with_file_load() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        let bytes = png_bytes(6, 6);
+        fs::write(&source_path, &bytes).unwrap();
+
+        let cache = ImageCache::default();
+        let from_file = cache.load_image(&source_path).unwrap();
+        let from_bytes = cache.load_from_bytes(&bytes).unwrap();
+
+        // 文件内容和内存字节内容相同，应当是同一份缓存条目
+        assert!(Arc::ptr_eq(&from_file, &from_bytes));
+        let (count, ..) = cache.cache_stats();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_load_from_data_uri_decodes_base64_payload() {
+        let cache = ImageCache::default();
+        let bytes = png_bytes(4, 4);
+        let data_uri = format!("data:image/png;base64,{}", base64::encode(&bytes));
+
+        let image = cache.load_from_data_uri(&data_uri).unwrap();
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.height(), 4);
+    }
+
+    #[test]
+    fn test_load_from_data_uri_rejects_malformed_input() {
+        let cache = ImageCache::default();
+        assert!(cache.load_from_data_uri("not a data uri").is_err());
+        assert!(cache.load_from_data_uri("data:image/png,no-comma-base64-marker").is_err());
+    }
+
+    #[test]
+    fn test_load_any_dispatches_by_src_shape() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        let bytes = png_bytes(5, 5);
+        fs::write(&source_path, &bytes).unwrap();
+
+        let cache = ImageCache::default();
+
+        let from_path = cache.load_any(&source_path.to_string_lossy()).unwrap();
+        assert_eq!(from_path.width(), 5);
+
+        let data_uri = format!("data:image/png;base64,{}", base64::encode(&bytes));
+        let from_data_uri = cache.load_any(&data_uri).unwrap();
+
+        // 路径加载和 data URI 加载的是同一份文件内容，应当复用同一个缓存条目
+        assert!(Arc::ptr_eq(&from_path, &from_data_uri));
+    }
+
+    #[test]
+    #[cfg(not(feature = "http-images"))]
+    fn test_load_from_url_without_feature_returns_error() {
+        let cache = ImageCache::default();
+        let err = cache.load_from_url("https://example.com/image.png").unwrap_err();
+        assert!(err.to_string().contains("http-images"));
+    }
+
+    #[test]
+    fn test_memory_report_tracks_hits_and_misses() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        write_test_image(&source_path, 4, 4);
+
+        let cache = ImageCache::default();
+        cache.load_image(&source_path).unwrap(); // miss：首次解码
+        cache.load_image(&source_path).unwrap(); // hit：命中内存缓存
+        cache.load_image(&source_path).unwrap(); // hit
+
+        let report = cache.memory_report();
+        assert_eq!(report.miss_count, 1);
+        assert_eq!(report.hit_count, 2);
+        assert!((report.hit_ratio() - 2.0 / 3.0).abs() < f32::EPSILON);
+        assert_eq!(report.entry_count, 1);
+        assert_eq!(*report.resident_bytes_by_format.get("Rgba8").unwrap(), report.resident_bytes);
+    }
+
+    #[test]
+    fn test_memory_report_counts_evictions() {
+        let dir = tempdir().unwrap();
+        let cache = ImageCache::new(ImageCacheConfig {
+            max_entries: 2,
+            enable_lru: true,
+            ..Default::default()
+        });
+
+        for i in 0..5 {
+            let path = dir.path().join(format!("img{}.png", i));
+            write_test_image(&path, 4, 4);
+            cache.load_image(&path).unwrap();
+        }
+
+        let report = cache.memory_report();
+        assert!(report.eviction_count > 0, "超过 max_entries 应当触发过淘汰");
+    }
+
+    #[test]
+    fn test_memory_report_reports_disk_cache_bytes() {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().join("source.png");
+        write_test_image(&source_path, 20, 20);
+
+        let disk_cache_dir = dir.path().join("cache");
+        let cache = ImageCache::new(ImageCacheConfig {
+            disk_cache_dir: Some(disk_cache_dir),
+            ..Default::default()
+        });
+
+        assert_eq!(cache.memory_report().disk_cache_bytes, 0);
+
+        cache
+            .load_scaled(&source_path, Size::new(10.0, 10.0), FilterType::Triangle)
+            .unwrap();
+
+        assert!(cache.memory_report().disk_cache_bytes > 0);
+    }
 }
\ No newline at end of file
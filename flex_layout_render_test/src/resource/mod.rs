@@ -4,7 +4,9 @@
 
 pub mod font_manager;
 pub mod image_cache;
+pub mod glyph_cache;
 
 // 重新导出主要类型
 pub use font_manager::*;
-pub use image_cache::*;
\ No newline at end of file
+pub use image_cache::*;
+pub use glyph_cache::*;
\ No newline at end of file
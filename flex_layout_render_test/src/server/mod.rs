@@ -0,0 +1,279 @@
+//! IPC/守护进程模式
+//!
+//! 把 `flex-render` 跑成长驻进程，通过 Unix Domain Socket 接收渲染任务，避免服务端场景下
+//! "每个请求 fork 一次进程、每次都重新解析模板"的开销。协议是逐行 JSON（newline-delimited
+//! JSON）：每个连接按行读取一个 [`RenderRequest`]，写回对应的一行 [`RenderResponse`]，
+//! 同一连接可以反复发送多个请求。
+//!
+//! 内部是一个消息传递的渲染 worker：accept 循环给每个连接起一个线程做逐行读写，真正的渲染
+//! 工作都打包成 [`RenderMsg`] 丢给单独的 worker 线程处理，worker 按路径缓存解析过的模板
+//! （[`CachedTemplate`]），同一模板只解析一次 YAML，不同请求换个 `variables` 就能直接复用。
+
+use crate::error::{FlexRenderError, Result};
+use crate::layout::engine::LayoutResult;
+use crate::layout::node::LayoutNode;
+use crate::parser::yaml_parser::{TemplateConfig, YamlParser};
+use crate::{FlexRenderer, TemplateVariables};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+/// 一次渲染请求期望得到的输出形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// 只要布局结果（每个节点的位置、尺寸），不渲染像素
+    Layout,
+    /// 渲染成 PNG 图片，以 base64 编码放进响应
+    Image,
+}
+
+fn default_output_mode() -> OutputMode {
+    OutputMode::Image
+}
+
+/// 逐行 JSON 协议里的一行请求：模板来源二选一，`template_path` 会按路径缓存解析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderRequest {
+    /// 模板文件路径，命中缓存时跳过 YAML 解析
+    pub template_path: Option<String>,
+    /// 内联的 YAML 模板内容，不参与缓存
+    pub template_inline: Option<String>,
+    #[serde(default)]
+    pub variables: TemplateVariables,
+    #[serde(default = "default_output_mode")]
+    pub output: OutputMode,
+}
+
+/// 逐行 JSON 协议里的一行响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderResponse {
+    pub status: ResponseStatus,
+    /// `output` 为 `Layout` 且渲染成功时填充
+    pub layout: Option<SerializableLayoutResult>,
+    /// `output` 为 `Image` 且渲染成功时填充，PNG 字节的 base64 编码
+    pub image_png_base64: Option<String>,
+    /// 渲染失败时的错误信息
+    pub message: Option<String>,
+}
+
+impl RenderResponse {
+    fn error(message: impl Into<String>) -> Self {
+        Self {
+            status: ResponseStatus::Error,
+            layout: None,
+            image_png_base64: None,
+            message: Some(message.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseStatus {
+    Ok,
+    Error,
+}
+
+/// [`LayoutResult`] 的可序列化投影：只保留位置、尺寸和子节点，丢弃节点内容本身
+/// （节点内容请求方本来就有，没必要在响应里再传一遍）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableLayoutResult {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub children: Vec<SerializableLayoutResult>,
+}
+
+impl SerializableLayoutResult {
+    fn from_layout_result(result: &LayoutResult) -> Self {
+        Self {
+            x: result.layout.location.x,
+            y: result.layout.location.y,
+            width: result.layout.size.width,
+            height: result.layout.size.height,
+            children: result
+                .children
+                .iter()
+                .map(SerializableLayoutResult::from_layout_result)
+                .collect(),
+        }
+    }
+}
+
+/// worker 线程的任务缓存条目：一次解析，反复用不同变量渲染
+struct CachedTemplate {
+    template_config: TemplateConfig,
+    root_node: LayoutNode,
+}
+
+/// 发给渲染 worker 线程的消息
+enum RenderMsg {
+    Job {
+        request: RenderRequest,
+        reply: Sender<RenderResponse>,
+    },
+}
+
+/// 以 Unix Domain Socket 方式运行守护进程；`socket_path` 处已存在的旧 socket 文件会先被删除。
+/// 调用后阻塞在 accept 循环里，直到出错或进程退出
+pub fn serve_unix_socket(socket_path: &str) -> Result<()> {
+    let _ = std::fs::remove_file(socket_path);
+    let listener = UnixListener::bind(socket_path)?;
+
+    let (job_sender, job_receiver) = mpsc::channel::<RenderMsg>();
+    thread::spawn(move || render_worker(job_receiver));
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let job_sender = job_sender.clone();
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, job_sender) {
+                log::warn!("flex-render 守护进程连接处理失败: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// 单个连接的逐行读写循环：每读到一行请求就转发给 worker，再把 worker 的回复写回去
+fn handle_connection(stream: UnixStream, job_sender: Sender<RenderMsg>) -> Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<RenderRequest>(&line) {
+            Ok(request) => dispatch_job(&job_sender, request),
+            Err(e) => RenderResponse::error(format!("请求 JSON 解析失败: {}", e)),
+        };
+
+        let serialized = serde_json::to_string(&response)?;
+        writer.write_all(serialized.as_bytes())?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+/// 把一个请求交给 worker 线程处理，同步等待它回复
+fn dispatch_job(job_sender: &Sender<RenderMsg>, request: RenderRequest) -> RenderResponse {
+    let (reply_tx, reply_rx) = mpsc::channel();
+    if job_sender
+        .send(RenderMsg::Job { request, reply: reply_tx })
+        .is_err()
+    {
+        return RenderResponse::error("渲染 worker 线程已退出");
+    }
+    reply_rx
+        .recv()
+        .unwrap_or_else(|_| RenderResponse::error("渲染 worker 未返回结果"))
+}
+
+/// 渲染 worker：单线程串行处理所有任务，模板解析缓存不需要加锁
+fn render_worker(job_receiver: mpsc::Receiver<RenderMsg>) {
+    let mut cache: HashMap<String, CachedTemplate> = HashMap::new();
+
+    while let Ok(RenderMsg::Job { request, reply }) = job_receiver.recv() {
+        let response = match render_one(&mut cache, &request) {
+            Ok(response) => response,
+            Err(e) => RenderResponse::error(e.to_string()),
+        };
+        let _ = reply.send(response);
+    }
+}
+
+/// 解析（或复用缓存的）模板，应用变量，按请求的 `output` 渲染出结果
+fn render_one(cache: &mut HashMap<String, CachedTemplate>, request: &RenderRequest) -> Result<RenderResponse> {
+    let (template_config, root_node) = match (&request.template_path, &request.template_inline) {
+        (Some(path), _) => {
+            if !cache.contains_key(path) {
+                let content = std::fs::read_to_string(path)?;
+                let mut warnings = Vec::new();
+                let (config, node) = YamlParser::parse_lenient(&content, &mut warnings)?;
+                cache.insert(
+                    path.clone(),
+                    CachedTemplate {
+                        template_config: config,
+                        root_node: node,
+                    },
+                );
+            }
+            let cached = cache.get(path).expect("刚刚插入的缓存条目一定存在");
+            (cached.template_config.clone(), cached.root_node.clone())
+        }
+        (None, Some(inline)) => {
+            let mut warnings = Vec::new();
+            YamlParser::parse_lenient(inline, &mut warnings)?
+        }
+        (None, None) => {
+            return Err(FlexRenderError::render_error(
+                "请求缺少 template_path 或 template_inline",
+            ));
+        }
+    };
+
+    let mut renderer = FlexRenderer::from_parts(template_config, root_node)?;
+    renderer.set_variables(request.variables.clone());
+
+    match request.output {
+        OutputMode::Layout => {
+            let result = renderer.compute_layout_result()?;
+            Ok(RenderResponse {
+                status: ResponseStatus::Ok,
+                layout: Some(SerializableLayoutResult::from_layout_result(&result)),
+                image_png_base64: None,
+                message: None,
+            })
+        }
+        OutputMode::Image => {
+            let image = renderer.render()?;
+            let mut bytes = Vec::new();
+            image.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+            Ok(RenderResponse {
+                status: ResponseStatus::Ok,
+                layout: None,
+                image_png_base64: Some(encode_base64(&bytes)),
+                message: None,
+            })
+        }
+    }
+}
+
+/// 标准 base64（RFC 4648，带 `=` 填充）编码；协议里图片字节走 JSON 字符串，
+/// 这里用一个手写的简单实现
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity((data.len() + 2) / 3 * 4);
+
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        output.push(ALPHABET[(b0 >> 2) as usize] as char);
+        output.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        output.push(if chunk.len() > 1 {
+            ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        output.push(if chunk.len() > 2 {
+            ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    output
+}
@@ -81,6 +81,20 @@ impl Color {
     pub fn blue() -> Self {
         Self::new(0, 0, 255, 255)
     }
+
+    /// 在两个颜色之间按 `t`（会被夹到 `[0, 1]`）做分量线性插值，用于渐变取色
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp_channel = |a: u8, b: u8| -> u8 {
+            (a as f32 + (b as f32 - a as f32) * t).round() as u8
+        };
+        Self::new(
+            lerp_channel(self.r, other.r),
+            lerp_channel(self.g, other.g),
+            lerp_channel(self.b, other.b),
+            lerp_channel(self.a, other.a),
+        )
+    }
 }
 
 /// 2D 点坐标
@@ -329,6 +343,86 @@ pub enum ObjectFit {
     None,
 }
 
+/// 垂直对齐方式，用于在一个边界框内纵向排布文本块
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl Default for VerticalAlign {
+    fn default() -> Self {
+        VerticalAlign::Top
+    }
+}
+
+/// 文本自动缩放模式（对应 `pane` crate 的 resize 行为）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TextResize {
+    /// 使用设定的字号，超出边界框时按当前字号换行并可能溢出
+    None,
+    /// 仅在设定字号放不下时才缩小（不会放大）
+    NoLarger,
+    /// 在 [min, max] 范围内搜索能放得下的最大字号
+    Max,
+}
+
+impl Default for TextResize {
+    fn default() -> Self {
+        TextResize::None
+    }
+}
+
+/// 文本溢出处理方式：一行/一段文字放不下边界框时怎么办
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TextOverflow {
+    /// 不做任何特殊处理，允许超出边界框正常绘制（现有默认行为）
+    Visible,
+    /// 硬性裁掉超出边界框的部分：shaping 路径上覆盖率蒙版字形按像素裁剪，彩色位图字形
+    /// 只按是否与边界框相交整张保留或丢弃；未启用 `shaping` feature 时不做裁剪
+    Clip,
+    /// 放不下时从行尾回退字符，直到加上省略号 `…` 能放进边界框
+    Ellipsis,
+}
+
+impl Default for TextOverflow {
+    fn default() -> Self {
+        TextOverflow::Visible
+    }
+}
+
+/// 文本旋转变换（参考 plotters 的 `FontTransform`），用于竖排的坐标轴标签或侧边文字
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FontTransform {
+    /// 不旋转
+    None,
+    /// 顺时针旋转 90 度
+    Rotate90,
+    /// 旋转 180 度
+    Rotate180,
+    /// 顺时针旋转 270 度（即逆时针 90 度）
+    Rotate270,
+}
+
+impl Default for FontTransform {
+    fn default() -> Self {
+        FontTransform::None
+    }
+}
+
+impl FontTransform {
+    /// 将旋转前、以 `(w, h)` 为尺寸的字形像素坐标 `(x, y)` 映射到旋转后包围盒内的坐标
+    pub fn transform(&self, x: f32, y: f32, w: f32, h: f32) -> (f32, f32) {
+        match self {
+            FontTransform::None => (x, y),
+            FontTransform::Rotate90 => (h - y, x),
+            FontTransform::Rotate180 => (w - x, h - y),
+            FontTransform::Rotate270 => (y, w - x),
+        }
+    }
+}
+
 /// 边框样式
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BorderStyle {
@@ -363,6 +457,18 @@ pub enum Gradient {
     },
 }
 
+impl Gradient {
+    /// 取第一个停止点的颜色；没有任何停止点时退回不透明黑色。
+    /// 给不支持渐变栅格化的输出路径（比如矢量 `DrawBackend`）当作退化近似
+    pub fn first_stop_color(&self) -> Color {
+        let stops = match self {
+            Gradient::Linear { stops, .. } => stops,
+            Gradient::Radial { stops, .. } => stops,
+        };
+        stops.first().map(|s| s.color).unwrap_or(Color::black())
+    }
+}
+
 /// 渐变色停止点
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColorStop {
@@ -372,6 +478,26 @@ pub struct ColorStop {
     pub color: Color,
 }
 
+/// 容器/文本的填充背景：纯色或渐变，`ContainerStyle::background` 用它统一建模两者
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Background {
+    Color(Color),
+    Gradient(Gradient),
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Background::Color(color)
+    }
+}
+
+impl From<Gradient> for Background {
+    fn from(gradient: Gradient) -> Self {
+        Background::Gradient(gradient)
+    }
+}
+
 /// 边距/内边距
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct EdgeInsets {
@@ -526,6 +652,17 @@ mod tests {
         assert_eq!(intersection, Rect::new(5.0, 5.0, 5.0, 5.0));
     }
     
+    #[test]
+    fn test_font_transform_rotate90() {
+        let (x, y) = FontTransform::Rotate90.transform(0.0, 0.0, 10.0, 20.0);
+        assert_eq!((x, y), (20.0, 0.0));
+    }
+
+    #[test]
+    fn test_font_transform_none_is_identity() {
+        assert_eq!(FontTransform::None.transform(3.0, 4.0, 10.0, 20.0), (3.0, 4.0));
+    }
+
     #[test]
     fn test_edge_insets() {
         let insets = EdgeInsets::all(5.0);
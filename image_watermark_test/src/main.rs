@@ -1,3 +1,5 @@
+mod shaping;
+
 use clap::{Arg, Command};
 use image::{DynamicImage, Rgba, RgbaImage};
 use imageproc::drawing::draw_text_mut;
@@ -5,13 +7,20 @@ use rusttype::{Font, Scale};
 use resvg::usvg;
 use tiny_skia::Pixmap;
 
-#[derive(Debug)]
+/// 水印平铺时相邻两块之间的像素间距
+const DEFAULT_TILE_GAP: u32 = 20;
+
+#[derive(Debug, Clone, Copy)]
 pub enum WatermarkPosition {
     TopLeft,
+    TopCenter,
     TopRight,
+    MiddleLeft,
+    Center,
+    MiddleRight,
     BottomLeft,
+    BottomCenter,
     BottomRight,
-    Center,
     Custom(u32, u32),
 }
 
@@ -25,27 +34,51 @@ impl WatermarkPosition {
     ) -> (u32, u32) {
         match self {
             WatermarkPosition::TopLeft => (10, 10),
+            WatermarkPosition::TopCenter => ((img_width.saturating_sub(text_width)) / 2, 10),
             WatermarkPosition::TopRight => (img_width.saturating_sub(text_width + 10), 10),
+            WatermarkPosition::MiddleLeft => (10, (img_height.saturating_sub(text_height)) / 2),
+            WatermarkPosition::Center => (
+                (img_width.saturating_sub(text_width)) / 2,
+                (img_height.saturating_sub(text_height)) / 2,
+            ),
+            WatermarkPosition::MiddleRight => (
+                img_width.saturating_sub(text_width + 10),
+                (img_height.saturating_sub(text_height)) / 2,
+            ),
             WatermarkPosition::BottomLeft => (10, img_height.saturating_sub(text_height + 10)),
+            WatermarkPosition::BottomCenter => (
+                (img_width.saturating_sub(text_width)) / 2,
+                img_height.saturating_sub(text_height + 10),
+            ),
             WatermarkPosition::BottomRight => (
                 img_width.saturating_sub(text_width + 10),
                 img_height.saturating_sub(text_height + 10),
             ),
-            WatermarkPosition::Center => (
-                (img_width.saturating_sub(text_width)) / 2,
-                (img_height.saturating_sub(text_height)) / 2,
-            ),
             WatermarkPosition::Custom(x, y) => (*x, *y),
         }
     }
 }
 
+/// `feDropShadow` 风格的投影滤镜配置
+#[derive(Debug, Clone, Copy)]
+pub struct DropShadowConfig {
+    pub dx: f32,
+    pub dy: f32,
+    pub blur: f32,
+    pub color: Rgba<u8>,
+}
+
 pub struct WatermarkConfig {
     pub text: String,
     pub position: WatermarkPosition,
     pub font_size: f32,
     pub color: Rgba<u8>,
     pub opacity: f32,
+    pub rotation: f32,
+    pub tiling: bool,
+    pub offset: (i32, i32),
+    pub blur: f32,
+    pub shadow: Option<DropShadowConfig>,
 }
 
 pub struct SvgWatermarkConfig {
@@ -54,6 +87,11 @@ pub struct SvgWatermarkConfig {
     pub width: u32,
     pub height: u32,
     pub opacity: f32,
+    pub rotation: f32,
+    pub tiling: bool,
+    pub offset: (i32, i32),
+    pub blur: f32,
+    pub shadow: Option<DropShadowConfig>,
 }
 
 impl Default for SvgWatermarkConfig {
@@ -64,6 +102,11 @@ impl Default for SvgWatermarkConfig {
             width: 100,
             height: 100,
             opacity: 0.7,
+            rotation: 0.0,
+            tiling: false,
+            offset: (0, 0),
+            blur: 0.0,
+            shadow: None,
         }
     }
 }
@@ -76,7 +119,292 @@ impl Default for WatermarkConfig {
             font_size: 48.0,
             color: Rgba([255, 255, 255, 255]), // 白色
             opacity: 0.7,
+            rotation: 0.0,
+            tiling: false,
+            offset: (0, 0),
+            blur: 0.0,
+            shadow: None,
+        }
+    }
+}
+
+/// 按标准差 `sigma` 算出等效的 box blur 半径：`d = floor(sigma * 3 * sqrt(2*pi)/4 + 0.5)`，
+/// 三次连续的 box blur（水平+垂直各一遍）就能很好地近似一次真正的高斯模糊
+fn box_blur_radius(sigma: f32) -> u32 {
+    if sigma <= 0.0 {
+        return 0;
+    }
+    ((sigma * 3.0 * (2.0 * std::f32::consts::PI).sqrt() / 4.0) + 0.5)
+        .floor()
+        .max(0.0) as u32
+}
+
+/// 把 RGBA 像素的颜色通道按自身 alpha 预乘，避免模糊半透明边缘时颜色从完全透明的
+/// 相邻像素"借"进黑色，产生暗边
+fn premultiply_alpha(img: &mut RgbaImage) {
+    for p in img.pixels_mut() {
+        let a = p[3] as f32 / 255.0;
+        p[0] = (p[0] as f32 * a).round() as u8;
+        p[1] = (p[1] as f32 * a).round() as u8;
+        p[2] = (p[2] as f32 * a).round() as u8;
+    }
+}
+
+/// `premultiply_alpha` 的逆操作
+fn unpremultiply_alpha(img: &mut RgbaImage) {
+    for p in img.pixels_mut() {
+        let a = p[3] as f32 / 255.0;
+        if a > 0.0 {
+            p[0] = ((p[0] as f32 / a).round() as u32).min(255) as u8;
+            p[1] = ((p[1] as f32 / a).round() as u32).min(255) as u8;
+            p[2] = ((p[2] as f32 / a).round() as u32).min(255) as u8;
+        }
+    }
+}
+
+/// 沿水平方向做一遍半径为 `radius` 的 box blur（窗口内像素求平均），边缘按最近像素延伸
+fn box_blur_horizontal(img: &mut RgbaImage, radius: u32) {
+    let (width, height) = img.dimensions();
+    if radius == 0 || width == 0 {
+        return;
+    }
+    let radius = radius as i64;
+    let src = img.clone();
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 4];
+            let mut count = 0u32;
+            for dx in -radius..=radius {
+                let sx = (x as i64 + dx).clamp(0, width as i64 - 1) as u32;
+                let p = src.get_pixel(sx, y);
+                for c in 0..4 {
+                    sum[c] += p[c] as u32;
+                }
+                count += 1;
+            }
+            let mut out = [0u8; 4];
+            for c in 0..4 {
+                out[c] = (sum[c] / count) as u8;
+            }
+            img.put_pixel(x, y, Rgba(out));
+        }
+    }
+}
+
+/// 沿竖直方向做一遍半径为 `radius` 的 box blur，用法和 [`box_blur_horizontal`] 对称
+fn box_blur_vertical(img: &mut RgbaImage, radius: u32) {
+    let (width, height) = img.dimensions();
+    if radius == 0 || height == 0 {
+        return;
+    }
+    let radius = radius as i64;
+    let src = img.clone();
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 4];
+            let mut count = 0u32;
+            for dy in -radius..=radius {
+                let sy = (y as i64 + dy).clamp(0, height as i64 - 1) as u32;
+                let p = src.get_pixel(x, sy);
+                for c in 0..4 {
+                    sum[c] += p[c] as u32;
+                }
+                count += 1;
+            }
+            let mut out = [0u8; 4];
+            for c in 0..4 {
+                out[c] = (sum[c] / count) as u8;
+            }
+            img.put_pixel(x, y, Rgba(out));
+        }
+    }
+}
+
+/// `feGaussianBlur` 的三遍 box blur 近似实现：先预乘 alpha，水平+垂直各做三遍 box blur，
+/// 最后反预乘
+fn gaussian_blur_rgba(img: &mut RgbaImage, sigma: f32) {
+    let radius = box_blur_radius(sigma);
+    if radius == 0 {
+        return;
+    }
+
+    premultiply_alpha(img);
+    for _ in 0..3 {
+        box_blur_horizontal(img, radius);
+        box_blur_vertical(img, radius);
+    }
+    unpremultiply_alpha(img);
+}
+
+/// 由水印自身的 alpha 通道生成一层 `feDropShadow`：取 alpha 做成纯色遮罩、按 `(dx, dy)`
+/// 偏移、模糊，再乘上阴影色自身的透明度，画布尺寸和 `source` 相同，偏移越界的部分被裁掉
+fn drop_shadow_layer(source: &RgbaImage, shadow: &DropShadowConfig) -> RgbaImage {
+    let (width, height) = source.dimensions();
+
+    let mut mask = RgbaImage::new(width, height);
+    for (x, y, pixel) in source.enumerate_pixels() {
+        mask.put_pixel(x, y, Rgba([shadow.color.0[0], shadow.color.0[1], shadow.color.0[2], pixel[3]]));
+    }
+
+    gaussian_blur_rgba(&mut mask, shadow.blur);
+
+    let mut shifted = RgbaImage::new(width, height);
+    let (dx, dy) = (shadow.dx.round() as i64, shadow.dy.round() as i64);
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            let (sx, sy) = (x - dx, y - dy);
+            if sx >= 0 && sy >= 0 && (sx as u32) < width && (sy as u32) < height {
+                shifted.put_pixel(x as u32, y as u32, *mask.get_pixel(sx as u32, sy as u32));
+            }
+        }
+    }
+
+    let shadow_alpha = shadow.color.0[3] as f32 / 255.0;
+    for p in shifted.pixels_mut() {
+        p[3] = (p[3] as f32 * shadow_alpha) as u8;
+    }
+
+    shifted
+}
+
+/// 对一个已经渲染好的水印缓冲区依次应用模糊和投影滤镜：先把阴影画在暂存画布上，
+/// 再把（可能模糊过的）水印本体合成在阴影之上
+fn apply_filters(mut buf: RgbaImage, blur: f32, shadow: Option<&DropShadowConfig>) -> RgbaImage {
+    let canvas = shadow.map(|shadow_cfg| drop_shadow_layer(&buf, shadow_cfg));
+
+    if blur > 0.0 {
+        gaussian_blur_rgba(&mut buf, blur);
+    }
+
+    match canvas {
+        Some(mut canvas) => {
+            composite_rgba(&mut canvas, &buf, 0, 0);
+            canvas
         }
+        None => buf,
+    }
+}
+
+/// 以双线性采样对 RGBA 图像做仿射旋转，目标画布按旋转后的包围盒扩张，避免四角被裁掉
+fn rotate_rgba_image(src: &RgbaImage, angle_degrees: f32) -> RgbaImage {
+    if angle_degrees == 0.0 {
+        return src.clone();
+    }
+
+    let angle = angle_degrees.to_radians();
+    let (cos_a, sin_a) = (angle.cos(), angle.sin());
+    let (src_w, src_h) = (src.width() as f32, src.height() as f32);
+
+    let dst_w = (src_w * cos_a.abs() + src_h * sin_a.abs()).ceil().max(1.0) as u32;
+    let dst_h = (src_w * sin_a.abs() + src_h * cos_a.abs()).ceil().max(1.0) as u32;
+
+    let mut dst = RgbaImage::new(dst_w, dst_h);
+    let (cx_src, cy_src) = (src_w / 2.0, src_h / 2.0);
+    let (cx_dst, cy_dst) = (dst_w as f32 / 2.0, dst_h as f32 / 2.0);
+
+    for dy in 0..dst_h {
+        for dx in 0..dst_w {
+            // 从目标像素反向旋转回源图坐标系做采样
+            let dx_c = dx as f32 - cx_dst + 0.5;
+            let dy_c = dy as f32 - cy_dst + 0.5;
+            let sx = dx_c * cos_a + dy_c * sin_a + cx_src;
+            let sy = -dx_c * sin_a + dy_c * cos_a + cy_src;
+
+            if let Some(pixel) = sample_bilinear(src, sx, sy) {
+                dst.put_pixel(dx, dy, pixel);
+            }
+        }
+    }
+
+    dst
+}
+
+/// 对 RGBA 图像做双线性采样，坐标落在图像外时返回 `None`（即透明）
+fn sample_bilinear(img: &RgbaImage, x: f32, y: f32) -> Option<Rgba<u8>> {
+    let (width, height) = (img.width(), img.height());
+    if x < 0.0 || y < 0.0 {
+        return None;
+    }
+
+    let x0 = x.floor() as i64;
+    let y0 = y.floor() as i64;
+    if x0 < 0 || y0 < 0 || x0 as u32 >= width || y0 as u32 >= height {
+        return None;
+    }
+
+    let x1 = ((x0 + 1) as u32).min(width - 1);
+    let y1 = ((y0 + 1) as u32).min(height - 1);
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let p00 = img.get_pixel(x0 as u32, y0 as u32);
+    let p10 = img.get_pixel(x1, y0 as u32);
+    let p01 = img.get_pixel(x0 as u32, y1);
+    let p11 = img.get_pixel(x1, y1);
+
+    let mut out = [0u8; 4];
+    for c in 0..4 {
+        let top = p00[c] as f32 * (1.0 - fx) + p10[c] as f32 * fx;
+        let bottom = p01[c] as f32 * (1.0 - fx) + p11[c] as f32 * fx;
+        out[c] = (top * (1.0 - fy) + bottom * fy).round() as u8;
+    }
+
+    Some(Rgba(out))
+}
+
+/// 把 `overlay` 按 alpha 混合叠加到 `base` 上的 `(x, y)` 处，超出 `base` 边界的部分直接裁掉
+fn composite_rgba(base: &mut RgbaImage, overlay: &RgbaImage, x: i32, y: i32) {
+    let (base_w, base_h) = (base.width() as i32, base.height() as i32);
+
+    for oy in 0..overlay.height() as i32 {
+        let ty = y + oy;
+        if ty < 0 || ty >= base_h {
+            continue;
+        }
+        for ox in 0..overlay.width() as i32 {
+            let tx = x + ox;
+            if tx < 0 || tx >= base_w {
+                continue;
+            }
+
+            let overlay_pixel = overlay.get_pixel(ox as u32, oy as u32);
+            let alpha = overlay_pixel[3] as f32 / 255.0;
+            if alpha <= 0.0 {
+                continue;
+            }
+            let inv_alpha = 1.0 - alpha;
+            let base_pixel = base.get_pixel(tx as u32, ty as u32);
+
+            let blended = Rgba([
+                (overlay_pixel[0] as f32 * alpha + base_pixel[0] as f32 * inv_alpha) as u8,
+                (overlay_pixel[1] as f32 * alpha + base_pixel[1] as f32 * inv_alpha) as u8,
+                (overlay_pixel[2] as f32 * alpha + base_pixel[2] as f32 * inv_alpha) as u8,
+                255, // 保持原图的不透明度
+            ]);
+
+            base.put_pixel(tx as u32, ty as u32, blended);
+        }
+    }
+}
+
+/// 把 `tile` 按 `tile_w + gap` / `tile_h + gap` 的步长铺满整张 `base`，越界的格子直接被
+/// `composite_rgba` 裁掉
+fn tile_across(base: &mut RgbaImage, tile: &RgbaImage, gap: u32) {
+    let step_x = tile.width() + gap;
+    let step_y = tile.height() + gap;
+    if step_x == 0 || step_y == 0 {
+        return;
+    }
+
+    let mut y = 0i32;
+    while y < base.height() as i32 {
+        let mut x = 0i32;
+        while x < base.width() as i32 {
+            composite_rgba(base, tile, x, y);
+            x += step_x as i32;
+        }
+        y += step_y as i32;
     }
 }
 
@@ -91,38 +419,42 @@ pub fn add_text_watermark(
 
     let scale = Scale::uniform(config.font_size);
 
-    // 估算文字尺寸
+    // 估算文字尺寸：宽度交给 TextShaper（`harfbuzz` feature 打开时走真正的 shaping，
+    // 对 CJK/连字/RTL 更准），竖直 metrics 与字体整形无关，始终用 font 自身的 v_metrics
     let v_metrics = font.v_metrics(scale);
-    let text_width = font
-        .layout(&config.text, scale, rusttype::point(0.0, 0.0))
-        .map(|g| g.position().x + g.unpositioned().h_metrics().advance_width)
-        .last()
-        .unwrap_or(0.0) as u32;
+    let shaper = crate::shaping::default_shaper();
+    let text_width = shaper.measure_width(font_data as &[u8], config.font_size, &config.text) as u32;
     let text_height = (v_metrics.ascent - v_metrics.descent) as u32;
 
-    // 计算水印位置
-    let (x, y) =
-        config
-            .position
-            .calculate_position(img.width(), img.height(), text_width, text_height);
-
     // 应用透明度
     let mut color = config.color;
     color.0[3] = (color.0[3] as f32 * config.opacity) as u8;
 
+    // 先把文字画到自己独立的透明缓冲区里，再整体旋转/平铺/合成
+    let mut text_buf = RgbaImage::new(text_width.max(1), text_height.max(1));
+    draw_text_mut(&mut text_buf, color, 0, 0, scale, &font, &config.text);
+    let watermark_buf = rotate_rgba_image(&text_buf, config.rotation);
+    let watermark_buf = apply_filters(watermark_buf, config.blur, config.shadow.as_ref());
+
     // 转换为RGBA格式
     let mut rgba_img = img.to_rgba8();
 
-    // 绘制文字
-    draw_text_mut(
-        &mut rgba_img,
-        color,
-        x as i32,
-        y as i32,
-        scale,
-        &font,
-        &config.text,
-    );
+    if config.tiling {
+        tile_across(&mut rgba_img, &watermark_buf, DEFAULT_TILE_GAP);
+    } else {
+        let (x, y) = config.position.calculate_position(
+            img.width(),
+            img.height(),
+            watermark_buf.width(),
+            watermark_buf.height(),
+        );
+        composite_rgba(
+            &mut rgba_img,
+            &watermark_buf,
+            x as i32 + config.offset.0,
+            y as i32 + config.offset.1,
+        );
+    }
 
     // 更新原图像
     *img = DynamicImage::ImageRgba8(rgba_img);
@@ -202,38 +534,28 @@ pub fn add_svg_watermark(
         }
     }
 
-    // 计算水印在原图上的位置
-    let (x, y) =
-        config
-            .position
-            .calculate_position(img.width(), img.height(), config.width, config.height);
+    // 整体旋转（独立缓冲区内完成，不影响原图）
+    let watermark_img = rotate_rgba_image(&watermark_img, config.rotation);
+    let watermark_img = apply_filters(watermark_img, config.blur, config.shadow.as_ref());
 
     // 将水印叠加到原图上
     let mut rgba_img = img.to_rgba8();
 
-    for dy in 0..config.height {
-        for dx in 0..config.width {
-            let target_x = x + dx;
-            let target_y = y + dy;
-
-            if target_x < img.width() && target_y < img.height() {
-                let watermark_pixel = watermark_img.get_pixel(dx, dy);
-                let base_pixel = rgba_img.get_pixel(target_x, target_y);
-
-                // Alpha 混合
-                let alpha = watermark_pixel[3] as f32 / 255.0;
-                let inv_alpha = 1.0 - alpha;
-
-                let blended_pixel = Rgba([
-                    (watermark_pixel[0] as f32 * alpha + base_pixel[0] as f32 * inv_alpha) as u8,
-                    (watermark_pixel[1] as f32 * alpha + base_pixel[1] as f32 * inv_alpha) as u8,
-                    (watermark_pixel[2] as f32 * alpha + base_pixel[2] as f32 * inv_alpha) as u8,
-                    255, // 保持原图的不透明度
-                ]);
-
-                rgba_img.put_pixel(target_x, target_y, blended_pixel);
-            }
-        }
+    if config.tiling {
+        tile_across(&mut rgba_img, &watermark_img, DEFAULT_TILE_GAP);
+    } else {
+        let (x, y) = config.position.calculate_position(
+            img.width(),
+            img.height(),
+            watermark_img.width(),
+            watermark_img.height(),
+        );
+        composite_rgba(
+            &mut rgba_img,
+            &watermark_img,
+            x as i32 + config.offset.0,
+            y as i32 + config.offset.1,
+        );
     }
 
     // 更新原图像
@@ -290,7 +612,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .long("position")
                 .value_name("POSITION")
                 .help(
-                    "水印位置: top-left, top-right, bottom-left, bottom-right, center, 或 x,y 坐标",
+                    "水印位置: top-left, top-center, top-right, middle-left, center, middle-right, \
+                     bottom-left, bottom-center, bottom-right, 或 x,y 坐标",
                 )
                 .default_value("bottom-right"),
         )
@@ -332,6 +655,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .help("透明度 (0.0-1.0)")
                 .default_value("1.0"),
         )
+        .arg(
+            Arg::new("rotate")
+                .long("rotate")
+                .value_name("DEGREES")
+                .help("水印旋转角度（度）")
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("tile")
+                .long("tile")
+                .help("平铺水印铺满整张图片")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("offset")
+                .long("offset")
+                .value_name("DX,DY")
+                .help("在计算出的位置基础上再偏移 (dx,dy)")
+                .default_value("0,0"),
+        )
+        .arg(
+            Arg::new("blur")
+                .long("blur")
+                .value_name("SIGMA")
+                .help("对水印本体做高斯模糊，SIGMA 为标准差（像素）")
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("shadow")
+                .long("shadow")
+                .value_name("DX,DY,BLUR,R,G,B[,A]")
+                .help("给水印加投影，格式为偏移/模糊标准差/阴影颜色"),
+        )
         .get_matches();
 
     let input_path = matches.get_one::<String>("input").unwrap();
@@ -345,14 +701,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let svg_height: u32 = matches.get_one::<String>("height").unwrap().parse()?;
     let color_str = matches.get_one::<String>("color").unwrap();
     let opacity: f32 = matches.get_one::<String>("opacity").unwrap().parse()?;
+    let rotation: f32 = matches.get_one::<String>("rotate").unwrap().parse()?;
+    let tiling = matches.get_flag("tile");
+    let offset_str = matches.get_one::<String>("offset").unwrap();
+    let blur: f32 = matches.get_one::<String>("blur").unwrap().parse()?;
+    let shadow_str = matches.get_one::<String>("shadow");
 
     // 解析位置
     let position = match position_str.as_str() {
         "top-left" => WatermarkPosition::TopLeft,
+        "top-center" => WatermarkPosition::TopCenter,
         "top-right" => WatermarkPosition::TopRight,
+        "middle-left" => WatermarkPosition::MiddleLeft,
+        "center" => WatermarkPosition::Center,
+        "middle-right" => WatermarkPosition::MiddleRight,
         "bottom-left" => WatermarkPosition::BottomLeft,
+        "bottom-center" => WatermarkPosition::BottomCenter,
         "bottom-right" => WatermarkPosition::BottomRight,
-        "center" => WatermarkPosition::Center,
         custom => {
             let coords: Vec<&str> = custom.split(',').collect();
             if coords.len() == 2 {
@@ -384,6 +749,40 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         _ => return Err("无效的颜色格式，请使用 r,g,b 或 r,g,b,a 格式".into()),
     };
 
+    // 解析偏移
+    let offset_parts: Vec<&str> = offset_str.split(',').collect();
+    let offset = if offset_parts.len() == 2 {
+        let dx: i32 = offset_parts[0].parse()?;
+        let dy: i32 = offset_parts[1].parse()?;
+        (dx, dy)
+    } else {
+        return Err("无效的偏移格式，请使用 dx,dy 格式".into());
+    };
+
+    // 解析投影配置
+    let shadow = match shadow_str {
+        Some(s) => {
+            let parts: Vec<&str> = s.split(',').collect();
+            if parts.len() != 6 && parts.len() != 7 {
+                return Err("无效的投影格式，请使用 dx,dy,blur,r,g,b[,a] 格式".into());
+            }
+            let dx: f32 = parts[0].parse()?;
+            let dy: f32 = parts[1].parse()?;
+            let shadow_blur: f32 = parts[2].parse()?;
+            let r: u8 = parts[3].parse()?;
+            let g: u8 = parts[4].parse()?;
+            let b: u8 = parts[5].parse()?;
+            let a: u8 = if parts.len() == 7 { parts[6].parse()? } else { 255 };
+            Some(DropShadowConfig {
+                dx,
+                dy,
+                blur: shadow_blur,
+                color: Rgba([r, g, b, a]),
+            })
+        }
+        None => None,
+    };
+
     // 加载图片
     let mut img = image::open(input_path)?;
 
@@ -399,6 +798,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 font_size,
                 color,
                 opacity,
+                rotation,
+                tiling,
+                offset,
+                blur,
+                shadow,
             };
 
             // 添加文字水印
@@ -411,6 +815,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("  字体大小: {}", config.font_size);
             println!("  颜色: {:?}", config.color);
             println!("  透明度: {}", config.opacity);
+            println!("  旋转角度: {}", config.rotation);
+            println!("  平铺: {}", config.tiling);
+            println!("  偏移: {:?}", config.offset);
+            println!("  模糊: {}", config.blur);
+            println!("  投影: {:?}", config.shadow);
         }
         "svg" => {
             // 创建SVG水印配置
@@ -420,6 +829,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 width: svg_width,
                 height: svg_height,
                 opacity,
+                rotation,
+                tiling,
+                offset,
+                blur,
+                shadow,
             };
 
             // 添加SVG水印
@@ -431,6 +845,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("  位置: {:?}", svg_config.position);
             println!("  尺寸: {}x{}", svg_config.width, svg_config.height);
             println!("  透明度: {}", svg_config.opacity);
+            println!("  旋转角度: {}", svg_config.rotation);
+            println!("  平铺: {}", svg_config.tiling);
+            println!("  偏移: {:?}", svg_config.offset);
+            println!("  模糊: {}", svg_config.blur);
+            println!("  投影: {:?}", svg_config.shadow);
         }
         _ => {
             return Err("无效的水印类型，请使用 'text' 或 'svg'".into());
@@ -0,0 +1,89 @@
+//! 文字整形（shaping）抽象
+//!
+//! `add_text_watermark` 原来直接用 rusttype 的 `Font::layout` 朴素地逐字形累加 advance 来
+//! 估算文字宽度，这对 CJK 的宽度估算、连字和 RTL/阿拉伯语这类需要整形重排的文字都不准确。
+//! `TextShaper` 把"测量一段文字的前进宽度"这件事抽成一个接口，`harfbuzz` feature 打开时
+//! 走真正的 HarfBuzz shaping，否则退回当前这条朴素路径，两者对调用方（`add_text_watermark`）
+//! 透明。
+
+use rusttype::{Font, Scale};
+
+/// 文字整形器：给定字体原始字节、像素字号和文字内容，返回整形后的总前进宽度（像素）
+pub trait TextShaper {
+    fn measure_width(&self, font_bytes: &[u8], pixel_size: f32, text: &str) -> f32;
+}
+
+/// 默认整形器：rusttype 朴素逐字形布局（`harfbuzz` feature 关闭时使用）
+pub struct NaiveShaper;
+
+impl TextShaper for NaiveShaper {
+    fn measure_width(&self, font_bytes: &[u8], pixel_size: f32, text: &str) -> f32 {
+        let font = match Font::try_from_bytes(font_bytes) {
+            Some(font) => font,
+            None => return 0.0,
+        };
+        let scale = Scale::uniform(pixel_size);
+
+        font.layout(text, scale, rusttype::point(0.0, 0.0))
+            .map(|g| g.position().x + g.unpositioned().h_metrics().advance_width)
+            .last()
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(feature = "harfbuzz")]
+mod harfbuzz_shaper {
+    use super::TextShaper;
+
+    /// HarfBuzz 整形器：得到的 advance 已经包含 kerning/连字，CJK 和 RTL 文字也能
+    /// 测得准确的宽度
+    pub struct HarfBuzzShaper;
+
+    impl TextShaper for HarfBuzzShaper {
+        fn measure_width(&self, font_bytes: &[u8], pixel_size: f32, text: &str) -> f32 {
+            let face = harfbuzz_rs::Face::from_bytes(font_bytes, 0);
+            let hb_font = harfbuzz_rs::Font::new(face);
+            let units_per_em = hb_font.face().upem() as f32;
+            if units_per_em <= 0.0 {
+                return 0.0;
+            }
+
+            let buffer = harfbuzz_rs::UnicodeBuffer::new().add_str(text);
+            let output = harfbuzz_rs::shape(&hb_font, buffer, &[]);
+            let scale = pixel_size / units_per_em;
+
+            output
+                .get_glyph_positions()
+                .iter()
+                .map(|pos| pos.x_advance as f32 * scale)
+                .sum()
+        }
+    }
+}
+
+#[cfg(feature = "harfbuzz")]
+pub use harfbuzz_shaper::HarfBuzzShaper;
+
+/// 根据 `harfbuzz` feature 是否开启选择默认整形器
+pub fn default_shaper() -> Box<dyn TextShaper> {
+    #[cfg(feature = "harfbuzz")]
+    {
+        Box::new(HarfBuzzShaper)
+    }
+    #[cfg(not(feature = "harfbuzz"))]
+    {
+        Box::new(NaiveShaper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naive_shaper_empty_text_has_zero_width() {
+        let font_data = include_bytes!("../assets/DejaVuSans.ttf");
+        let shaper = NaiveShaper;
+        assert_eq!(shaper.measure_width(font_data as &[u8], 16.0, ""), 0.0);
+    }
+}